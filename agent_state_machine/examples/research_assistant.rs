@@ -46,7 +46,11 @@ struct ArxivSearch {
 impl ArxivSearch {
     fn new() -> Self {
         Self {
-            client: reqwest::Client::new(),
+            // Don't let a hung arXiv request stall the agent
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(10))
+                .build()
+                .expect("reqwest client should build"),
         }
     }
 
@@ -0,0 +1,52 @@
+mod calculator_tool;
+
+use crate::calculator_tool::CalculatorTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::anthropic::{self, ClientBuilder};
+use std::io::{self, Write};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // The Anthropic client has no `from_env`, so read the key ourselves
+    let api_key = std::env::var("ANTHROPIC_API_KEY").map_err(|_| "ANTHROPIC_API_KEY not set")?;
+    let anthropic_client = ClientBuilder::new(&api_key).build();
+
+    // Build the agent with the CalculatorTool. Claude requires `max_tokens`
+    // to be set on every request.
+    let agent = anthropic_client
+        .agent(anthropic::CLAUDE_3_5_SONNET)
+        .preamble("You are a helpful assistant. Use the calculate tool for any arithmetic instead of working it out yourself.")
+        .max_tokens(1024)
+        .tool(CalculatorTool)
+        .build();
+
+    println!("Ask Claude anything (type 'exit' to quit)");
+
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            break;
+        }
+
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        if input.eq_ignore_ascii_case("exit") {
+            break;
+        }
+
+        match agent.prompt(input).await {
+            Ok(response) => println!("{}\n", response),
+            Err(e) => eprintln!("Error: {}\n", e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,196 @@
+// azure.rs
+
+use rig::agent::AgentBuilder;
+use rig::completion::{self, CompletionError, CompletionRequest};
+use rig::json_utils;
+use rig::providers::openai;
+use serde_json::{json, Value};
+
+/// API version used when `AZURE_OPENAI_API_VERSION` is not set
+pub const DEFAULT_API_VERSION: &str = "2024-06-01";
+
+/// A client for an Azure OpenAI resource.
+///
+/// Rig's `openai::Client` sends the key as a bearer token to
+/// `{base_url}/v1/chat/completions`, while Azure expects an `api-key` header, a
+/// per-deployment URL and an `api-version` query parameter, so this client
+/// builds the requests itself. The request and response bodies are the same as
+/// OpenAI's, so the response is parsed with rig's OpenAI types.
+#[derive(Clone)]
+pub struct Client {
+    endpoint: String,
+    api_version: String,
+    http_client: reqwest::Client,
+}
+
+impl Client {
+    /// `endpoint` is the resource URL, e.g. `https://my-resource.openai.azure.com`
+    pub fn new(endpoint: &str, api_key: &str, api_version: &str) -> Result<Self, String> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        let api_key = reqwest::header::HeaderValue::from_str(api_key)
+            .map_err(|_| "The Azure OpenAI API key contains invalid characters".to_string())?;
+        headers.insert("api-key", api_key);
+
+        let http_client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            endpoint: endpoint.trim_end_matches('/').to_string(),
+            api_version: api_version.to_string(),
+            http_client,
+        })
+    }
+
+    /// Create a client from `AZURE_OPENAI_ENDPOINT`, `AZURE_OPENAI_API_KEY` and,
+    /// optionally, `AZURE_OPENAI_API_VERSION`
+    pub fn from_env() -> Result<Self, String> {
+        let endpoint = std::env::var("AZURE_OPENAI_ENDPOINT")
+            .map_err(|_| "AZURE_OPENAI_ENDPOINT not set".to_string())?;
+        let api_key = std::env::var("AZURE_OPENAI_API_KEY")
+            .map_err(|_| "AZURE_OPENAI_API_KEY not set".to_string())?;
+        let api_version = std::env::var("AZURE_OPENAI_API_VERSION")
+            .unwrap_or_else(|_| DEFAULT_API_VERSION.to_string());
+
+        Self::new(&endpoint, &api_key, &api_version)
+    }
+
+    /// A completion model for `deployment`, the name given to the model when
+    /// it was deployed in the Azure resource (not the OpenAI model name)
+    pub fn completion_model(&self, deployment: &str) -> CompletionModel {
+        CompletionModel {
+            client: self.clone(),
+            deployment: deployment.to_string(),
+        }
+    }
+
+    /// Start building an agent for `deployment`
+    pub fn agent(&self, deployment: &str) -> AgentBuilder<CompletionModel> {
+        AgentBuilder::new(self.completion_model(deployment))
+    }
+
+    fn chat_completions_url(&self, deployment: &str) -> String {
+        format!(
+            "{}/openai/deployments/{}/chat/completions?api-version={}",
+            self.endpoint, deployment, self.api_version
+        )
+    }
+}
+
+#[derive(Clone)]
+pub struct CompletionModel {
+    client: Client,
+    pub deployment: String,
+}
+
+/// Build the chat completions body for `request`. There is no `model` field:
+/// Azure picks the model from the deployment in the URL.
+fn request_body(mut request: CompletionRequest) -> Value {
+    let mut messages = Vec::new();
+    if let Some(preamble) = &request.preamble {
+        messages.push(completion::Message {
+            role: "system".into(),
+            content: preamble.clone(),
+        });
+    }
+    messages.append(&mut request.chat_history);
+    messages.push(completion::Message {
+        role: "user".into(),
+        content: request.prompt_with_context(),
+    });
+
+    let mut body = json!({
+        "messages": messages,
+        "temperature": request.temperature,
+    });
+    if let Some(max_tokens) = request.max_tokens {
+        body["max_tokens"] = json!(max_tokens);
+    }
+    if !request.tools.is_empty() {
+        body["tools"] = json!(request
+            .tools
+            .into_iter()
+            .map(openai::ToolDefinition::from)
+            .collect::<Vec<_>>());
+        body["tool_choice"] = json!("auto");
+    }
+
+    match request.additional_params {
+        Some(params) => json_utils::merge(body, params),
+        None => body,
+    }
+}
+
+impl completion::CompletionModel for CompletionModel {
+    type Response = openai::CompletionResponse;
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<openai::CompletionResponse>, CompletionError> {
+        let response = self
+            .client
+            .http_client
+            .post(self.client.chat_completions_url(&self.deployment))
+            .json(&request_body(request))
+            .send()
+            .await?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+        let text = response.text().await?;
+
+        // Azure explains what went wrong (unknown deployment, unsupported
+        // api-version, content filter...) in the body of the error response
+        if !status.is_success() {
+            return Err(CompletionError::ProviderError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        serde_json::from_str::<openai::CompletionResponse>(&text)?.try_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chat_completions_url() {
+        let client = Client::new("https://my-resource.openai.azure.com/", "key", "2024-06-01").unwrap();
+        assert_eq!(
+            client.chat_completions_url("gpt-4o-prod"),
+            "https://my-resource.openai.azure.com/openai/deployments/gpt-4o-prod/chat/completions?api-version=2024-06-01"
+        );
+    }
+
+    #[test]
+    fn test_request_body() {
+        let request = CompletionRequest {
+            prompt: "What is Rig?".into(),
+            preamble: Some("You are a helpful assistant.".into()),
+            chat_history: vec![],
+            documents: vec![],
+            tools: vec![],
+            temperature: Some(0.5),
+            max_tokens: Some(256),
+            additional_params: Some(json!({ "top_p": 0.9 })),
+        };
+
+        assert_eq!(
+            request_body(request),
+            json!({
+                "messages": [
+                    { "role": "system", "content": "You are a helpful assistant." },
+                    { "role": "user", "content": "What is Rig?" },
+                ],
+                "temperature": 0.5,
+                "max_tokens": 256,
+                "top_p": 0.9,
+            })
+        );
+    }
+}
@@ -0,0 +1,48 @@
+mod azure;
+
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use std::io::{self, Write};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Requests go to the Azure resource instead of api.openai.com
+    let azure_client = azure::Client::from_env()?;
+    let deployment =
+        std::env::var("AZURE_OPENAI_DEPLOYMENT").map_err(|_| "AZURE_OPENAI_DEPLOYMENT not set")?;
+
+    // The agent is built exactly like an OpenAI one
+    let agent = azure_client
+        .agent(&deployment)
+        .preamble("You are a helpful assistant.")
+        .build();
+
+    println!("Ask the {} deployment anything (type 'exit' to quit)", deployment);
+
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            break;
+        }
+
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        if input.eq_ignore_ascii_case("exit") {
+            break;
+        }
+
+        match agent.prompt(input).await {
+            Ok(response) => println!("{}\n", response),
+            Err(e) => eprintln!("Error: {}\n", e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+pub struct CalculatorArgs {
+    expression: String,
+}
+
+// Anything returned here is sent back to the model as the tool's output, so
+// the messages say what went wrong in terms it can act on
+#[derive(Debug, thiserror::Error)]
+pub enum CalculatorError {
+    #[error("Invalid expression: {0}")]
+    InvalidExpression(String),
+    #[error("The result is infinite: a division by zero, or too large to represent (e.g. 10^400)")]
+    Infinite,
+    #[error("The result is undefined (e.g. 0/0 or the square root of a negative number)")]
+    Undefined,
+}
+
+/// A tool that runs entirely in-process: no HTTP client, no API key, just a
+/// function from arguments to a result
+pub struct CalculatorTool;
+
+impl Tool for CalculatorTool {
+    const NAME: &'static str = "calculator";
+
+    type Args = CalculatorArgs;
+    type Output = f64;
+    type Error = CalculatorError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "calculator".to_string(),
+            description: "Evaluate an arithmetic expression. Supports + - * / ^ %, parentheses, the constants pi and e, and functions such as sqrt, abs, ln, sin and cos.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "expression": { "type": "string", "description": "The expression to evaluate (e.g., '(1920 * 1080) / 2')" },
+                },
+                "required": ["expression"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let value = meval::eval_str(&args.expression)
+            .map_err(|e| CalculatorError::InvalidExpression(e.to_string()))?;
+
+        // meval follows IEEE 754, so errors show up as special values
+        if value.is_infinite() {
+            return Err(CalculatorError::Infinite);
+        }
+        if value.is_nan() {
+            return Err(CalculatorError::Undefined);
+        }
+
+        Ok(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn evaluate(expression: &str) -> Result<f64, CalculatorError> {
+        CalculatorTool
+            .call(CalculatorArgs {
+                expression: expression.to_string(),
+            })
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_evaluates_expressions() {
+        assert_eq!(evaluate("(1920 * 1080) / 2").await.unwrap(), 1_036_800.0);
+        assert_eq!(evaluate("2^10 + sqrt(16)").await.unwrap(), 1028.0);
+    }
+
+    #[tokio::test]
+    async fn test_errors() {
+        assert!(matches!(evaluate("1 / 0").await, Err(CalculatorError::Infinite)));
+        assert!(matches!(evaluate("10^400").await, Err(CalculatorError::Infinite)));
+        assert!(matches!(evaluate("0 / 0").await, Err(CalculatorError::Undefined)));
+        assert!(matches!(evaluate("2 +* 3").await, Err(CalculatorError::InvalidExpression(_))));
+    }
+}
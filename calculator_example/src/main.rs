@@ -0,0 +1,35 @@
+mod calculator_tool;
+
+use crate::calculator_tool::CalculatorTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the CalculatorTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a helpful assistant. Use the calculator tool for any arithmetic instead of working it out yourself.")
+        .tool(CalculatorTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "If I invest 2500 at 4.5% interest compounded yearly, how much do I have after 12 years?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
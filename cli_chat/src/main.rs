@@ -0,0 +1,78 @@
+use dotenv::dotenv;
+use rig::completion::{Chat, Message};
+use rig::providers::openai;
+use std::io::{self, Write};
+use std::time::Instant;
+
+const HELP: &str = "Commands: /reset clears the conversation, /exit quits";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let openai_client = openai::Client::from_env();
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a friendly assistant having a conversation in a terminal. Keep your answers short.")
+        .build();
+
+    // Every exchange is appended here and sent along with the next prompt,
+    // which is what gives the agent its memory of the conversation
+    let mut history: Vec<Message> = Vec::new();
+
+    println!("{}", HELP);
+
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            break;
+        }
+
+        let input = input.trim();
+        match input {
+            "" => continue,
+            "/exit" => break,
+            "/reset" => {
+                history.clear();
+                println!("Conversation cleared.\n");
+                continue;
+            }
+            _ if input.starts_with('/') => {
+                println!("Unknown command. {}\n", HELP);
+                continue;
+            }
+            _ => {}
+        }
+
+        let started = Instant::now();
+        match agent.chat(input, history.clone()).await {
+            Ok(response) => {
+                println!("{}", response);
+                // rig does not expose token usage for chat responses, so only
+                // the latency is reported
+                println!(
+                    "[{:.2}s, {} messages in history]\n",
+                    started.elapsed().as_secs_f64(),
+                    history.len() + 2
+                );
+
+                history.push(Message {
+                    role: "user".to_string(),
+                    content: input.to_string(),
+                });
+                history.push(Message {
+                    role: "assistant".to_string(),
+                    content: response,
+                });
+            }
+            // The failed prompt is not added to the history, so it can simply
+            // be sent again
+            Err(e) => eprintln!("Error: {}\n", e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,110 @@
+// main.rs
+
+mod rerank;
+
+use anyhow::{Context, Result};
+use dotenv::dotenv;
+use rerank::{Reranker, RERANK_ENGLISH_V3};
+use rig::completion::Prompt;
+use rig::embeddings::EmbeddingsBuilder;
+use rig::providers::cohere;
+use rig::vector_store::in_memory_store::InMemoryVectorStore;
+use rig::vector_store::{VectorStore, VectorStoreIndex};
+
+/// Candidates retrieved by embedding similarity
+const CANDIDATES: usize = 6;
+/// Candidates kept after reranking and given to the agent
+const TOP_K: usize = 2;
+
+/// The knowledge base
+const PASSAGES: &[&str] = &[
+    "Rig agents can be given tools: Rust types implementing the Tool trait that the model can call with JSON arguments.",
+    "Rig's EmbeddingsBuilder embeds documents in batches and returns them ready to be added to a vector store.",
+    "The InMemoryVectorStore keeps embeddings in a HashMap and is best suited to small datasets and prototypes.",
+    "Rig supports several providers, including OpenAI, Cohere, Anthropic and Perplexity.",
+    "A RAG agent retrieves the documents closest to the prompt and adds them to the context before calling the model.",
+    "Rust's ownership model ensures each value has a single owner, which is dropped when it goes out of scope.",
+    "Cargo features let a crate compile optional functionality only when it is requested.",
+    "Tokio tasks are lightweight and are scheduled cooperatively on a pool of worker threads.",
+];
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "How do I store embeddings for a quick prototype?".to_string()
+    } else {
+        query
+    };
+
+    // Initialize Cohere client
+    let api_key = std::env::var("COHERE_API_KEY").context("COHERE_API_KEY not set")?;
+    let cohere_client = cohere::Client::new(&api_key);
+
+    // Cohere embeds documents and queries differently, so each gets its own
+    // model: one to build the store, one for the index to embed queries with
+    let document_model = cohere_client.embedding_model(cohere::EMBED_ENGLISH_V3, "search_document");
+    let query_model = cohere_client.embedding_model(cohere::EMBED_ENGLISH_V3, "search_query");
+
+    let mut builder = EmbeddingsBuilder::new(document_model);
+    for (i, passage) in PASSAGES.iter().enumerate() {
+        builder = builder.simple_document(&i.to_string(), passage);
+    }
+    let embeddings = builder.build().await?;
+
+    let mut vector_store = InMemoryVectorStore::default();
+    vector_store.add_documents(embeddings).await?;
+    let index = vector_store.index(query_model);
+
+    // Step 1: retrieve candidates by embedding similarity. The index returns
+    // them in no particular order, so sort by its score (higher is closer).
+    let mut candidates = index.top_n_from_query(&query, CANDIDATES).await?;
+    candidates.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let candidates: Vec<String> = candidates
+        .into_iter()
+        .filter_map(|(_, doc)| doc.document.as_str().map(str::to_string))
+        .collect();
+
+    println!("Before reranking (embedding similarity):");
+    for (rank, passage) in candidates.iter().enumerate() {
+        println!("  {}. {}", rank + 1, passage);
+    }
+
+    // Step 2: rerank the candidates against the query
+    let reranker = Reranker::new(&api_key, RERANK_ENGLISH_V3)?;
+    let reranked = reranker.rerank(&query, &candidates, candidates.len()).await?;
+
+    println!("\nAfter reranking:");
+    for (rank, result) in reranked.iter().enumerate() {
+        println!(
+            "  {}. ({:.3}) {}",
+            rank + 1,
+            result.relevance_score,
+            candidates[result.index]
+        );
+    }
+
+    // Step 3: answer using only the best reranked passages
+    let context = reranked
+        .iter()
+        .take(TOP_K)
+        .map(|result| format!("- {}", candidates[result.index]))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let agent = cohere_client
+        .agent(cohere::COMMAND_R)
+        .preamble("You answer questions about Rig using only the passages provided. If they don't contain the answer, say so.")
+        .build();
+
+    let response = agent
+        .prompt(&format!("Passages:\n{}\n\nQuestion: {}", context, query))
+        .await?;
+
+    println!("\nAgent response:\n{}", response);
+
+    Ok(())
+}
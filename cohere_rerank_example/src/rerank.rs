@@ -0,0 +1,153 @@
+// rerank.rs
+
+// Rig has no rerank API, so Cohere's rerank endpoint is called directly.
+// See https://docs.cohere.com/reference/rerank
+
+use serde::Deserialize;
+use serde_json::json;
+use std::time::Duration;
+
+const COHERE_RERANK_URL: &str = "https://api.cohere.ai/v1/rerank";
+
+pub const RERANK_ENGLISH_V3: &str = "rerank-english-v3.0";
+
+// Maximum time to wait for the rerank API before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, thiserror::Error)]
+pub enum RerankError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+}
+
+/// One reranked document: its position in the list passed to `rerank` and
+/// how relevant Cohere judged it to the query, from 0 to 1
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct RerankResult {
+    pub index: usize,
+    pub relevance_score: f64,
+}
+
+#[derive(Deserialize)]
+struct RerankResponse {
+    results: Vec<RerankResult>,
+}
+
+pub struct Reranker {
+    http_client: reqwest::Client,
+    api_key: String,
+    model: String,
+}
+
+// Turn the raw response body into results, most relevant first
+fn parse_results(text: &str, document_count: usize) -> Result<Vec<RerankResult>, RerankError> {
+    let response: RerankResponse =
+        serde_json::from_str(text).map_err(|e| RerankError::InvalidResponse(e.to_string()))?;
+
+    let mut results = response.results;
+    if let Some(result) = results.iter().find(|result| result.index >= document_count) {
+        return Err(RerankError::InvalidResponse(format!(
+            "index {} out of range for {} documents",
+            result.index, document_count
+        )));
+    }
+    results.sort_by(|a, b| b.relevance_score.total_cmp(&a.relevance_score));
+
+    Ok(results)
+}
+
+impl Reranker {
+    pub fn new(api_key: &str, model: &str) -> Result<Self, RerankError> {
+        let http_client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| RerankError::HttpRequestFailed(e.to_string()))?;
+
+        Ok(Self {
+            http_client,
+            api_key: api_key.to_string(),
+            model: model.to_string(),
+        })
+    }
+
+    /// Rank `documents` by relevance to `query` and return the `top_n` best
+    pub async fn rerank(
+        &self,
+        query: &str,
+        documents: &[String],
+        top_n: usize,
+    ) -> Result<Vec<RerankResult>, RerankError> {
+        let response = self
+            .http_client
+            .post(COHERE_RERANK_URL)
+            .bearer_auth(&self.api_key)
+            .json(&json!({
+                "model": self.model,
+                "query": query,
+                "documents": documents,
+                "top_n": top_n,
+            }))
+            .send()
+            .await
+            .map_err(|e| RerankError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| RerankError::HttpRequestFailed(e.to_string()))?;
+
+        if !status.is_success() {
+            return Err(RerankError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        parse_results(&text, documents.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_results_sorts_by_relevance() {
+        let text = r#"{
+            "id": "07734bd2-2473-4f07-94e1-0d9f0e6843cf",
+            "results": [
+                { "index": 2, "relevance_score": 0.12 },
+                { "index": 0, "relevance_score": 0.98 }
+            ],
+            "meta": { "api_version": { "version": "1" } }
+        }"#;
+
+        assert_eq!(
+            parse_results(text, 3).unwrap(),
+            vec![
+                RerankResult { index: 0, relevance_score: 0.98 },
+                RerankResult { index: 2, relevance_score: 0.12 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_results_rejects_bad_responses() {
+        let out_of_range = r#"{"results": [{ "index": 5, "relevance_score": 0.5 }]}"#;
+        assert!(matches!(
+            parse_results(out_of_range, 3),
+            Err(RerankError::InvalidResponse(_))
+        ));
+        assert!(matches!(
+            parse_results("not json", 3),
+            Err(RerankError::InvalidResponse(_))
+        ));
+    }
+}
@@ -0,0 +1,236 @@
+use rig::completion::{
+    CompletionError, CompletionModel, CompletionRequest, CompletionResponse, ModelChoice,
+};
+use rig::providers::openai;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+/// USD prices per million input and output tokens. Prices change, so check
+/// https://openai.com/api/pricing before relying on the estimates.
+const PRICING: &[(&str, f64, f64)] = &[
+    ("gpt-4o", 2.50, 10.00),
+    ("gpt-4o-mini", 0.15, 0.60),
+    ("gpt-4-turbo", 10.00, 30.00),
+    ("gpt-4", 30.00, 60.00),
+    ("gpt-3.5-turbo", 0.50, 1.50),
+    ("o1-preview", 15.00, 60.00),
+    ("o1-mini", 3.00, 12.00),
+];
+
+/// Token counts for one completion
+#[derive(Debug, PartialEq)]
+pub struct TokenUsage {
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+}
+
+/// Raw provider responses that report the model used and the tokens billed
+pub trait ReportsUsage {
+    fn model(&self) -> &str;
+    fn usage(&self) -> Option<TokenUsage>;
+}
+
+impl ReportsUsage for openai::CompletionResponse {
+    fn model(&self) -> &str {
+        &self.model
+    }
+
+    fn usage(&self) -> Option<TokenUsage> {
+        self.usage.as_ref().map(|usage| TokenUsage {
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.total_tokens.saturating_sub(usage.prompt_tokens),
+        })
+    }
+}
+
+/// Number of tokens `text` takes for `model`, used when the provider doesn't
+/// report usage. Models tiktoken doesn't know are counted with the encoding of
+/// the GPT-4o family, which is a fair estimate for other recent models too.
+fn count_tokens(model: &str, text: &str) -> usize {
+    tiktoken_rs::bpe_for_model(model)
+        .unwrap_or_else(|_| tiktoken_rs::o200k_base_singleton())
+        .encode_with_special_tokens(text)
+        .len()
+}
+
+/// Estimated cost in USD, or `None` for a model missing from `PRICING`.
+/// Dated snapshots such as `gpt-4o-2024-08-06` are priced like the model they
+/// start with; the longest match wins, so `gpt-4o-mini` isn't priced as `gpt-4o`.
+fn estimate_cost(model: &str, usage: &TokenUsage) -> Option<f64> {
+    let (_, input, output) = PRICING
+        .iter()
+        .filter(|(name, _, _)| model.starts_with(name))
+        .max_by_key(|(name, _, _)| name.len())?;
+    Some((usage.prompt_tokens as f64 * input + usage.completion_tokens as f64 * output) / 1_000_000.0)
+}
+
+/// One line of the log file
+#[derive(Serialize)]
+struct LogEntry<'a> {
+    /// Seconds since the Unix epoch
+    timestamp: u64,
+    model: &'a str,
+    prompt: &'a str,
+    response: String,
+    prompt_tokens: usize,
+    completion_tokens: usize,
+    /// Whether the token counts were worked out with tiktoken because the
+    /// provider didn't report them
+    estimated: bool,
+    cost_usd: Option<f64>,
+    elapsed_ms: u128,
+}
+
+/// A completion model that appends every prompt, response, token count and
+/// estimated cost to a JSONL file. It wraps another model, so any agent can
+/// use it:
+///
+/// ```ignore
+/// let model = CostLogger::new(openai_client.completion_model(openai::GPT_4O), "costs.jsonl");
+/// let agent = AgentBuilder::new(model).preamble("...").build();
+/// ```
+#[derive(Clone)]
+pub struct CostLogger<M> {
+    inner: M,
+    path: PathBuf,
+}
+
+impl<M> CostLogger<M> {
+    pub fn new(inner: M, path: impl Into<PathBuf>) -> Self {
+        Self {
+            inner,
+            path: path.into(),
+        }
+    }
+
+    fn append(&self, entry: &LogEntry) -> std::io::Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", serde_json::to_string(entry)?)
+    }
+}
+
+impl<M> CompletionModel for CostLogger<M>
+where
+    M: CompletionModel,
+    M::Response: ReportsUsage,
+{
+    type Response = M::Response;
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<CompletionResponse<Self::Response>, CompletionError> {
+        let prompt = request.prompt.clone();
+        let sent_text = request
+            .preamble
+            .iter()
+            .chain(request.chat_history.iter().map(|message| &message.content))
+            .chain([&request.prompt_with_context()])
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        let started = Instant::now();
+        let response = self.inner.completion(request).await?;
+        let elapsed_ms = started.elapsed().as_millis();
+
+        let text = match &response.choice {
+            ModelChoice::Message(text) => text.clone(),
+            ModelChoice::ToolCall(name, args) => format!("Tool call: {}({})", name, args),
+        };
+        let model = response.raw_response.model();
+        let (usage, estimated) = match response.raw_response.usage() {
+            Some(usage) => (usage, false),
+            None => (
+                TokenUsage {
+                    prompt_tokens: count_tokens(model, &sent_text),
+                    completion_tokens: count_tokens(model, &text),
+                },
+                true,
+            ),
+        };
+
+        let entry = LogEntry {
+            timestamp: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |time| time.as_secs()),
+            model,
+            prompt: &prompt,
+            cost_usd: estimate_cost(model, &usage),
+            response: text,
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+            estimated,
+            elapsed_ms,
+        };
+
+        // A logging failure shouldn't cost the user their answer
+        if let Err(e) = self.append(&entry) {
+            eprintln!("Could not write to {}: {}", self.path.display(), e);
+        }
+
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_estimate_cost() {
+        let usage = TokenUsage {
+            prompt_tokens: 1_000,
+            completion_tokens: 200,
+        };
+        // 1000 * $2.50 / 1M + 200 * $10 / 1M
+        assert_eq!(estimate_cost("gpt-4o-2024-08-06", &usage), Some(0.0045));
+        // 1000 * $0.15 / 1M + 200 * $0.60 / 1M
+        let mini = estimate_cost("gpt-4o-mini-2024-07-18", &usage).unwrap();
+        assert!((mini - 0.00027).abs() < 1e-12);
+        assert_eq!(estimate_cost("my-fine-tune", &usage), None);
+    }
+
+    #[test]
+    fn test_count_tokens() {
+        assert_eq!(count_tokens("gpt-4o-2024-08-06", "Hello, world!"), 4);
+        assert_eq!(count_tokens("gpt-3.5-turbo", "What's the price of ETH in euros?"), 9);
+        // Unknown models fall back to the GPT-4o encoding
+        assert_eq!(count_tokens("my-fine-tune", "Hello, world!"), 4);
+    }
+
+    #[test]
+    fn test_append_writes_one_json_line_per_entry() {
+        let path = std::env::temp_dir().join(format!("cost_log_test_{}.jsonl", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let logger = CostLogger::new((), &path);
+
+        for prompt in ["first", "second"] {
+            let entry = LogEntry {
+                timestamp: 0,
+                model: "gpt-4o",
+                prompt,
+                response: "answer".into(),
+                prompt_tokens: 10,
+                completion_tokens: 2,
+                estimated: false,
+                cost_usd: Some(0.000045),
+                elapsed_ms: 5,
+            };
+            logger.append(&entry).unwrap();
+        }
+
+        let contents = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let lines: Vec<serde_json::Value> = contents
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["prompt"], "second");
+        assert_eq!(lines[1]["cost_usd"], 0.000045);
+    }
+}
@@ -0,0 +1,44 @@
+mod coingecko_tool;
+mod cost_log;
+
+use crate::coingecko_tool::CoinGeckoTool;
+use crate::cost_log::CostLogger;
+use dotenv::dotenv;
+use rig::agent::AgentBuilder;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+// Every prompt, response and its estimated cost is appended here
+const COST_LOG_PATH: &str = "costs.jsonl";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Wrap the model so each completion is logged with its cost
+    let model = CostLogger::new(openai_client.completion_model(openai::GPT_4O), COST_LOG_PATH);
+
+    // Build the agent with the CoinGeckoTool
+    let agent = AgentBuilder::new(model)
+        .preamble("You are a crypto market assistant. Use the get_coin_price tool to look up current prices.")
+        .tool(CoinGeckoTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What's the price of ETH in euros?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+    println!("\nUsage and cost logged to {}", COST_LOG_PATH);
+
+    Ok(())
+}
@@ -0,0 +1,319 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::env;
+use std::time::Duration;
+
+const FMP_API_URL: &str = "https://financialmodelingprep.com/stable";
+
+// Maximum time to wait for Financial Modeling Prep before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+pub struct CompanyFundamentalsArgs {
+    symbol: String,
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum CompanyFundamentalsError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Missing API key: set the FMP_API_KEY environment variable")]
+    MissingApiKey,
+    #[error("Unknown ticker '{0}': use the symbol the company trades under, e.g. 'AAPL'")]
+    UnknownTicker(String),
+    #[error("Financial Modeling Prep quota reached: {0}")]
+    QuotaExceeded(String),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CompanyFundamentals {
+    symbol: String,
+    name: String,
+    /// `None` for ETFs and funds
+    sector: Option<String>,
+    industry: Option<String>,
+    /// Currency of `price`, `market_cap` and `revenue`
+    currency: String,
+    price: f64,
+    market_cap: f64,
+    /// Price over the last twelve months' earnings per share. `None` when
+    /// earnings were negative, where a P/E isn't meaningful.
+    pe_ratio: Option<f64>,
+    /// Revenue of the latest fiscal year
+    revenue: Option<f64>,
+    /// e.g. `FY 2024`
+    revenue_period: Option<String>,
+}
+
+// The fields used from Financial Modeling Prep's company profile
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Profile {
+    symbol: String,
+    company_name: String,
+    sector: Option<String>,
+    industry: Option<String>,
+    currency: String,
+    price: f64,
+    market_cap: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RatiosTtm {
+    #[serde(rename = "priceToEarningsRatioTTM")]
+    price_to_earnings_ratio_ttm: Option<f64>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IncomeStatement {
+    // Sent as a string such as "2024"; a number is accepted too
+    fiscal_year: serde_json::Value,
+    revenue: f64,
+}
+
+// Errors are reported as `{"Error Message": "..."}`
+#[derive(Deserialize)]
+struct FmpError {
+    #[serde(rename = "Error Message")]
+    message: String,
+}
+
+pub struct CompanyFundamentalsTool;
+
+// Normalize `symbol`, rejecting anything that can't be a ticker before a
+// request uses up the daily quota
+fn normalize_symbol(symbol: &str) -> Result<String, CompanyFundamentalsError> {
+    let symbol = symbol.trim().to_uppercase();
+    let valid = (1..=10).contains(&symbol.len())
+        && symbol
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-'));
+    if valid {
+        Ok(symbol)
+    } else {
+        Err(CompanyFundamentalsError::UnknownTicker(symbol))
+    }
+}
+
+// Map a failed response to an error. Running out of quota is reported as a
+// 429, or on some plans as a 403 or even a 200 with an error message, so it is
+// recognized by the message as well.
+fn check_response(status: reqwest::StatusCode, text: &str) -> Result<(), CompanyFundamentalsError> {
+    let message = serde_json::from_str::<FmpError>(text).ok().map(|error| error.message);
+    let quota = message.as_deref().is_some_and(|message| message.contains("Limit Reach"));
+
+    if status == reqwest::StatusCode::TOO_MANY_REQUESTS || quota {
+        return Err(CompanyFundamentalsError::QuotaExceeded(
+            message.unwrap_or_else(|| "too many requests".to_string()),
+        ));
+    }
+    if let Some(message) = message {
+        return Err(CompanyFundamentalsError::ApiError(message));
+    }
+    if !status.is_success() {
+        return Err(CompanyFundamentalsError::ApiError(format!(
+            "Status: {}, Response: {}",
+            status, text
+        )));
+    }
+    Ok(())
+}
+
+// Every endpoint answers with a list, which is empty for unknown symbols
+fn parse_first<T: serde::de::DeserializeOwned>(text: &str) -> Result<Option<T>, CompanyFundamentalsError> {
+    let items: Vec<T> = serde_json::from_str(text)
+        .map_err(|e| CompanyFundamentalsError::InvalidResponse(e.to_string()))?;
+    Ok(items.into_iter().next())
+}
+
+// Combine the three responses into the fundamentals of `symbol`
+fn parse_fundamentals(
+    symbol: &str,
+    profile: &str,
+    ratios: &str,
+    income: &str,
+) -> Result<CompanyFundamentals, CompanyFundamentalsError> {
+    let profile: Profile = parse_first(profile)?
+        .ok_or_else(|| CompanyFundamentalsError::UnknownTicker(symbol.to_string()))?;
+    let ratios: Option<RatiosTtm> = parse_first(ratios)?;
+    let income: Option<IncomeStatement> = parse_first(income)?;
+
+    let not_empty = |text: Option<String>| text.filter(|text| !text.trim().is_empty());
+    Ok(CompanyFundamentals {
+        symbol: profile.symbol,
+        name: profile.company_name,
+        sector: not_empty(profile.sector),
+        industry: not_empty(profile.industry),
+        currency: profile.currency,
+        price: profile.price,
+        market_cap: profile.market_cap,
+        pe_ratio: ratios
+            .and_then(|ratios| ratios.price_to_earnings_ratio_ttm)
+            .filter(|pe| *pe > 0.0),
+        revenue_period: income.as_ref().map(|income| match &income.fiscal_year {
+            serde_json::Value::String(year) => format!("FY {}", year),
+            year => format!("FY {}", year),
+        }),
+        revenue: income.map(|income| income.revenue),
+    })
+}
+
+impl From<BodyError> for CompanyFundamentalsError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => CompanyFundamentalsError::TooLarge,
+            BodyError::Read(e) => CompanyFundamentalsError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+// GET `{FMP_API_URL}/{endpoint}` with `query`, returning the body
+async fn get(
+    client: &reqwest::Client,
+    api_key: &str,
+    endpoint: &str,
+    query: &[(&str, &str)],
+) -> Result<String, CompanyFundamentalsError> {
+    let response = client
+        .get(format!("{}/{}", FMP_API_URL, endpoint))
+        .query(query)
+        .query(&[("apikey", api_key)])
+        .send()
+        .await
+        .map_err(|e| CompanyFundamentalsError::HttpRequestFailed(e.to_string()))?;
+
+    // Get the status code before consuming `response`
+    let status = response.status();
+
+    let text = read_body(response).await?;
+
+    check_response(status, &text)?;
+    Ok(text)
+}
+
+impl Tool for CompanyFundamentalsTool {
+    const NAME: &'static str = "get_company_fundamentals";
+
+    type Args = CompanyFundamentalsArgs;
+    type Output = CompanyFundamentals;
+    type Error = CompanyFundamentalsError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "get_company_fundamentals".to_string(),
+            description: "Get a public company's fundamentals: sector and industry, share price, market capitalization, trailing twelve-month P/E ratio and revenue for the latest fiscal year".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "symbol": { "type": "string", "description": "The company's ticker symbol (e.g., 'AAPL', 'MSFT')" },
+                },
+                "required": ["symbol"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // Use the Financial Modeling Prep API key from an environment variable
+        let api_key = env::var("FMP_API_KEY").map_err(|_| CompanyFundamentalsError::MissingApiKey)?;
+
+        let symbol = normalize_symbol(&args.symbol)?;
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| CompanyFundamentalsError::HttpRequestFailed(e.to_string()))?;
+
+        // The profile comes first: an unknown ticker is found out without
+        // spending two more requests of the daily quota
+        let symbol_query = [("symbol", symbol.as_str())];
+        let profile = get(&client, &api_key, "profile", &symbol_query).await?;
+        if parse_first::<Profile>(&profile)?.is_none() {
+            return Err(CompanyFundamentalsError::UnknownTicker(symbol));
+        }
+
+        let income_query = [("symbol", symbol.as_str()), ("limit", "1")];
+        let (ratios, income) = tokio::try_join!(
+            get(&client, &api_key, "ratios-ttm", &symbol_query),
+            get(&client, &api_key, "income-statement", &income_query),
+        )?;
+
+        parse_fundamentals(&symbol, &profile, &ratios, &income)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_fundamentals() {
+        let profile = r#"[{"symbol":"AAPL","price":232.8,"marketCap":3500823120000,"beta":1.24,"companyName":"Apple Inc.","currency":"USD","exchange":"NASDAQ","industry":"Consumer Electronics","sector":"Technology","country":"US","isEtf":false}]"#;
+        let ratios = r#"[{"symbol":"AAPL","priceToEarningsRatioTTM":35.41,"priceToBookRatioTTM":52.1}]"#;
+        let income = r#"[{"date":"2024-09-28","symbol":"AAPL","reportedCurrency":"USD","fiscalYear":"2024","period":"FY","revenue":391035000000,"netIncome":93736000000}]"#;
+
+        assert_eq!(
+            parse_fundamentals("AAPL", profile, ratios, income).unwrap(),
+            CompanyFundamentals {
+                symbol: "AAPL".into(),
+                name: "Apple Inc.".into(),
+                sector: Some("Technology".into()),
+                industry: Some("Consumer Electronics".into()),
+                currency: "USD".into(),
+                price: 232.8,
+                market_cap: 3500823120000.0,
+                pe_ratio: Some(35.41),
+                revenue: Some(391035000000.0),
+                revenue_period: Some("FY 2024".into()),
+            }
+        );
+
+        // Loss-making, with no income statement available
+        let ratios = r#"[{"symbol":"AAPL","priceToEarningsRatioTTM":-12.5}]"#;
+        let fundamentals = parse_fundamentals("AAPL", profile, ratios, "[]").unwrap();
+        assert_eq!(fundamentals.pe_ratio, None);
+        assert_eq!(fundamentals.revenue, None);
+    }
+
+    #[test]
+    fn test_unknown_tickers_and_quota_errors() {
+        assert_eq!(normalize_symbol(" brk.b "), Ok("BRK.B".into()));
+        assert_eq!(
+            normalize_symbol("apple inc"),
+            Err(CompanyFundamentalsError::UnknownTicker("APPLE INC".into()))
+        );
+        assert_eq!(
+            parse_fundamentals("NOTREAL", "[]", "[]", "[]"),
+            Err(CompanyFundamentalsError::UnknownTicker("NOTREAL".into()))
+        );
+
+        let limit = r#"{"Error Message":"Limit Reach . Please upgrade your plan or visit our documentation for more details at https://site.financialmodelingprep.com/"}"#;
+        for status in [reqwest::StatusCode::OK, reqwest::StatusCode::TOO_MANY_REQUESTS] {
+            assert!(matches!(
+                check_response(status, limit),
+                Err(CompanyFundamentalsError::QuotaExceeded(_))
+            ));
+        }
+        assert!(matches!(
+            check_response(reqwest::StatusCode::TOO_MANY_REQUESTS, ""),
+            Err(CompanyFundamentalsError::QuotaExceeded(_))
+        ));
+
+        let invalid_key = r#"{"Error Message":"Invalid API KEY. Feel free to create a Free API Key or visit https://site.financialmodelingprep.com/faqs?search=why-is-my-api-key-invalid for more information."}"#;
+        assert!(matches!(
+            check_response(reqwest::StatusCode::UNAUTHORIZED, invalid_key),
+            Err(CompanyFundamentalsError::ApiError(_))
+        ));
+        assert_eq!(check_response(reqwest::StatusCode::OK, "[]"), Ok(()));
+    }
+}
@@ -0,0 +1,35 @@
+mod company_fundamentals_tool;
+
+use crate::company_fundamentals_tool::CompanyFundamentalsTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the CompanyFundamentalsTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are an equity research assistant. Use the get_company_fundamentals tool to look up a company's sector, market cap, P/E ratio and revenue. Give figures in the currency the tool reports, with large amounts in billions.")
+        .tool(CompanyFundamentalsTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What sector is Apple in, and how is it valued relative to its revenue?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
@@ -0,0 +1,216 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const FRANKFURTER_URL: &str = "https://api.frankfurter.app/latest";
+
+// Maximum time to wait for the FX API before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+pub struct CurrencyConversionArgs {
+    amount: f64,
+    from: String,
+    to: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CurrencyConversionError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Invalid currency code '{0}': use a three-letter ISO 4217 code such as 'USD'")]
+    InvalidCurrencyCode(String),
+    #[error("Unsupported currency: {0}. Only currencies with European Central Bank reference rates are available")]
+    UnknownCurrency(String),
+    #[error("Invalid amount {0}: the amount must be a positive number")]
+    InvalidAmount(f64),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Conversion {
+    amount: f64,
+    from: String,
+    to: String,
+    converted: f64,
+    rate: f64,
+    /// Date of the exchange rate used
+    date: String,
+}
+
+#[derive(Deserialize)]
+struct FrankfurterResponse {
+    date: String,
+    rates: HashMap<String, f64>,
+}
+
+pub struct CurrencyConversionTool;
+
+// Normalize a currency code, rejecting anything that isn't three letters
+fn currency_code(code: &str) -> Result<String, CurrencyConversionError> {
+    let code = code.trim().to_uppercase();
+    if code.len() != 3 || !code.chars().all(|c| c.is_ascii_alphabetic()) {
+        return Err(CurrencyConversionError::InvalidCurrencyCode(code));
+    }
+    Ok(code)
+}
+
+// Pull the converted amount out of a `{ "date": ..., "rates": { "<to>": <amount> } }` response
+fn parse_conversion(
+    text: &str,
+    amount: f64,
+    from: &str,
+    to: &str,
+) -> Result<Conversion, CurrencyConversionError> {
+    let response: FrankfurterResponse = serde_json::from_str(text)
+        .map_err(|e| CurrencyConversionError::InvalidResponse(e.to_string()))?;
+
+    let converted = *response
+        .rates
+        .get(to)
+        .ok_or_else(|| CurrencyConversionError::UnknownCurrency(to.to_string()))?;
+
+    Ok(Conversion {
+        amount,
+        from: from.to_string(),
+        to: to.to_string(),
+        converted,
+        rate: converted / amount,
+        date: response.date,
+    })
+}
+
+impl From<BodyError> for CurrencyConversionError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => CurrencyConversionError::TooLarge,
+            BodyError::Read(e) => CurrencyConversionError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for CurrencyConversionTool {
+    const NAME: &'static str = "convert_currency";
+
+    type Args = CurrencyConversionArgs;
+    type Output = Conversion;
+    type Error = CurrencyConversionError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "convert_currency".to_string(),
+            description: "Convert an amount of money from one currency to another using the latest European Central Bank reference rates".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "amount": { "type": "number", "description": "The amount to convert (e.g., 250)" },
+                    "from": { "type": "string", "description": "Three-letter code of the currency to convert from (e.g., 'USD')" },
+                    "to": { "type": "string", "description": "Three-letter code of the currency to convert to (e.g., 'JPY')" },
+                },
+                "required": ["amount", "from", "to"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let from = currency_code(&args.from)?;
+        let to = currency_code(&args.to)?;
+        if !args.amount.is_finite() || args.amount <= 0.0 {
+            return Err(CurrencyConversionError::InvalidAmount(args.amount));
+        }
+
+        // The API rejects converting a currency to itself
+        if from == to {
+            return Ok(Conversion {
+                amount: args.amount,
+                from,
+                to,
+                converted: args.amount,
+                rate: 1.0,
+                date: String::new(),
+            });
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| CurrencyConversionError::HttpRequestFailed(e.to_string()))?;
+
+        // The Frankfurter API needs no key
+        let response = client
+            .get(FRANKFURTER_URL)
+            .query(&[
+                ("amount", args.amount.to_string()),
+                ("from", from.clone()),
+                ("to", to.clone()),
+            ])
+            .send()
+            .await
+            .map_err(|e| CurrencyConversionError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        let text = read_body(response).await?;
+
+        // Unknown currencies are answered with a 404
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(CurrencyConversionError::UnknownCurrency(format!("{} or {}", from, to)));
+        }
+        if !status.is_success() {
+            return Err(CurrencyConversionError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        parse_conversion(&text, args.amount, &from, &to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_currency_code() {
+        assert_eq!(currency_code(" usd ").unwrap(), "USD");
+        assert!(matches!(
+            currency_code("dollars"),
+            Err(CurrencyConversionError::InvalidCurrencyCode(_))
+        ));
+        assert!(matches!(
+            currency_code("U$D"),
+            Err(CurrencyConversionError::InvalidCurrencyCode(_))
+        ));
+    }
+
+    #[test]
+    fn test_parse_conversion() {
+        let text = r#"{"amount":250.0,"base":"USD","date":"2024-10-08","rates":{"JPY":36925.0}}"#;
+        assert_eq!(
+            parse_conversion(text, 250.0, "USD", "JPY").unwrap(),
+            Conversion {
+                amount: 250.0,
+                from: "USD".into(),
+                to: "JPY".into(),
+                converted: 36925.0,
+                rate: 147.7,
+                date: "2024-10-08".into(),
+            }
+        );
+        assert!(matches!(
+            parse_conversion(r#"{"date":"2024-10-08","rates":{}}"#, 1.0, "USD", "XYZ"),
+            Err(CurrencyConversionError::UnknownCurrency(_))
+        ));
+    }
+}
@@ -0,0 +1,35 @@
+mod currency_tool;
+
+use crate::currency_tool::CurrencyConversionTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the CurrencyConversionTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a helpful travel and finance assistant. Use the convert_currency tool for any currency conversion and mention the date of the rate used.")
+        .tool(CurrencyConversionTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "How many yen will I get for 250 US dollars?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
@@ -0,0 +1,196 @@
+use chrono::{DateTime, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use chrono_tz::Tz;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+// Formats accepted for a timestamp without a UTC offset, read in `from_timezone`
+const LOCAL_FORMATS: &[&str] = &["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"];
+
+#[derive(Deserialize)]
+pub struct DateTimeArgs {
+    timestamp: Option<String>,
+    from_timezone: Option<String>,
+    timezone: Option<String>,
+}
+
+// Anything returned here is sent back to the model as the tool's output, so
+// the messages say what went wrong in terms it can act on
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum DateTimeError {
+    #[error("Unknown timezone '{0}': use an IANA name such as 'Europe/Paris', 'America/New_York' or 'UTC'")]
+    UnknownTimezone(String),
+    #[error("Invalid timestamp '{0}': use RFC 3339 (e.g., '2024-10-08T14:30:00Z') or 'YYYY-MM-DD HH:MM' in from_timezone")]
+    InvalidTimestamp(String),
+    #[error("{0} doesn't exist in {1}: the clocks skip it when daylight saving time starts")]
+    NonexistentTime(String, String),
+    #[error("{0} happens twice in {1} because the clocks go back when daylight saving time ends: give the timestamp with a UTC offset instead")]
+    AmbiguousTime(String, String),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DateTimeOutput {
+    /// The instant in UTC, RFC 3339
+    utc: String,
+    /// The same instant in `timezone`, RFC 3339
+    local: String,
+    timezone: String,
+    /// e.g. `CEST` or `EST`
+    abbreviation: String,
+    /// e.g. `+02:00`
+    utc_offset: String,
+    weekday: String,
+}
+
+/// Tells the model the current date and time, which it has no other way of
+/// knowing, and converts times between timezones. Runs entirely in-process
+/// with the timezone database compiled into chrono-tz.
+pub struct DateTimeTool;
+
+fn parse_timezone(name: &str) -> Result<Tz, DateTimeError> {
+    name.trim()
+        .parse()
+        .map_err(|_| DateTimeError::UnknownTimezone(name.to_string()))
+}
+
+// A timestamp with an offset is unambiguous; one without is read as a wall
+// clock time in `from`
+fn parse_timestamp(timestamp: &str, from: Tz) -> Result<DateTime<Utc>, DateTimeError> {
+    let timestamp = timestamp.trim();
+    if let Ok(instant) = DateTime::parse_from_rfc3339(timestamp) {
+        return Ok(instant.with_timezone(&Utc));
+    }
+
+    let naive = LOCAL_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(timestamp, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(timestamp, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })
+        .ok_or_else(|| DateTimeError::InvalidTimestamp(timestamp.to_string()))?;
+
+    match from.from_local_datetime(&naive) {
+        LocalResult::Single(instant) => Ok(instant.with_timezone(&Utc)),
+        LocalResult::None => Err(DateTimeError::NonexistentTime(timestamp.to_string(), from.to_string())),
+        LocalResult::Ambiguous(_, _) => Err(DateTimeError::AmbiguousTime(timestamp.to_string(), from.to_string())),
+    }
+}
+
+// The tool's logic, with the current time passed in so it can be tested
+fn convert(args: &DateTimeArgs, now: DateTime<Utc>) -> Result<DateTimeOutput, DateTimeError> {
+    let from = parse_timezone(args.from_timezone.as_deref().unwrap_or("UTC"))?;
+    let to = parse_timezone(args.timezone.as_deref().unwrap_or("UTC"))?;
+
+    let instant = match &args.timestamp {
+        Some(timestamp) if !timestamp.trim().is_empty() => parse_timestamp(timestamp, from)?,
+        _ => now,
+    };
+    let local = instant.with_timezone(&to);
+
+    Ok(DateTimeOutput {
+        utc: instant.to_rfc3339(),
+        local: local.to_rfc3339(),
+        timezone: to.to_string(),
+        abbreviation: local.format("%Z").to_string(),
+        utc_offset: local.format("%:z").to_string(),
+        weekday: local.format("%A").to_string(),
+    })
+}
+
+impl Tool for DateTimeTool {
+    const NAME: &'static str = "date_time";
+
+    type Args = DateTimeArgs;
+    type Output = DateTimeOutput;
+    type Error = DateTimeError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "date_time".to_string(),
+            description: "Get the current date and time, or convert a timestamp to another timezone. Without a timestamp, returns the current time. Always use this instead of guessing today's date or working out timezone offsets yourself, since they depend on daylight saving time".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "timestamp": { "type": "string", "description": "The time to convert, in RFC 3339 (e.g., '2024-10-08T14:30:00Z') or as 'YYYY-MM-DD HH:MM' in from_timezone. Omit for the current time" },
+                    "from_timezone": { "type": "string", "description": "IANA timezone of a timestamp without a UTC offset (e.g., 'Asia/Tokyo'). Defaults to UTC" },
+                    "timezone": { "type": "string", "description": "IANA timezone to express the time in (e.g., 'America/New_York'). Defaults to UTC" },
+                },
+                "required": []
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        convert(&args, Utc::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(timestamp: Option<&str>, from: Option<&str>, to: Option<&str>) -> DateTimeArgs {
+        DateTimeArgs {
+            timestamp: timestamp.map(Into::into),
+            from_timezone: from.map(Into::into),
+            timezone: to.map(Into::into),
+        }
+    }
+
+    #[test]
+    fn test_current_time_and_conversions() {
+        let now = Utc.with_ymd_and_hms(2024, 7, 1, 12, 0, 0).unwrap();
+
+        assert_eq!(
+            convert(&args(None, None, Some("Europe/Paris")), now).unwrap(),
+            DateTimeOutput {
+                utc: "2024-07-01T12:00:00+00:00".into(),
+                local: "2024-07-01T14:00:00+02:00".into(),
+                timezone: "Europe/Paris".into(),
+                abbreviation: "CEST".into(),
+                utc_offset: "+02:00".into(),
+                weekday: "Monday".into(),
+            }
+        );
+
+        // A wall clock time in Tokyo, shown in New York in winter
+        let output = convert(
+            &args(Some("2024-12-25 09:00"), Some("Asia/Tokyo"), Some("America/New_York")),
+            now,
+        )
+        .unwrap();
+        assert_eq!(output.utc, "2024-12-25T00:00:00+00:00");
+        assert_eq!(output.local, "2024-12-24T19:00:00-05:00");
+        assert_eq!(output.abbreviation, "EST");
+
+        // An offset in the timestamp wins over from_timezone
+        let output = convert(&args(Some("2024-10-08T14:30:00+05:30"), Some("Asia/Tokyo"), None), now).unwrap();
+        assert_eq!(output.utc, "2024-10-08T09:00:00+00:00");
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let now = Utc::now();
+        assert_eq!(
+            convert(&args(None, None, Some("Mars/Olympus_Mons")), now),
+            Err(DateTimeError::UnknownTimezone("Mars/Olympus_Mons".into()))
+        );
+        assert!(matches!(
+            convert(&args(Some("next tuesday"), None, None), now),
+            Err(DateTimeError::InvalidTimestamp(_))
+        ));
+        // Clocks in Paris went from 02:00 to 03:00 on 31 March 2024, and back
+        // from 03:00 to 02:00 on 27 October
+        assert!(matches!(
+            convert(&args(Some("2024-03-31 02:30"), Some("Europe/Paris"), None), now),
+            Err(DateTimeError::NonexistentTime(_, _))
+        ));
+        assert!(matches!(
+            convert(&args(Some("2024-10-27 02:30"), Some("Europe/Paris"), None), now),
+            Err(DateTimeError::AmbiguousTime(_, _))
+        ));
+    }
+}
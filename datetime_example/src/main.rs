@@ -0,0 +1,37 @@
+mod datetime_tool;
+
+use crate::datetime_tool::DateTimeTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the DateTimeTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a helpful assistant. You don't know the current date or time: use the date_time tool whenever a question depends on them, and for every timezone conversion.")
+        .tool(DateTimeTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example.
+    // Rig answers with the output of the first tool call, so the example needs
+    // only one.
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What time is it in Tokyo right now?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
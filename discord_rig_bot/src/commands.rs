@@ -0,0 +1,238 @@
+// commands.rs
+
+use serenity::builder::{CreateApplicationCommands, CreateEmbed};
+use serenity::model::application::command::CommandOptionType;
+use serenity::model::Permissions;
+
+/// An option of a slash command
+pub struct OptionSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub kind: CommandOptionType,
+    pub required: bool,
+}
+
+/// A slash command. Both the registration in `ready` and the `/help` embed are
+/// built from these, so the help text always matches what Discord shows.
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub options: &'static [OptionSpec],
+    /// Members need these permissions to see and use the command. Such
+    /// commands aren't offered in direct messages, where there are none.
+    pub permissions: Option<Permissions>,
+}
+
+pub const COMMANDS: &[CommandSpec] = &[
+    CommandSpec {
+        name: "ask",
+        description: "Ask the bot a question",
+        options: &[OptionSpec {
+            name: "query",
+            description: "Your question for the bot",
+            kind: CommandOptionType::String,
+            required: true,
+        }],
+        permissions: None,
+    },
+    CommandSpec {
+        name: "docs",
+        description: "Search the knowledge base without asking the model",
+        options: &[
+            OptionSpec {
+                name: "query",
+                description: "What to search for",
+                kind: CommandOptionType::String,
+                required: true,
+            },
+            OptionSpec {
+                name: "k",
+                description: "Number of documents to show, from 1 to 10 (default 3)",
+                kind: CommandOptionType::Integer,
+                required: false,
+            },
+        ],
+        permissions: None,
+    },
+    CommandSpec {
+        name: "summarize",
+        description: "Summarize the recent messages in this channel",
+        options: &[OptionSpec {
+            name: "count",
+            description: "Number of recent messages to read, from 1 to 100 (default 50)",
+            kind: CommandOptionType::Integer,
+            required: false,
+        }],
+        permissions: None,
+    },
+    CommandSpec {
+        name: "price",
+        description: "Get the current USD price of a cryptocurrency",
+        options: &[OptionSpec {
+            name: "symbol",
+            description: "A ticker such as btc or a CoinGecko coin id such as bitcoin",
+            kind: CommandOptionType::String,
+            required: true,
+        }],
+        permissions: None,
+    },
+    CommandSpec {
+        name: "hello",
+        description: "Say hello to the bot",
+        options: &[],
+        permissions: None,
+    },
+    CommandSpec {
+        name: "reset",
+        description: "Clear the conversation history for this channel",
+        options: &[],
+        permissions: None,
+    },
+    CommandSpec {
+        name: "export",
+        description: "Download the conversation history for this channel as a markdown file",
+        options: &[],
+        permissions: None,
+    },
+    CommandSpec {
+        name: "reload",
+        description: "Rebuild the knowledge base from the documents directory",
+        options: &[],
+        permissions: Some(Permissions::MANAGE_GUILD),
+    },
+    CommandSpec {
+        name: "model",
+        description: "Switch the completion model (bot admin only)",
+        options: &[OptionSpec {
+            name: "name",
+            description: "An OpenAI model id such as gpt-4o-mini",
+            kind: CommandOptionType::String,
+            required: true,
+        }],
+        permissions: None,
+    },
+    CommandSpec {
+        name: "stats",
+        description: "Show usage statistics since the bot started",
+        options: &[],
+        permissions: None,
+    },
+    CommandSpec {
+        name: "help",
+        description: "List the available commands",
+        options: &[],
+        permissions: None,
+    },
+];
+
+/// Add every command in `COMMANDS` to a global command registration
+pub fn register(commands: &mut CreateApplicationCommands) -> &mut CreateApplicationCommands {
+    for spec in COMMANDS {
+        commands.create_application_command(|command| {
+            command.name(spec.name).description(spec.description);
+            for option_spec in spec.options {
+                command.create_option(|option| {
+                    option
+                        .name(option_spec.name)
+                        .description(option_spec.description)
+                        .kind(option_spec.kind)
+                        .required(option_spec.required)
+                });
+            }
+            if let Some(permissions) = spec.permissions {
+                command
+                    .default_member_permissions(permissions)
+                    .dm_permission(false);
+            }
+            command
+        });
+    }
+    commands
+}
+
+/// Whether a member with `member_permissions` may run the command `name`.
+/// Discord hides restricted commands from other members, but that is only a
+/// default a server can change, so the handler checks again. Outside a server
+/// there are no member permissions, so restricted commands are refused.
+pub fn permitted(name: &str, member_permissions: Option<Permissions>) -> bool {
+    match COMMANDS.iter().find(|spec| spec.name == name).and_then(|spec| spec.permissions) {
+        Some(required) => member_permissions.is_some_and(|granted| granted.contains(required)),
+        None => true,
+    }
+}
+
+/// Fill in the `/help` embed with one field per command
+pub fn help_embed(embed: &mut CreateEmbed) -> &mut CreateEmbed {
+    embed
+        .title("Commands")
+        .description("You can also mention me with a question.");
+    for spec in COMMANDS {
+        embed.field(usage(spec), help_text(spec), false);
+    }
+    embed
+}
+
+/// e.g. `/ask query:<query>`; optional options are wrapped in brackets
+fn usage(spec: &CommandSpec) -> String {
+    let mut usage = format!("/{}", spec.name);
+    for option in spec.options {
+        if option.required {
+            usage.push_str(&format!(" {}:<{}>", option.name, option.name));
+        } else {
+            usage.push_str(&format!(" [{}:<{}>]", option.name, option.name));
+        }
+    }
+    usage
+}
+
+fn help_text(spec: &CommandSpec) -> String {
+    let mut text = spec.description.to_string();
+    for option in spec.options {
+        text.push_str(&format!("\n• `{}`: {}", option.name, option.description));
+    }
+    if spec.permissions.is_some() {
+        text.push_str("\n(Server managers only)");
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_usage() {
+        let ask = COMMANDS.iter().find(|spec| spec.name == "ask").unwrap();
+        assert_eq!(usage(ask), "/ask query:<query>");
+
+        let optional = CommandSpec {
+            name: "search",
+            description: "",
+            options: &[OptionSpec {
+                name: "limit",
+                description: "",
+                kind: CommandOptionType::Integer,
+                required: false,
+            }],
+            permissions: None,
+        };
+        assert_eq!(usage(&optional), "/search [limit:<limit>]");
+    }
+
+    #[test]
+    fn test_permitted() {
+        assert!(permitted("ask", None));
+        assert!(permitted("reload", Some(Permissions::MANAGE_GUILD | Permissions::SEND_MESSAGES)));
+        assert!(!permitted("reload", Some(Permissions::SEND_MESSAGES)));
+        // Direct messages have no member permissions
+        assert!(!permitted("reload", None));
+    }
+
+    #[test]
+    fn test_command_names_are_unique() {
+        let mut names: Vec<_> = COMMANDS.iter().map(|spec| spec.name).collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(names.len(), COMMANDS.len());
+    }
+}
@@ -0,0 +1,176 @@
+// config.rs
+
+use rig::providers::openai;
+use serenity::model::id::UserId;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+// Questions each user may ask per minute unless `RIG_RATE_LIMIT` says otherwise
+const DEFAULT_RATE_LIMIT: u32 = 5;
+
+// Longest question, in characters, accepted unless `RIG_MAX_QUERY_LENGTH` says otherwise
+const DEFAULT_MAX_QUERY_LENGTH: usize = 4000;
+
+/// Tokens the preamble and retrieved documents may use together when
+/// `RIG_CONTEXT_TOKENS` is not set. Leaves plenty of the model's context window
+/// for the conversation history, the question and the answer.
+pub const DEFAULT_CONTEXT_TOKENS: usize = 8_000;
+
+/// Where the embeddings are cached when `RIG_EMBEDDINGS_CACHE` is not set
+const DEFAULT_EMBEDDINGS_CACHE: &str = "embeddings_cache.json";
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Missing environment variables: {}. Set them in the environment or in a .env file", .0.join(", "))]
+    Missing(Vec<&'static str>),
+    #[error("{name} must be {expected}, got {value:?}")]
+    Invalid {
+        name: &'static str,
+        value: String,
+        expected: &'static str,
+    },
+}
+
+/// Everything the bot reads from the environment. Loaded once at startup, so a
+/// missing token or a malformed setting stops the bot before it connects
+/// rather than when it is first needed.
+pub struct Config {
+    pub discord_token: String,
+    pub openai_api_key: String,
+    /// Completion model (`RIG_MODEL`)
+    pub model: String,
+    /// File to load the system prompt from (`RIG_PREAMBLE_PATH`)
+    pub preamble_path: Option<PathBuf>,
+    /// File to load the system prompt for direct messages from
+    /// (`RIG_DM_PREAMBLE_PATH`)
+    pub dm_preamble_path: Option<PathBuf>,
+    /// Send answers as embeds when they fit (`RIG_EMBED_RESPONSES`)
+    pub embed_responses: bool,
+    /// Questions each user may ask per minute (`RIG_RATE_LIMIT`)
+    pub rate_limit: u32,
+    /// Longest question accepted, in characters (`RIG_MAX_QUERY_LENGTH`)
+    pub max_query_length: usize,
+    /// The only user allowed to switch models with `/model` (`RIG_ADMIN_USER_ID`)
+    pub admin_user_id: Option<UserId>,
+    /// Where the document embeddings are cached (`RIG_EMBEDDINGS_CACHE`)
+    pub embeddings_cache: PathBuf,
+    /// Tokens the preamble and retrieved documents may use (`RIG_CONTEXT_TOKENS`)
+    pub context_tokens: usize,
+}
+
+impl Config {
+    /// Load the configuration from the process environment
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_vars(|name| std::env::var(name).ok())
+    }
+
+    /// Load the configuration from `var`, which looks up a variable by name.
+    /// Every required variable is checked before returning, so all the missing
+    /// ones are reported at once.
+    fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let mut missing = Vec::new();
+        let mut required = |name: &'static str| {
+            let value = var(name).filter(|value| !value.trim().is_empty());
+            if value.is_none() {
+                missing.push(name);
+            }
+            value.unwrap_or_default()
+        };
+        let discord_token = required("DISCORD_TOKEN");
+        let openai_api_key = required("OPENAI_API_KEY");
+        if !missing.is_empty() {
+            return Err(ConfigError::Missing(missing));
+        }
+
+        Ok(Self {
+            discord_token,
+            openai_api_key,
+            model: var("RIG_MODEL").unwrap_or_else(|| openai::GPT_4O.to_string()),
+            preamble_path: var("RIG_PREAMBLE_PATH").map(PathBuf::from),
+            dm_preamble_path: var("RIG_DM_PREAMBLE_PATH").map(PathBuf::from),
+            embed_responses: var("RIG_EMBED_RESPONSES").is_some_and(|value| value == "true"),
+            rate_limit: parse(&var, "RIG_RATE_LIMIT", "a number")?.unwrap_or(DEFAULT_RATE_LIMIT),
+            max_query_length: parse(&var, "RIG_MAX_QUERY_LENGTH", "a number")?
+                .unwrap_or(DEFAULT_MAX_QUERY_LENGTH),
+            admin_user_id: parse(&var, "RIG_ADMIN_USER_ID", "a Discord user id")?.map(UserId),
+            embeddings_cache: PathBuf::from(
+                var("RIG_EMBEDDINGS_CACHE").unwrap_or_else(|| DEFAULT_EMBEDDINGS_CACHE.to_string()),
+            ),
+            context_tokens: parse(&var, "RIG_CONTEXT_TOKENS", "a number")?
+                .unwrap_or(DEFAULT_CONTEXT_TOKENS),
+        })
+    }
+}
+
+/// Parse the optional variable `name`, described as `expected` if it is malformed
+fn parse<T: FromStr>(
+    var: impl Fn(&str) -> Option<String>,
+    name: &'static str,
+    expected: &'static str,
+) -> Result<Option<T>, ConfigError> {
+    var(name)
+        .map(|value| {
+            value.trim().parse().map_err(|_| ConfigError::Invalid {
+                name,
+                value,
+                expected,
+            })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn load(vars: &[(&str, &str)]) -> Result<Config, ConfigError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        Config::from_vars(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn test_defaults_and_overrides() {
+        let config = load(&[("DISCORD_TOKEN", "discord"), ("OPENAI_API_KEY", "sk-test")]).unwrap();
+        assert_eq!(config.model, openai::GPT_4O);
+        assert_eq!(config.rate_limit, DEFAULT_RATE_LIMIT);
+        assert_eq!(config.context_tokens, DEFAULT_CONTEXT_TOKENS);
+        assert_eq!(config.embeddings_cache, PathBuf::from(DEFAULT_EMBEDDINGS_CACHE));
+        assert!(config.admin_user_id.is_none());
+        assert!(!config.embed_responses);
+
+        let config = load(&[
+            ("DISCORD_TOKEN", "discord"),
+            ("OPENAI_API_KEY", "sk-test"),
+            ("RIG_MODEL", "gpt-4o-mini"),
+            ("RIG_RATE_LIMIT", "0"),
+            ("RIG_ADMIN_USER_ID", "80351110224678912"),
+            ("RIG_EMBED_RESPONSES", "true"),
+        ])
+        .unwrap();
+        assert_eq!(config.model, "gpt-4o-mini");
+        assert_eq!(config.rate_limit, 0);
+        assert_eq!(config.admin_user_id, Some(UserId(80351110224678912)));
+        assert!(config.embed_responses);
+    }
+
+    #[test]
+    fn test_missing_and_invalid_variables() {
+        assert_eq!(
+            load(&[("DISCORD_TOKEN", " ")]).err(),
+            Some(ConfigError::Missing(vec!["DISCORD_TOKEN", "OPENAI_API_KEY"]))
+        );
+
+        let error = load(&[
+            ("DISCORD_TOKEN", "discord"),
+            ("OPENAI_API_KEY", "sk-test"),
+            ("RIG_CONTEXT_TOKENS", "8k"),
+        ])
+        .err()
+        .unwrap();
+        assert_eq!(error.to_string(), "RIG_CONTEXT_TOKENS must be a number, got \"8k\"");
+    }
+}
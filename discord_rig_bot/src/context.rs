@@ -0,0 +1,135 @@
+// context.rs
+
+use rig::completion::Document;
+use rig::embeddings::DocumentEmbeddings;
+use std::collections::HashMap;
+
+/// Rough size of a token in English text. Good enough to stay under a budget
+/// without pulling in a tokenizer.
+const CHARS_PER_TOKEN: usize = 4;
+
+/// A document cut short by less than this is dropped instead, since a few
+/// lines of it are unlikely to help
+const MIN_TRUNCATED_TOKENS: usize = 100;
+
+/// Appended to a document that was cut to fit the budget
+const TRUNCATION_MARKER: &str = "\n[...truncated]";
+
+/// Estimate the number of tokens `text` will use
+pub fn estimate_tokens(text: &str) -> usize {
+    text.chars().count().div_ceil(CHARS_PER_TOKEN)
+}
+
+/// The retrieved documents that fit in the token budget
+pub struct FittedContext {
+    /// Highest-scoring first
+    pub documents: Vec<Document>,
+    /// Id of the document that was cut short to fit, if any
+    pub truncated: Option<String>,
+    /// Ids of the documents left out entirely
+    pub dropped: Vec<String>,
+}
+
+/// Keep the highest-scoring retrieved documents that fit in `budget` tokens.
+/// The first document that doesn't fit is truncated when enough of the budget
+/// is left for it to be useful; it and everything scoring lower is dropped
+/// otherwise.
+pub fn fit_to_budget(mut retrieved: Vec<(f64, DocumentEmbeddings)>, budget: usize) -> FittedContext {
+    retrieved.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut fitted = FittedContext {
+        documents: Vec::new(),
+        truncated: None,
+        dropped: Vec::new(),
+    };
+    let mut remaining = budget;
+
+    for (_, doc) in retrieved {
+        // Once a document has been cut short or dropped, the budget is used up
+        let full = fitted.truncated.is_some() || !fitted.dropped.is_empty();
+
+        // Formatted the way rig's dynamic context formats documents
+        let text = serde_json::to_string_pretty(&doc.document)
+            .unwrap_or_else(|_| doc.document.to_string());
+        let tokens = estimate_tokens(&text);
+
+        let text = if full {
+            None
+        } else if tokens <= remaining {
+            remaining -= tokens;
+            Some(text)
+        } else if remaining >= MIN_TRUNCATED_TOKENS {
+            let keep = (remaining - estimate_tokens(TRUNCATION_MARKER)) * CHARS_PER_TOKEN;
+            let mut text: String = text.chars().take(keep).collect();
+            text.push_str(TRUNCATION_MARKER);
+            fitted.truncated = Some(doc.id.clone());
+            Some(text)
+        } else {
+            None
+        };
+
+        match text {
+            Some(text) => fitted.documents.push(Document {
+                id: doc.id,
+                text,
+                additional_props: HashMap::new(),
+            }),
+            None => fitted.dropped.push(doc.id),
+        }
+    }
+
+    fitted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn retrieved(score: f64, id: &str, chars: usize) -> (f64, DocumentEmbeddings) {
+        // The JSON quotes add two characters
+        let document = Value::String("x".repeat(chars - 2));
+        (
+            score,
+            DocumentEmbeddings {
+                id: id.to_string(),
+                document,
+                embeddings: vec![],
+            },
+        )
+    }
+
+    #[test]
+    fn test_documents_within_budget_are_kept_best_first() {
+        let fitted = fit_to_budget(vec![retrieved(0.2, "faq", 400), retrieved(0.9, "guide", 400)], 1_000);
+
+        let ids: Vec<&str> = fitted.documents.iter().map(|doc| doc.id.as_str()).collect();
+        assert_eq!(ids, ["guide", "faq"]);
+        assert_eq!(fitted.truncated, None);
+        assert!(fitted.dropped.is_empty());
+    }
+
+    #[test]
+    fn test_lowest_scoring_documents_are_truncated_then_dropped() {
+        let fitted = fit_to_budget(
+            vec![
+                retrieved(0.5, "faq", 4_000),
+                retrieved(0.9, "guide", 2_000),
+                retrieved(0.1, "changelog", 400),
+            ],
+            1_000,
+        );
+
+        // 500 tokens for the guide, leaving 500 for the start of the FAQ
+        assert_eq!(fitted.documents.len(), 2);
+        assert_eq!(fitted.truncated.as_deref(), Some("faq"));
+        assert!(fitted.documents[1].text.ends_with(TRUNCATION_MARKER));
+        assert!(estimate_tokens(&fitted.documents[1].text) <= 500);
+        // Nothing lower-scoring is added once a document has been cut short
+        assert_eq!(fitted.dropped, ["changelog"]);
+
+        let fitted = fit_to_budget(vec![retrieved(0.9, "guide", 2_000)], 50);
+        assert!(fitted.documents.is_empty());
+        assert_eq!(fitted.dropped, ["guide"]);
+    }
+}
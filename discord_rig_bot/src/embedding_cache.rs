@@ -0,0 +1,102 @@
+// embedding_cache.rs
+
+use anyhow::{Context, Result};
+use rig::vector_store::in_memory_store::InMemoryVectorStore;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// What is written to the cache file: the vector store together with a hash
+/// of everything that went into it
+#[derive(Serialize, Deserialize)]
+struct CachedStore {
+    source_hash: String,
+    store: InMemoryVectorStore,
+}
+
+/// Hash the embedding model name and every `(id, content)` pair, so the cache
+/// is invalidated when a document is added, removed or edited, or when the
+/// model changes. Uses FNV-1a, which unlike `DefaultHasher` is stable across
+/// Rust versions.
+pub fn source_hash(model: &str, documents: &[(String, String)]) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    let mut feed = |bytes: &[u8]| {
+        for byte in bytes.iter().chain([0u8].iter()) {
+            hash = (hash ^ *byte as u64).wrapping_mul(0x100000001b3);
+        }
+    };
+
+    feed(model.as_bytes());
+    for (id, content) in documents {
+        feed(id.as_bytes());
+        feed(content.as_bytes());
+    }
+
+    format!("{:016x}", hash)
+}
+
+/// Load the vector store cached at `path` if it was built from sources with
+/// `source_hash`. A missing, stale or unreadable cache just means the
+/// embeddings have to be rebuilt, so it is never an error.
+pub fn load(path: &Path, source_hash: &str) -> Option<InMemoryVectorStore> {
+    let contents = fs::read_to_string(path).ok()?;
+    match serde_json::from_str::<CachedStore>(&contents) {
+        Ok(cached) if cached.source_hash == source_hash => {
+            info!("Loaded {} cached embeddings from {:?}", cached.store.len(), path);
+            Some(cached.store)
+        }
+        Ok(_) => {
+            info!("Documents changed since {:?} was written; rebuilding embeddings", path);
+            None
+        }
+        Err(e) => {
+            warn!("Ignoring unreadable embeddings cache {:?}: {}", path, e);
+            None
+        }
+    }
+}
+
+/// Write `store` to `path`, tagged with `source_hash`
+pub fn save(path: &Path, source_hash: &str, store: &InMemoryVectorStore) -> Result<()> {
+    let cached = CachedStore {
+        source_hash: source_hash.to_string(),
+        store: store.clone(),
+    };
+    let contents = serde_json::to_string(&cached)?;
+    fs::write(path, contents)
+        .with_context(|| format!("Failed to write embeddings cache: {:?}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn documents(content: &str) -> Vec<(String, String)> {
+        vec![("Rig_faq".to_string(), content.to_string())]
+    }
+
+    #[test]
+    fn test_source_hash_tracks_documents_and_model() {
+        let hash = source_hash("text-embedding-3-small", &documents("faq"));
+        assert_eq!(hash, source_hash("text-embedding-3-small", &documents("faq")));
+        assert_ne!(hash, source_hash("text-embedding-3-small", &documents("faq!")));
+        assert_ne!(hash, source_hash("text-embedding-3-large", &documents("faq")));
+        assert_ne!(hash, source_hash("text-embedding-3-small", &[]));
+    }
+
+    #[test]
+    fn test_cache_is_only_used_for_matching_sources() {
+        let path = std::env::temp_dir().join(format!("rig_cache_{}.json", std::process::id()));
+        save(&path, "abc", &InMemoryVectorStore::default()).unwrap();
+
+        assert!(load(&path, "abc").is_some());
+        assert!(load(&path, "def").is_none());
+
+        fs::write(&path, "not json").unwrap();
+        assert!(load(&path, "abc").is_none());
+
+        fs::remove_file(&path).unwrap();
+        assert!(load(&path, "abc").is_none());
+    }
+}
@@ -0,0 +1,182 @@
+// history.rs
+
+use rig::completion::Message;
+use serenity::model::id::ChannelId;
+use serenity::model::Timestamp;
+use std::collections::HashMap;
+use std::fmt::Write;
+use tokio::sync::Mutex;
+
+/// Number of messages (user and assistant) kept per channel. Older messages are
+/// dropped so the prompt doesn't grow without bound.
+const MAX_HISTORY_MESSAGES: usize = 20;
+
+/// A message in the history with who sent it and when, for `/export`
+#[derive(Clone)]
+struct Entry {
+    message: Message,
+    /// Display name of the user who asked; `None` for the bot's answers
+    author: Option<String>,
+    sent_at: Timestamp,
+}
+
+/// Per-channel conversation memory so follow-up questions keep their context.
+#[derive(Default)]
+pub struct ChannelHistory {
+    channels: Mutex<HashMap<ChannelId, Vec<Entry>>>,
+}
+
+/// The current time, rounded down to the second so exports stay readable
+pub fn now() -> Timestamp {
+    let now = Timestamp::now();
+    Timestamp::from_unix_timestamp(now.unix_timestamp()).unwrap_or(now)
+}
+
+/// Render a conversation as markdown, one section per message labelled with
+/// the speaker and time
+fn format_transcript(entries: &[Entry], bot_name: &str) -> String {
+    let mut transcript = String::from("# Conversation export\n");
+    for entry in entries {
+        let speaker = entry.author.as_deref().unwrap_or(bot_name);
+        let _ = write!(
+            transcript,
+            "\n## {} ({})\n\n{}\n",
+            speaker,
+            entry.sent_at,
+            entry.message.content.trim()
+        );
+    }
+    transcript
+}
+
+impl ChannelHistory {
+    /// Get a copy of the conversation so far in `channel`
+    pub async fn get(&self, channel: ChannelId) -> Vec<Message> {
+        self.channels
+            .lock()
+            .await
+            .get(&channel)
+            .map(|history| history.iter().map(|entry| entry.message.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Record a completed exchange in `channel`: `prompt`, asked by `author` at
+    /// `asked_at`, and the `response` to it, answered now
+    pub async fn record(
+        &self,
+        channel: ChannelId,
+        author: &str,
+        asked_at: Timestamp,
+        prompt: &str,
+        response: &str,
+    ) {
+        let mut channels = self.channels.lock().await;
+        let history = channels.entry(channel).or_default();
+
+        history.push(Entry {
+            message: Message {
+                role: "user".into(),
+                content: prompt.into(),
+            },
+            author: Some(author.to_string()),
+            sent_at: asked_at,
+        });
+        history.push(Entry {
+            message: Message {
+                role: "assistant".into(),
+                content: response.into(),
+            },
+            author: None,
+            sent_at: now(),
+        });
+
+        if history.len() > MAX_HISTORY_MESSAGES {
+            let excess = history.len() - MAX_HISTORY_MESSAGES;
+            history.drain(..excess);
+        }
+    }
+
+    /// The conversation in `channel` as a markdown transcript, with the bot's
+    /// messages labelled `bot_name`. Returns `None` if there is no history.
+    pub async fn export(&self, channel: ChannelId, bot_name: &str) -> Option<String> {
+        let channels = self.channels.lock().await;
+        let history = channels.get(&channel).filter(|history| !history.is_empty())?;
+        Some(format_transcript(history, bot_name))
+    }
+
+    /// Forget the conversation in `channel`. Returns `false` if there was none.
+    pub async fn clear(&self, channel: ChannelId) -> bool {
+        self.channels
+            .lock()
+            .await
+            .remove(&channel)
+            .is_some_and(|history| !history.is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_and_clear() {
+        let history = ChannelHistory::default();
+        let channel = ChannelId(1);
+
+        assert!(!history.clear(channel).await);
+        assert!(history.export(channel, "Rig Bot").await.is_none());
+
+        history.record(channel, "alice", now(), "What is Rig?", "A Rust library.").await;
+        assert_eq!(history.get(channel).await.len(), 2);
+        assert!(history.get(ChannelId(2)).await.is_empty());
+        assert!(history.export(channel, "Rig Bot").await.is_some());
+
+        assert!(history.clear(channel).await);
+        assert!(history.get(channel).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_history_is_capped() {
+        let history = ChannelHistory::default();
+        let channel = ChannelId(1);
+
+        for i in 0..MAX_HISTORY_MESSAGES {
+            history.record(channel, "alice", now(), &format!("q{i}"), &format!("a{i}")).await;
+        }
+
+        let messages = history.get(channel).await;
+        assert_eq!(messages.len(), MAX_HISTORY_MESSAGES);
+        assert_eq!(messages.last().unwrap().content, format!("a{}", MAX_HISTORY_MESSAGES - 1));
+    }
+
+    #[test]
+    fn test_format_transcript() {
+        let asked_at = Timestamp::from_unix_timestamp(1_728_388_800).unwrap();
+        let answered_at = Timestamp::from_unix_timestamp(1_728_388_805).unwrap();
+        let entries = [
+            Entry {
+                message: Message {
+                    role: "user".into(),
+                    content: "What is Rig?".into(),
+                },
+                author: Some("alice".into()),
+                sent_at: asked_at,
+            },
+            Entry {
+                message: Message {
+                    role: "assistant".into(),
+                    content: "A Rust library.\n".into(),
+                },
+                author: None,
+                sent_at: answered_at,
+            },
+        ];
+
+        assert_eq!(
+            format_transcript(&entries, "Rig Bot"),
+            "# Conversation export\n\
+             \n## alice (2024-10-08T12:00:00Z)\n\nWhat is Rig?\n\
+             \n## Rig Bot (2024-10-08T12:00:05Z)\n\nA Rust library.\n"
+        );
+    }
+}
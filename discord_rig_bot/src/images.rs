@@ -0,0 +1,110 @@
+// images.rs
+
+use base64::Engine as _;
+use serde_json::{json, Value};
+use serenity::model::channel::Attachment;
+use tracing::warn;
+
+/// Image formats OpenAI's vision models accept
+const SUPPORTED_IMAGE_TYPES: &[&str] = &["image/png", "image/jpeg", "image/gif", "image/webp"];
+
+/// OpenAI rejects images larger than this
+const MAX_IMAGE_SIZE: u64 = 20 * 1024 * 1024;
+
+/// Most images from a single message that are sent to the model
+const MAX_IMAGES: usize = 4;
+
+/// Explains which attachments were ignored
+pub fn skipped_message(skipped: &[String]) -> String {
+    format!(
+        "I can only look at up to {} PNG, JPEG, GIF or WebP images under 20 MB per message, so I skipped: {}",
+        MAX_IMAGES,
+        skipped.join(", ")
+    )
+}
+
+fn is_supported_image(content_type: Option<&str>, size: u64) -> bool {
+    let mime = content_type
+        .and_then(|content_type| content_type.split(';').next())
+        .map(str::trim);
+    mime.is_some_and(|mime| SUPPORTED_IMAGE_TYPES.contains(&mime)) && size <= MAX_IMAGE_SIZE
+}
+
+/// Download the image attachments of a message as data URLs the model can
+/// read. Returns the images and the file names of the attachments that were
+/// skipped because of their type, their size or the image limit, or because
+/// they couldn't be downloaded.
+pub async fn load_images(attachments: &[Attachment]) -> (Vec<String>, Vec<String>) {
+    let mut images = Vec::new();
+    let mut skipped = Vec::new();
+
+    for attachment in attachments {
+        let content_type = attachment.content_type.as_deref();
+        if images.len() >= MAX_IMAGES || !is_supported_image(content_type, attachment.size) {
+            skipped.push(attachment.filename.clone());
+            continue;
+        }
+        match attachment.download().await {
+            Ok(bytes) => images.push(data_url(content_type.unwrap_or_default(), &bytes)),
+            Err(why) => {
+                warn!("Error downloading attachment {}: {:?}", attachment.filename, why);
+                skipped.push(attachment.filename.clone());
+            }
+        }
+    }
+
+    (images, skipped)
+}
+
+fn data_url(content_type: &str, bytes: &[u8]) -> String {
+    format!(
+        "data:{};base64,{}",
+        content_type,
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    )
+}
+
+/// The content of the user's message in a chat completion request: plain text,
+/// or text followed by the images when there are any
+pub fn user_content(text: &str, images: &[String]) -> Value {
+    if images.is_empty() {
+        return json!(text);
+    }
+
+    let mut parts = vec![json!({ "type": "text", "text": text })];
+    parts.extend(
+        images
+            .iter()
+            .map(|url| json!({ "type": "image_url", "image_url": { "url": url } })),
+    );
+    json!(parts)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_supported_image() {
+        assert!(is_supported_image(Some("image/png"), 1024));
+        assert!(is_supported_image(Some("image/jpeg; charset=binary"), 1024));
+        assert!(!is_supported_image(Some("image/png"), MAX_IMAGE_SIZE + 1));
+        assert!(!is_supported_image(Some("application/pdf"), 1024));
+        assert!(!is_supported_image(None, 1024));
+    }
+
+    #[test]
+    fn test_user_content() {
+        assert_eq!(user_content("Hi", &[]), json!("Hi"));
+
+        let image = data_url("image/png", b"png");
+        assert_eq!(image, "data:image/png;base64,cG5n");
+        assert_eq!(
+            user_content("What is this?", &[image]),
+            json!([
+                { "type": "text", "text": "What is this?" },
+                { "type": "image_url", "image_url": { "url": "data:image/png;base64,cG5n" } },
+            ])
+        );
+    }
+}
@@ -0,0 +1,64 @@
+// in_progress.rs
+
+use serenity::model::id::UserId;
+use std::collections::HashSet;
+use std::sync::Mutex;
+
+/// Tracks which users have a question being answered, so a second question
+/// sent before the first is answered can be turned away instead of producing
+/// two overlapping streamed replies.
+///
+/// Uses a std mutex because the flag is cleared in `Drop`, which can't await.
+/// The lock is never held across an await.
+#[derive(Default)]
+pub struct InProgress {
+    users: Mutex<HashSet<UserId>>,
+}
+
+/// Marks a user's question as being answered until it is dropped, whether
+/// the answer was sent, failed or the task panicked
+pub struct InProgressGuard<'a> {
+    in_progress: &'a InProgress,
+    user: UserId,
+}
+
+impl InProgress {
+    /// Mark `user` as having a question in progress. Returns `None` if they
+    /// already have one.
+    pub fn start(&self, user: UserId) -> Option<InProgressGuard<'_>> {
+        let inserted = self.users.lock().unwrap_or_else(|e| e.into_inner()).insert(user);
+        // Only a guard that inserted the user may remove them
+        inserted.then(|| InProgressGuard {
+            in_progress: self,
+            user,
+        })
+    }
+}
+
+impl Drop for InProgressGuard<'_> {
+    fn drop(&mut self) {
+        let mut users = self.in_progress.users.lock().unwrap_or_else(|e| e.into_inner());
+        users.remove(&self.user);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one_question_per_user_until_finished() {
+        let in_progress = InProgress::default();
+        let alice = UserId(1);
+        let bob = UserId(2);
+
+        let first = in_progress.start(alice);
+        assert!(first.is_some());
+        assert!(in_progress.start(alice).is_none());
+        // Other users aren't affected
+        assert!(in_progress.start(bob).is_some());
+
+        drop(first);
+        assert!(in_progress.start(alice).is_some());
+    }
+}
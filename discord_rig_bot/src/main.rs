@@ -1,21 +1,75 @@
 // main.rs
 
+mod commands;
+mod config;
+mod context;
+mod embedding_cache;
+mod history;
+mod images;
+mod in_progress;
+mod message_utils;
+mod openai_stream;
+mod reply;
+mod retry;
+mod rate_limit;
 mod rig_agent;
+mod stats;
+mod summary;
+mod tools;
 
 use anyhow::Result;
 use serenity::async_trait;
 use serenity::model::application::command::Command;
 use serenity::model::application::interaction::{Interaction, InteractionResponseType};
 use serenity::model::gateway::Ready;
-use serenity::model::channel::Message;
+use serenity::model::channel::{AttachmentType, Message};
+use serenity::model::id::{ChannelId, UserId};
 use serenity::prelude::*;
-use serenity::model::application::command::CommandOptionType;
-use std::env;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, RwLock};
 use tracing::{error, info, debug};
+use config::Config;
 use rig_agent::RigAgent;
+use history::ChannelHistory;
+use rate_limit::RateLimiter;
+use in_progress::{InProgress, InProgressGuard};
+use stats::Stats;
+use summary::ChannelMessage;
+use message_utils::{chunk_message, fits_in_embed, snippet, truncate_chars, DISCORD_MESSAGE_LIMIT, EMBED_TITLE_LIMIT};
+use serenity::builder::CreateEmbed;
+use reply::ReplyTarget;
+use rig::embeddings::DocumentEmbeddings;
+use rig::tool::Tool;
+use serde_json::Value;
+use tools::{CoinPrice, CoinPriceArgs};
 use dotenv::dotenv;
 
+// Discord rate limits message edits, so streamed output is flushed at most this often
+const EDIT_INTERVAL: Duration = Duration::from_secs(1);
+
+// Discord shows the typing indicator for about 10 seconds per broadcast
+const TYPING_INTERVAL: Duration = Duration::from_secs(8);
+
+// `RIG_RATE_LIMIT` questions are allowed per user in each window
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60);
+
+// Documents `/docs` shows unless its `k` option says otherwise, and the most it
+// shows, which keeps the reply within a single message
+const DEFAULT_DOCS_RESULTS: u64 = 3;
+const MAX_DOCS_RESULTS: u64 = 10;
+
+// Characters of each document shown by `/docs`
+const DOCS_SNIPPET_LENGTH: usize = 120;
+
+// Messages `/summarize` reads unless its `count` option says otherwise, and the
+// most it reads, which is as many as Discord returns for one request
+const DEFAULT_SUMMARY_MESSAGES: u64 = 50;
+const MAX_SUMMARY_MESSAGES: u64 = 100;
+
+// How long shutdown waits for answers that are still being generated
+const SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(30);
+
 // Define a key for storing the bot's user ID in the TypeMap
 struct BotUserId;
 
@@ -25,6 +79,230 @@ impl TypeMapKey for BotUserId {
 
 struct Handler {
     rig_agent: Arc<RigAgent>,
+    history: ChannelHistory,
+    /// Held for reading by every answer in progress; shutdown takes the write
+    /// lock to wait until they have all been sent.
+    in_flight: Arc<RwLock<()>>,
+    /// Send answers as embeds when they fit (`RIG_EMBED_RESPONSES`)
+    embed_responses: bool,
+    rate_limiter: RateLimiter,
+    /// Users with a question being answered
+    in_progress: InProgress,
+    /// Longest question accepted, in characters (`RIG_MAX_QUERY_LENGTH`)
+    max_query_length: usize,
+    stats: Stats,
+    /// The only user allowed to switch models with `/model` (`RIG_ADMIN_USER_ID`)
+    admin_user_id: Option<UserId>,
+}
+
+/// The reply sent instead of an answer when a user is over the rate limit
+fn slow_down_message(wait: Duration) -> String {
+    format!(
+        "Slow down! You're asking questions too quickly. Try again in {} seconds.",
+        wait.as_secs().max(1)
+    )
+}
+
+/// The reply sent instead of an answer when a user asks again before their
+/// previous question has been answered
+const STILL_WORKING_MESSAGE: &str =
+    "I'm still working on your last question. Please wait for that answer before asking another.";
+
+/// The `/docs` reply: each matching document's id and the start of its text,
+/// best match first
+fn docs_message(documents: &[DocumentEmbeddings]) -> String {
+    if documents.is_empty() {
+        return "The knowledge base is empty.".to_string();
+    }
+    let mut message = format!("Top {} matching documents:", documents.len());
+    for (rank, document) in documents.iter().enumerate() {
+        let text = match &document.document {
+            Value::String(text) => text.clone(),
+            other => other.to_string(),
+        };
+        message.push_str(&format!(
+            "\n\n**{}.** `{}`\n> {}",
+            rank + 1,
+            document.id,
+            snippet(&text, DOCS_SNIPPET_LENGTH)
+        ));
+    }
+    message
+}
+
+/// The `/summarize` reply: the summary, with a note when older messages had to
+/// be left out
+fn summary_message(summary: &rig_agent::Summary) -> String {
+    let mut message = format!(
+        "**Summary of the last {} messages:**\n\n{}",
+        summary.messages,
+        summary.response.trim()
+    );
+    if summary.omitted > 0 {
+        message.push_str(&format!(
+            "\n\n_{} older messages were left out to fit the model's context window._",
+            summary.omitted
+        ));
+    }
+    message
+}
+
+impl Handler {
+    /// Fetch the last `count` messages in `channel_id` and summarize them.
+    /// Returns the reply to send.
+    async fn summarize(&self, ctx: &Context, channel_id: ChannelId, count: u64) -> String {
+        let messages = match channel_id
+            .messages(&ctx.http, |retriever| retriever.limit(count))
+            .await
+        {
+            Ok(messages) => messages,
+            Err(why) => {
+                error!("Error fetching channel messages: {:?}", why);
+                return "I couldn't read this channel's messages. I need the Read Message History permission here.".to_string();
+            }
+        };
+
+        // Mentions are resolved to names so the model doesn't see raw ids
+        let messages: Vec<ChannelMessage> = messages
+            .iter()
+            .map(|msg| ChannelMessage {
+                author: msg.author.name.clone(),
+                bot: msg.author.bot,
+                content: msg.content_safe(&ctx.cache),
+                sent_at: msg.timestamp,
+            })
+            .collect();
+
+        match self.rig_agent.summarize(&messages).await {
+            Ok(Some(summary)) => summary_message(&summary),
+            Ok(None) => "There are no recent messages to summarize.".to_string(),
+            Err(e) => {
+                error!("Error summarizing messages: {:?}", e);
+                format!("Error summarizing messages: {:?}", e)
+            }
+        }
+    }
+
+    /// Check a question from `user` before it is sent to the agent. Returns the
+    /// reply to send instead if it is rejected, or a guard that marks the
+    /// user's question as in progress until it is dropped.
+    ///
+    /// The length and the question in progress are checked first so neither
+    /// an oversized question nor a duplicate counts against the rate limit.
+    async fn reject(&self, user: UserId, query: &str) -> Result<InProgressGuard<'_>, String> {
+        let length = query.chars().count();
+        if length > self.max_query_length {
+            return Err(format!(
+                "That question is too long ({} characters). Please keep it under {} characters.",
+                length, self.max_query_length
+            ));
+        }
+        let in_progress = self
+            .in_progress
+            .start(user)
+            .ok_or_else(|| STILL_WORKING_MESSAGE.to_string())?;
+        self.rate_limiter.check(user).await.map_err(slow_down_message)?;
+        Ok(in_progress)
+    }
+
+    /// Run a query through the agent and stream the answer into `target`.
+    /// Both the `/ask` command and mentions go through here so each request
+    /// results in exactly one LLM call.
+    ///
+    /// The placeholder is edited with the text received so far at most once
+    /// per `EDIT_INTERVAL`, then replaced with the complete response.
+    /// `author` is the name of the user who asked, recorded in the history,
+    /// and `private` is set when they asked in a direct message.
+    async fn answer(
+        &self,
+        ctx: &Context,
+        author: &str,
+        private: bool,
+        query: &str,
+        images: Vec<String>,
+        mut target: ReplyTarget,
+    ) {
+        let _in_flight = self.in_flight.read().await;
+        let asked_at = history::now();
+        let channel_id = target.channel_id();
+        let chat_history = self.history.get(channel_id).await;
+
+        // Keep "Bot is typing..." visible until the answer is complete
+        let typing = tokio::spawn({
+            let http = Arc::clone(&ctx.http);
+            async move {
+                loop {
+                    if let Err(why) = channel_id.broadcast_typing(&http).await {
+                        debug!("Error broadcasting typing: {:?}", why);
+                    }
+                    tokio::time::sleep(TYPING_INTERVAL).await;
+                }
+            }
+        });
+
+        let started = Instant::now();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let agent = Arc::clone(&self.rig_agent);
+        let prompt = query.to_string();
+        let completion = tokio::spawn(async move {
+            agent.process_message_streaming(&prompt, chat_history, &images, private, tx).await
+        });
+
+        let mut partial = String::new();
+        let mut last_edit = Instant::now();
+        while let Some(text) = rx.recv().await {
+            partial.push_str(&text);
+            if last_edit.elapsed() >= EDIT_INTERVAL {
+                if let Some(preview) = chunk_message(&partial, DISCORD_MESSAGE_LIMIT).first() {
+                    if let Err(why) = target.edit(ctx, preview).await {
+                        error!("Error editing message: {:?}", why);
+                    }
+                }
+                last_edit = Instant::now();
+            }
+        }
+
+        let result = completion.await;
+        typing.abort();
+
+        let response = match result {
+            Ok(Ok(answer)) => {
+                self.stats.record(started.elapsed(), answer.tool_calls).await;
+                // The footer is left out of the history so the model doesn't imitate it
+                self.history
+                    .record(channel_id, author, asked_at, query, &answer.response)
+                    .await;
+
+                if self.embed_responses && fits_in_embed(&answer.response) {
+                    let mut embed = CreateEmbed::default();
+                    embed
+                        .title(truncate_chars(query, EMBED_TITLE_LIMIT))
+                        .description(&answer.response)
+                        .footer(|footer| footer.text(format!("Model: {}", answer.model)));
+                    if !answer.sources.is_empty() {
+                        embed.field("Sources", answer.sources.join(", "), false);
+                    }
+                    match target.edit_embed(ctx, embed).await {
+                        Ok(()) => return,
+                        Err(why) => error!("Error sending embed, falling back to text: {:?}", why),
+                    }
+                }
+                answer.with_sources()
+            }
+            Ok(Err(e)) => {
+                error!("Error processing request: {:?}", e);
+                format!("Error processing request: {:?}", e)
+            }
+            Err(e) => {
+                error!("Completion task failed: {:?}", e);
+                "Error processing request".to_string()
+            }
+        };
+
+        if let Err(why) = target.finish(ctx, &response).await {
+            error!("Error sending response: {:?}", why);
+        }
+    }
 }
 
 #[async_trait]
@@ -33,27 +311,75 @@ impl EventHandler for Handler {
         debug!("Received an interaction");
         if let Interaction::ApplicationCommand(command) = interaction {
             debug!("Received command: {}", command.data.name);
+
+            let query = command
+                .data
+                .options
+                .first()
+                .and_then(|opt| opt.value.as_ref())
+                .and_then(|v| v.as_str())
+                .unwrap_or("What would you like to ask?")
+                .to_string();
+
+            // Checked before responding so a rejected question never reaches
+            // the agent. An accepted question stays in progress until this
+            // handler returns.
+            let member_permissions = command.member.as_ref().and_then(|member| member.permissions);
+            let (rejection, _in_progress) = match command.data.name.as_str() {
+                name if !commands::permitted(name, member_permissions) => {
+                    (Some("Only server managers can use this command.".to_string()), None)
+                }
+                "ask" | "docs" | "summarize" => match self.reject(command.user.id, &query).await {
+                    Ok(in_progress) => (None, Some(in_progress)),
+                    Err(rejection) => (Some(rejection), None),
+                },
+                "model" if self.admin_user_id != Some(command.user.id) => {
+                    (Some("Only the bot admin can switch models.".to_string()), None)
+                }
+                _ => (None, None),
+            };
+
+            // Built before responding so the transcript can be attached to the response
+            let export = match command.data.name.as_str() {
+                "export" => {
+                    let bot_name = ctx.cache.current_user().name;
+                    self.history.export(command.channel_id, &bot_name).await
+                }
+                _ => None,
+            };
+
             let content = match command.data.name.as_str() {
                 "hello" => "Hello! I'm your helpful Rust and Rig-powered assistant. How can I assist you today?".to_string(),
-                "ask" => {
-                    let query = command
-                        .data
-                        .options
-                        .get(0)
-                        .and_then(|opt| opt.value.as_ref())
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("What would you like to ask?");
-                    debug!("Query: {}", query);
-                    match self.rig_agent.process_message(query).await {
-                        Ok(response) => response,
-                        Err(e) => {
-                            error!("Error processing request: {:?}", e);
-                            format!("Error processing request: {:?}", e)
-                        }
+                "ask" => rejection
+                    .clone()
+                    .unwrap_or_else(|| "Thinking...".to_string()),
+                "docs" => rejection
+                    .clone()
+                    .unwrap_or_else(|| "Searching the knowledge base...".to_string()),
+                "summarize" => rejection
+                    .clone()
+                    .unwrap_or_else(|| "Reading the recent messages...".to_string()),
+                "price" => "Looking up the price...".to_string(),
+                "reload" => rejection
+                    .clone()
+                    .unwrap_or_else(|| "Reloading the knowledge base...".to_string()),
+                "model" => rejection
+                    .clone()
+                    .unwrap_or_else(|| format!("Switching to {}...", query)),
+                "reset" => {
+                    if self.history.clear(command.channel_id).await {
+                        "Conversation history cleared. Let's start fresh!".to_string()
+                    } else {
+                        "There's no conversation history to clear yet.".to_string()
                     }
                 }
+                "export" if export.is_some() => "Here's the conversation history for this channel.".to_string(),
+                "export" => "There's no conversation history to export yet.".to_string(),
+                "stats" => self.stats.summary().await,
+                "help" => "Here's what I can do:".to_string(),
                 _ => "Not implemented :(".to_string(),
             };
+            let is_help = command.data.name == "help";
 
             debug!("Sending response: {}", content);
 
@@ -61,20 +387,160 @@ impl EventHandler for Handler {
                 .create_interaction_response(&ctx.http, |response| {
                     response
                         .kind(InteractionResponseType::ChannelMessageWithSource)
-                        .interaction_response_data(|message| message.content(content))
+                        .interaction_response_data(|message| {
+                            message.content(content);
+                            if is_help {
+                                message.embed(commands::help_embed);
+                            }
+                            if let Some(transcript) = export {
+                                message.add_file(AttachmentType::Bytes {
+                                    data: transcript.into_bytes().into(),
+                                    filename: format!("conversation-{}.md", command.channel_id),
+                                });
+                            }
+                            message
+                        })
                 })
                 .await
             {
                 error!("Cannot respond to slash command: {}", why);
-            } else {
-                debug!("Response sent successfully");
+                return;
+            }
+
+            match command.data.name.as_str() {
+                "ask" if rejection.is_none() => {
+                    debug!("Query: {}", query);
+                    let author = command.user.name.clone();
+                    let private = command.guild_id.is_none();
+                    self.answer(&ctx, &author, private, &query, Vec::new(), ReplyTarget::Interaction(Box::new(command))).await;
+                }
+                // Only the query is embedded: no completion, so the bot shows
+                // exactly what retrieval finds for it
+                "docs" if rejection.is_none() => {
+                    let k = command
+                        .data
+                        .options
+                        .iter()
+                        .find(|opt| opt.name == "k")
+                        .and_then(|opt| opt.value.as_ref())
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(DEFAULT_DOCS_RESULTS)
+                        .clamp(1, MAX_DOCS_RESULTS);
+                    let result = match self.rig_agent.search_documents(&query, k as usize).await {
+                        Ok(documents) => docs_message(&documents),
+                        Err(e) => {
+                            error!("Error searching knowledge base: {:?}", e);
+                            format!("Error searching knowledge base: {:?}", e)
+                        }
+                    };
+                    let result = truncate_chars(&result, DISCORD_MESSAGE_LIMIT);
+                    if let Err(why) = command
+                        .edit_original_interaction_response(&ctx.http, |response| {
+                            response.content(result)
+                        })
+                        .await
+                    {
+                        error!("Cannot edit slash command response: {}", why);
+                    }
+                }
+                // Bot messages, including earlier answers, are left out so
+                // the summary covers what people said
+                "summarize" if rejection.is_none() => {
+                    let count = command
+                        .data
+                        .options
+                        .iter()
+                        .find(|opt| opt.name == "count")
+                        .and_then(|opt| opt.value.as_ref())
+                        .and_then(|v| v.as_u64())
+                        .unwrap_or(DEFAULT_SUMMARY_MESSAGES)
+                        .clamp(1, MAX_SUMMARY_MESSAGES);
+                    let _in_flight = self.in_flight.read().await;
+                    let result = self.summarize(&ctx, command.channel_id, count).await;
+                    let mut target = ReplyTarget::Interaction(Box::new(command));
+                    if let Err(why) = target.finish(&ctx, &result).await {
+                        error!("Error sending summary: {:?}", why);
+                    }
+                }
+                // Calls the tool directly: no LLM round trip, so the answer is
+                // fast, free and always in the same format
+                "price" => {
+                    let args = CoinPriceArgs {
+                        coin: query.clone(),
+                        currency: None,
+                    };
+                    let result = match CoinPrice.call(args).await {
+                        Ok(quote) => quote.to_string(),
+                        Err(e) => {
+                            debug!("Price lookup failed: {:?}", e);
+                            e.to_string()
+                        }
+                    };
+                    if let Err(why) = command
+                        .edit_original_interaction_response(&ctx.http, |response| {
+                            response.content(result)
+                        })
+                        .await
+                    {
+                        error!("Cannot edit slash command response: {}", why);
+                    }
+                }
+                "reload" if rejection.is_none() => {
+                    let result = match self.rig_agent.reload().await {
+                        Ok(()) => "Knowledge base reloaded.".to_string(),
+                        Err(e) => {
+                            error!("Error reloading knowledge base: {:?}", e);
+                            format!("Error reloading knowledge base: {}", e)
+                        }
+                    };
+                    if let Err(why) = command
+                        .edit_original_interaction_response(&ctx.http, |response| {
+                            response.content(result)
+                        })
+                        .await
+                    {
+                        error!("Cannot edit slash command response: {}", why);
+                    }
+                }
+                "model" if rejection.is_none() => {
+                    let result = match self.rig_agent.set_model(&query).await {
+                        Ok(previous) => format!("Now using {} (was {}).", query, previous),
+                        Err(e) => {
+                            error!("Error switching model: {:?}", e);
+                            format!("Could not switch to {}: {}", query, e)
+                        }
+                    };
+                    if let Err(why) = command
+                        .edit_original_interaction_response(&ctx.http, |response| {
+                            response.content(result)
+                        })
+                        .await
+                    {
+                        error!("Cannot edit slash command response: {}", why);
+                    }
+                }
+                _ => {}
             }
+
+            debug!("Response sent successfully");
         }
     }
 
     async fn message(&self, ctx: Context, msg: Message) {
-        if msg.mentions_me(&ctx.http).await.unwrap_or(false) {
-            debug!("Bot mentioned in message: {}", msg.content);
+        // Never respond to bots (including ourselves) to avoid reply loops
+        if msg.author.bot {
+            return;
+        }
+
+        // Every direct message is addressed to the bot; in a server it has to
+        // be mentioned
+        let private = msg.guild_id.is_none();
+        if private && msg.content.trim().is_empty() && msg.attachments.is_empty() {
+            return;
+        }
+
+        if private || msg.mentions_me(&ctx.http).await.unwrap_or(false) {
+            debug!("Message for the bot: {}", msg.content);
 
             let bot_id = {
                 let data = ctx.data.read().await;
@@ -83,26 +549,36 @@ impl EventHandler for Handler {
 
             if let Some(bot_id) = bot_id {
                 let mention = format!("<@{}>", bot_id);
-                let content = msg.content.replace(&mention, "").trim().to_string();
+                let mut content = msg.content.replace(&mention, "").trim().to_string();
 
                 debug!("Processed content after removing mention: {}", content);
 
-                match self.rig_agent.process_message(&content).await {
-                    Ok(response) => {
-                        if let Err(why) = msg.channel_id.say(&ctx.http, response).await {
+                let _in_progress = match self.reject(msg.author.id, &content).await {
+                    Ok(in_progress) => in_progress,
+                    Err(rejection) => {
+                        if let Err(why) = msg.reply(&ctx.http, rejection).await {
                             error!("Error sending message: {:?}", why);
                         }
+                        return;
                     }
-                    Err(e) => {
-                        error!("Error processing message: {:?}", e);
-                        if let Err(why) = msg
-                            .channel_id
-                            .say(&ctx.http, format!("Error processing message: {:?}", e))
-                            .await
-                        {
-                            error!("Error sending error message: {:?}", why);
-                        }
+                };
+
+                // Screenshots and other images are passed to the model with the question
+                let (images, skipped) = images::load_images(&msg.attachments).await;
+                if !skipped.is_empty() {
+                    if let Err(why) = msg.reply(&ctx.http, images::skipped_message(&skipped)).await {
+                        error!("Error sending message: {:?}", why);
+                    }
+                }
+                if content.is_empty() && !images.is_empty() {
+                    content = "What's in this image?".to_string();
+                }
+
+                match msg.channel_id.say(&ctx.http, "Thinking...").await {
+                    Ok(placeholder) => {
+                        self.answer(&ctx, &msg.author.name, private, &content, images, ReplyTarget::Message(Box::new(placeholder))).await
                     }
+                    Err(why) => error!("Error sending message: {:?}", why),
                 }
             } else {
                 error!("Bot user ID not found in TypeMap");
@@ -118,27 +594,7 @@ impl EventHandler for Handler {
             data.insert::<BotUserId>(ready.user.id);
         }
 
-        let commands = Command::set_global_application_commands(&ctx.http, |commands| {
-            commands
-                .create_application_command(|command| {
-                    command
-                        .name("hello")
-                        .description("Say hello to the bot")
-                })
-                .create_application_command(|command| {
-                    command
-                        .name("ask")
-                        .description("Ask the bot a question")
-                        .create_option(|option| {
-                            option
-                                .name("query")
-                                .description("Your question for the bot")
-                                .kind(CommandOptionType::String)
-                                .required(true)
-                        })
-                })
-        })
-        .await;
+        let commands = Command::set_global_application_commands(&ctx.http, commands::register).await;
 
         println!("Created the following global commands: {:#?}", commands);
     }
@@ -152,24 +608,54 @@ async fn main() -> Result<()> {
         .with_max_level(tracing::Level::DEBUG)
         .init();
 
-    let token = env::var("DISCORD_TOKEN").expect("Expected DISCORD_TOKEN in environment");
+    // Every setting is read and checked here, before connecting to Discord
+    let config = Config::from_env()?;
 
-    let rig_agent = Arc::new(RigAgent::new().await?);
+    let rig_agent = Arc::new(RigAgent::new(&config).await?);
 
     let intents = GatewayIntents::GUILD_MESSAGES
         | GatewayIntents::DIRECT_MESSAGES
         | GatewayIntents::MESSAGE_CONTENT;
 
-    let mut client = Client::builder(&token, intents)
+    let in_flight = Arc::new(RwLock::new(()));
+
+    let mut client = Client::builder(&config.discord_token, intents)
         .event_handler(Handler {
             rig_agent: Arc::clone(&rig_agent),
+            history: ChannelHistory::default(),
+            in_flight: Arc::clone(&in_flight),
+            embed_responses: config.embed_responses,
+            rate_limiter: RateLimiter::new(config.rate_limit, RATE_LIMIT_WINDOW),
+            in_progress: InProgress::default(),
+            max_query_length: config.max_query_length,
+            stats: Stats::default(),
+            admin_user_id: config.admin_user_id,
         })
         .await
         .expect("Err creating client");
 
+    // On Ctrl-C, close the gateway connections so Discord shows the bot as
+    // offline right away; `client.start` returns once they are closed
+    let shard_manager = Arc::clone(&client.shard_manager);
+    tokio::spawn(async move {
+        if let Err(why) = tokio::signal::ctrl_c().await {
+            error!("Could not listen for Ctrl-C: {:?}", why);
+            return;
+        }
+        info!("Received Ctrl-C, shutting down");
+        shard_manager.lock().await.shutdown_all().await;
+    });
+
     if let Err(why) = client.start().await {
         error!("Client error: {:?}", why);
     }
 
+    // Replies go over HTTP rather than the gateway, so answers already being
+    // generated can still be delivered
+    if tokio::time::timeout(SHUTDOWN_GRACE_PERIOD, in_flight.write()).await.is_err() {
+        error!("Gave up waiting for in-flight answers after {:?}", SHUTDOWN_GRACE_PERIOD);
+    }
+    info!("Shutdown complete");
+
     Ok(())
 }
\ No newline at end of file
@@ -0,0 +1,243 @@
+// message_utils.rs
+
+/// Maximum size of a single message we send to Discord. The hard limit is 2000
+/// characters; we stay a little below it to leave room for fence markers.
+pub const DISCORD_MESSAGE_LIMIT: usize = 1900;
+
+/// Discord's limits for embeds, in characters
+pub const EMBED_DESCRIPTION_LIMIT: usize = 4096;
+pub const EMBED_TITLE_LIMIT: usize = 256;
+
+/// Whether `text` reads well as a single embed description. Code blocks look
+/// cramped inside embeds, so anything with a fence is sent as plain messages.
+pub fn fits_in_embed(text: &str) -> bool {
+    !text.trim().is_empty()
+        && !text.lines().any(is_fence)
+        && text.chars().count() <= EMBED_DESCRIPTION_LIMIT
+}
+
+/// Shorten `text` to at most `limit` characters, marking the cut with an
+/// ellipsis
+pub fn truncate_chars(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(limit.saturating_sub(1)).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// The start of `text` on a single line, at most `limit` characters, for
+/// previews such as `/docs` results
+pub fn snippet(text: &str, limit: usize) -> String {
+    let line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    truncate_chars(&line, limit)
+}
+
+/// Split `text` into pieces of at most `limit` bytes that can be sent as
+/// sequential Discord messages.
+///
+/// Pieces are cut on paragraph boundaries whenever possible. A fenced code
+/// block is never split between paragraphs; if a single code block is larger
+/// than `limit` it is split on line boundaries and the fence is closed and
+/// reopened (with the same language tag) so every piece still renders.
+pub fn chunk_message(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for block in split_blocks(text) {
+        for piece in split_oversized(&block, limit) {
+            if !current.is_empty() && current.len() + 2 + piece.len() > limit {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(&piece);
+        }
+    }
+
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+fn is_fence(line: &str) -> bool {
+    line.trim_start().starts_with("```")
+}
+
+/// Group lines into paragraphs separated by blank lines, keeping fenced code
+/// blocks (including any blank lines inside them) together.
+fn split_blocks(text: &str) -> Vec<String> {
+    let mut blocks = Vec::new();
+    let mut current: Vec<&str> = Vec::new();
+    let mut in_fence = false;
+
+    for line in text.lines() {
+        if is_fence(line) {
+            in_fence = !in_fence;
+        }
+        if !in_fence && line.trim().is_empty() {
+            if !current.is_empty() {
+                blocks.push(current.join("\n"));
+                current.clear();
+            }
+            continue;
+        }
+        current.push(line);
+    }
+
+    if !current.is_empty() {
+        blocks.push(current.join("\n"));
+    }
+
+    blocks
+}
+
+/// Split a single paragraph or code block that does not fit in `limit` on line
+/// boundaries, closing and reopening code fences around each cut.
+fn split_oversized(block: &str, limit: usize) -> Vec<String> {
+    if block.len() <= limit {
+        return vec![block.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    let mut open_fence: Option<&str> = None;
+
+    for line in block.lines() {
+        // Leave room for the fence we may have to reopen at the top of a piece
+        // and close at the bottom of it.
+        let reserve = open_fence.map_or(0, |fence| fence.len() + 5);
+        for segment in split_line(line, limit.saturating_sub(reserve).max(1)) {
+            let closing = if open_fence.is_some() && !is_fence(line) { 4 } else { 0 };
+            if !current.is_empty() && current.len() + 1 + segment.len() + closing > limit {
+                if open_fence.is_some() {
+                    current.push_str("\n```");
+                }
+                pieces.push(std::mem::take(&mut current));
+                if let Some(fence) = open_fence {
+                    current.push_str(fence);
+                }
+            }
+            if !current.is_empty() {
+                current.push('\n');
+            }
+            current.push_str(segment);
+        }
+
+        if is_fence(line) {
+            open_fence = match open_fence {
+                Some(_) => None,
+                None => Some(line.trim()),
+            };
+        }
+    }
+
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+
+    pieces
+}
+
+/// Split a single line into segments of at most `limit` bytes without cutting
+/// through a multi-byte character.
+fn split_line(line: &str, limit: usize) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut rest = line;
+
+    while rest.len() > limit {
+        let mut end = limit;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == 0 {
+            // A single character wider than the limit; emit it whole.
+            end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+        segments.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    segments.push(rest);
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_message_is_single_chunk() {
+        let chunks = chunk_message("Hello, Rig!", DISCORD_MESSAGE_LIMIT);
+        assert_eq!(chunks, vec!["Hello, Rig!".to_string()]);
+    }
+
+    #[test]
+    fn test_splits_on_paragraph_boundaries() {
+        let paragraph = "word ".repeat(50);
+        let text = [paragraph.trim(); 4].join("\n\n");
+        let chunks = chunk_message(&text, 600);
+
+        assert_eq!(chunks.len(), 2);
+        for chunk in &chunks {
+            assert!(chunk.len() <= 600);
+            assert!(!chunk.starts_with('\n') && !chunk.ends_with('\n'));
+        }
+    }
+
+    #[test]
+    fn test_multibyte_characters_at_the_limit_are_not_split() {
+        // 1 + 4 * n bytes never lands on a character boundary at 1900
+        let line = format!("a{}", "🦀".repeat(600));
+        assert!(!line.is_char_boundary(DISCORD_MESSAGE_LIMIT));
+
+        let chunks = chunk_message(&line, DISCORD_MESSAGE_LIMIT);
+        assert_eq!(chunks.len(), 2);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= DISCORD_MESSAGE_LIMIT));
+        assert_eq!(chunks.concat(), line);
+    }
+
+    #[test]
+    fn test_fits_in_embed() {
+        assert!(fits_in_embed("Rig is a Rust library for building LLM applications."));
+        assert!(!fits_in_embed("Try this:\n\n```rust\nlet x = 1;\n```"));
+        assert!(!fits_in_embed(&"é".repeat(EMBED_DESCRIPTION_LIMIT + 1)));
+        assert!(!fits_in_embed("  "));
+    }
+
+    #[test]
+    fn test_truncate_chars() {
+        assert_eq!(truncate_chars("short", 10), "short");
+        assert_eq!(truncate_chars("héllo wörld", 6), "héllo…");
+    }
+
+    #[test]
+    fn test_snippet() {
+        assert_eq!(snippet("# Rig\n\nRig is  a Rust library.", 100), "# Rig Rig is a Rust library.");
+        assert_eq!(snippet("# Rig\n\nRig is a Rust library.", 10), "# Rig Rig…");
+    }
+
+    #[test]
+    fn test_code_blocks_stay_balanced() {
+        let code: Vec<String> = (0..80)
+            .map(|i| format!("    let value_{i} = compute({i});"))
+            .collect();
+        let text = format!(
+            "Here is an annotated example:\n\n```rust\nfn main() {{\n{}\n}}\n```\n\nThat's it.",
+            code.join("\n")
+        );
+        let chunks = chunk_message(&text, 500);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.len() <= 500, "chunk too long: {}", chunk.len());
+            let fences = chunk.lines().filter(|line| is_fence(line)).count();
+            assert_eq!(fences % 2, 0, "unbalanced fences in chunk:\n{chunk}");
+        }
+        assert!(chunks[1].starts_with("```rust"));
+    }
+}
@@ -0,0 +1,241 @@
+// openai_stream.rs
+
+// rig 0.2 has no streaming API, so this module talks to OpenAI's chat
+// completions endpoint directly with `"stream": true` and parses the
+// server-sent events it returns.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use crate::retry::with_retry;
+
+const OPENAI_CHAT_COMPLETIONS_URL: &str = "https://api.openai.com/v1/chat/completions";
+
+/// A single `data:` event of a chat completion stream
+#[derive(Deserialize)]
+struct StreamChunk {
+    choices: Vec<StreamChoice>,
+}
+
+#[derive(Deserialize)]
+struct StreamChoice {
+    delta: StreamDelta,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+struct StreamDelta {
+    content: Option<String>,
+    #[serde(default)]
+    tool_calls: Vec<ToolCallDelta>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct ToolCallDelta {
+    index: usize,
+    id: Option<String>,
+    function: Option<FunctionDelta>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct FunctionDelta {
+    name: Option<String>,
+    arguments: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum StreamEvent {
+    Delta(StreamDelta),
+    Done,
+}
+
+/// A tool call requested by the model, assembled from its streamed fragments
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct ToolCall {
+    pub id: String,
+    pub name: String,
+    pub arguments: String,
+}
+
+/// Everything the model produced in one streamed completion
+#[derive(Debug, Default)]
+pub struct StreamedTurn {
+    pub content: String,
+    pub tool_calls: Vec<ToolCall>,
+}
+
+impl StreamedTurn {
+    /// Merge a delta into the turn, returning any new text for the user
+    fn apply(&mut self, delta: StreamDelta) -> Option<String> {
+        for call in delta.tool_calls {
+            if self.tool_calls.len() <= call.index {
+                self.tool_calls.resize(call.index + 1, ToolCall::default());
+            }
+            let pending = &mut self.tool_calls[call.index];
+            if let Some(id) = call.id {
+                pending.id = id;
+            }
+            if let Some(function) = call.function {
+                if let Some(name) = function.name {
+                    pending.name.push_str(&name);
+                }
+                if let Some(arguments) = function.arguments {
+                    pending.arguments.push_str(&arguments);
+                }
+            }
+        }
+
+        let text = delta.content.filter(|text| !text.is_empty())?;
+        self.content.push_str(&text);
+        Some(text)
+    }
+
+    /// The assistant message to put in the history before the tool results
+    pub fn assistant_message(&self) -> Value {
+        json!({
+            "role": "assistant",
+            "content": self.content,
+            "tool_calls": self.tool_calls.iter().map(|call| json!({
+                "id": call.id,
+                "type": "function",
+                "function": { "name": call.name, "arguments": call.arguments },
+            })).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Where `RigAgent` sends its chat completion requests. Answering a question
+/// only depends on this trait, so tests can replace OpenAI with a scripted
+/// provider and run without an API key.
+#[async_trait]
+pub trait ChatProvider: Send + Sync {
+    /// Stream one completion for the request `body`, forwarding text over `tx`
+    /// as it arrives, and return the whole turn once it ends
+    async fn stream_turn(&self, body: Value, tx: &mpsc::UnboundedSender<String>) -> Result<StreamedTurn>;
+}
+
+/// Streams completions from the OpenAI API
+pub struct OpenAiChat {
+    pub http_client: reqwest::Client,
+    pub api_key: String,
+}
+
+#[async_trait]
+impl ChatProvider for OpenAiChat {
+    async fn stream_turn(&self, body: Value, tx: &mpsc::UnboundedSender<String>) -> Result<StreamedTurn> {
+        stream_chat_completion(&self.http_client, &self.api_key, body, tx).await
+    }
+}
+
+/// Send a chat completion request with streaming enabled, forwarding text over
+/// `tx` as it arrives, and return the whole turn once the stream ends.
+pub async fn stream_chat_completion(
+    http_client: &reqwest::Client,
+    api_key: &str,
+    mut body: Value,
+    tx: &mpsc::UnboundedSender<String>,
+) -> Result<StreamedTurn> {
+    body["stream"] = json!(true);
+
+    // Only the request itself is retried; once text has been forwarded a retry
+    // would repeat it.
+    let response = with_retry("Chat completion request", || async {
+        Ok(http_client
+            .post(OPENAI_CHAT_COMPLETIONS_URL)
+            .bearer_auth(api_key)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?)
+    })
+    .await?;
+
+    let mut stream = response.bytes_stream();
+    let mut buffer = Vec::new();
+    let mut turn = StreamedTurn::default();
+
+    'stream: while let Some(bytes) = stream.next().await {
+        buffer.extend_from_slice(&bytes?);
+
+        // Events are newline delimited; keep any partial line for the next read
+        while let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=pos).collect();
+            match parse_stream_line(String::from_utf8_lossy(&line).trim()) {
+                Some(StreamEvent::Delta(delta)) => {
+                    if let Some(text) = turn.apply(delta) {
+                        // The receiver going away only means nobody is watching
+                        let _ = tx.send(text);
+                    }
+                }
+                Some(StreamEvent::Done) => break 'stream,
+                None => {}
+            }
+        }
+    }
+
+    Ok(turn)
+}
+
+fn parse_stream_line(line: &str) -> Option<StreamEvent> {
+    let data = line.strip_prefix("data:")?.trim();
+    if data == "[DONE]" {
+        return Some(StreamEvent::Done);
+    }
+
+    let chunk: StreamChunk = serde_json::from_str(data).ok()?;
+    chunk
+        .choices
+        .into_iter()
+        .next()
+        .map(|choice| StreamEvent::Delta(choice.delta))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stream_line() {
+        let line = r#"data: {"id":"chatcmpl-1","choices":[{"index":0,"delta":{"content":"Hello"},"finish_reason":null}]}"#;
+        assert_eq!(
+            parse_stream_line(line),
+            Some(StreamEvent::Delta(StreamDelta {
+                content: Some("Hello".into()),
+                tool_calls: vec![],
+            }))
+        );
+
+        assert_eq!(parse_stream_line("data: [DONE]"), Some(StreamEvent::Done));
+        assert_eq!(parse_stream_line(": keep-alive"), None);
+        assert_eq!(parse_stream_line(""), None);
+    }
+
+    #[test]
+    fn test_tool_call_fragments_are_assembled() {
+        let lines = [
+            r#"data: {"choices":[{"index":0,"delta":{"role":"assistant","content":null,"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"calculator","arguments":""}}]}}]}"#,
+            r#"data: {"choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"{\"expression\":"}}]}}]}"#,
+            r#"data: {"choices":[{"index":0,"delta":{"tool_calls":[{"index":0,"function":{"arguments":"\"2 * 21\"}"}}]}}]}"#,
+        ];
+
+        let mut turn = StreamedTurn::default();
+        for line in lines {
+            match parse_stream_line(line) {
+                Some(StreamEvent::Delta(delta)) => assert_eq!(turn.apply(delta), None),
+                other => panic!("unexpected event: {:?}", other),
+            }
+        }
+
+        assert!(turn.content.is_empty());
+        assert_eq!(
+            turn.tool_calls,
+            vec![ToolCall {
+                id: "call_1".into(),
+                name: "calculator".into(),
+                arguments: r#"{"expression":"2 * 21"}"#.into(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,80 @@
+// rate_limit.rs
+
+use serenity::model::id::UserId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Limits how many questions each user can ask per window, so a single user
+/// can't run up the OpenAI bill. Uses fixed windows: a user's window starts
+/// with their first request and their count resets once it has passed.
+pub struct RateLimiter {
+    /// Requests allowed per window; 0 disables the limit
+    limit: u32,
+    window: Duration,
+    users: Mutex<HashMap<UserId, (Instant, u32)>>,
+}
+
+impl RateLimiter {
+    pub fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            users: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a request from `user`. Returns how long they have to wait if
+    /// they are over the limit.
+    pub async fn check(&self, user: UserId) -> Result<(), Duration> {
+        self.check_at(user, Instant::now()).await
+    }
+
+    async fn check_at(&self, user: UserId, now: Instant) -> Result<(), Duration> {
+        if self.limit == 0 {
+            return Ok(());
+        }
+
+        let mut users = self.users.lock().await;
+        // Drop expired windows so the map doesn't grow with every user ever seen
+        users.retain(|_, (start, _)| now.duration_since(*start) < self.window);
+
+        let (start, count) = users.entry(user).or_insert((now, 0));
+        if *count >= self.limit {
+            return Err(self.window - now.duration_since(*start));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_limit_is_per_user_and_resets() {
+        let limiter = RateLimiter::new(2, Duration::from_secs(60));
+        let start = Instant::now();
+        let alice = UserId(1);
+        let bob = UserId(2);
+
+        assert!(limiter.check_at(alice, start).await.is_ok());
+        assert!(limiter.check_at(alice, start).await.is_ok());
+        assert_eq!(
+            limiter.check_at(alice, start + Duration::from_secs(15)).await,
+            Err(Duration::from_secs(45))
+        );
+        assert!(limiter.check_at(bob, start).await.is_ok());
+
+        assert!(limiter.check_at(alice, start + Duration::from_secs(60)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_zero_disables_the_limit() {
+        let limiter = RateLimiter::new(0, Duration::from_secs(60));
+        for _ in 0..100 {
+            assert!(limiter.check(UserId(1)).await.is_ok());
+        }
+    }
+}
@@ -0,0 +1,76 @@
+// reply.rs
+
+use crate::message_utils::{chunk_message, DISCORD_MESSAGE_LIMIT};
+use serenity::builder::CreateEmbed;
+use serenity::model::application::interaction::application_command::ApplicationCommandInteraction;
+use serenity::model::channel::Message;
+use serenity::model::id::ChannelId;
+use serenity::prelude::*;
+
+/// Where an answer is being written: the placeholder message posted in reply
+/// to a mention, or the original response of a slash command.
+pub enum ReplyTarget {
+    Message(Box<Message>),
+    Interaction(Box<ApplicationCommandInteraction>),
+}
+
+impl ReplyTarget {
+    /// The channel the answer is posted in
+    pub fn channel_id(&self) -> ChannelId {
+        match self {
+            ReplyTarget::Message(msg) => msg.channel_id,
+            ReplyTarget::Interaction(command) => command.channel_id,
+        }
+    }
+
+    /// Replace the content of the placeholder.
+    pub async fn edit(&mut self, ctx: &Context, content: &str) -> serenity::Result<()> {
+        match self {
+            ReplyTarget::Message(msg) => msg.edit(ctx, |m| m.content(content)).await,
+            ReplyTarget::Interaction(command) => command
+                .edit_original_interaction_response(&ctx.http, |response| response.content(content))
+                .await
+                .map(|_| ()),
+        }
+    }
+
+    /// Post an additional message after the placeholder.
+    pub async fn follow_up(&self, ctx: &Context, content: &str) -> serenity::Result<()> {
+        match self {
+            ReplyTarget::Message(msg) => msg.channel_id.say(&ctx.http, content).await.map(|_| ()),
+            ReplyTarget::Interaction(command) => command
+                .create_followup_message(&ctx.http, |message| message.content(content))
+                .await
+                .map(|_| ()),
+        }
+    }
+
+    /// Replace the placeholder with `embed`, clearing its text.
+    pub async fn edit_embed(&mut self, ctx: &Context, embed: CreateEmbed) -> serenity::Result<()> {
+        match self {
+            ReplyTarget::Message(msg) => msg.edit(ctx, |m| m.content("").set_embed(embed)).await,
+            ReplyTarget::Interaction(command) => command
+                .edit_original_interaction_response(&ctx.http, |response| {
+                    response.content("").set_embed(embed)
+                })
+                .await
+                .map(|_| ()),
+        }
+    }
+
+    /// Write the final response: the first chunk replaces the placeholder and
+    /// any remaining chunks are sent as follow-up messages.
+    pub async fn finish(&mut self, ctx: &Context, response: &str) -> serenity::Result<()> {
+        let mut chunks = chunk_message(response, DISCORD_MESSAGE_LIMIT).into_iter();
+        let first = chunks
+            .next()
+            .unwrap_or_else(|| "I don't have an answer for that.".to_string());
+
+        self.edit(ctx, &first).await?;
+        for chunk in chunks {
+            self.follow_up(ctx, &chunk).await?;
+        }
+
+        Ok(())
+    }
+}
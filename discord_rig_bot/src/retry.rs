@@ -0,0 +1,88 @@
+// retry.rs
+
+use anyhow::Result;
+use reqwest::StatusCode;
+use std::future::Future;
+use std::time::Duration;
+use tracing::warn;
+
+/// Number of retries after the first attempt fails
+const MAX_RETRIES: u32 = 3;
+/// Delay before the first retry; doubled after every failed attempt
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Run `operation` until it succeeds, retrying transient OpenAI failures with
+/// exponential backoff. Errors that would fail the same way again (bad
+/// requests, authentication) are returned immediately.
+pub async fn with_retry<T, F, Fut>(description: &str, mut operation: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let mut backoff = INITIAL_BACKOFF;
+    let mut retry = 0;
+
+    loop {
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(e) if retry < MAX_RETRIES && is_retryable(&e) => {
+                retry += 1;
+                warn!(
+                    "{} failed, retrying in {:?} (retry {}/{}): {}",
+                    description, backoff, retry, MAX_RETRIES, e
+                );
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Rate limits, server errors, timeouts and connection failures are worth
+/// retrying. rig wraps the underlying `reqwest::Error`, so look through the
+/// whole error chain for it.
+pub fn is_retryable(error: &anyhow::Error) -> bool {
+    error
+        .chain()
+        .find_map(|cause| cause.downcast_ref::<reqwest::Error>())
+        .is_some_and(|e| match e.status() {
+            Some(status) => status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error(),
+            None => e.is_timeout() || e.is_connect(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[tokio::test]
+    async fn test_permanent_errors_are_not_retried() {
+        let attempts = AtomicU32::new(0);
+        let result: Result<()> = with_retry("test", || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(anyhow::anyhow!("invalid api key"))
+        })
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_connection_errors_are_retried() {
+        let attempts = AtomicU32::new(0);
+        let result: Result<()> = with_retry("test", || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            // Nothing listens on port 1, so this fails with a connection error
+            reqwest::get("http://127.0.0.1:1").await?;
+            Ok(())
+        })
+        .await;
+
+        assert!(result.is_err());
+        // The first attempt, then every retry
+        assert_eq!(attempts.load(Ordering::SeqCst), 1 + MAX_RETRIES);
+    }
+}
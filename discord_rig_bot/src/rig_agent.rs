@@ -2,73 +2,331 @@
 
 use anyhow::{Context, Result};
 use rig::providers::openai;
-use rig::vector_store::in_memory_store::InMemoryVectorStore;
-use rig::vector_store::VectorStore;
-use rig::embeddings::EmbeddingsBuilder;
+use rig::vector_store::in_memory_store::{InMemoryVectorIndex, InMemoryVectorStore};
+use rig::vector_store::{VectorStore, VectorStoreIndex};
+use rig::embeddings::{DocumentEmbeddings, EmbeddingsBuilder};
 use rig::agent::Agent;
-use rig::completion::Prompt;
-use std::path::Path;
+use rig::completion::{Completion, Message};
+use serde_json::{json, Value};
+use std::path::{Path, PathBuf};
 use std::fs;
 use std::sync::Arc;
+use tokio::sync::{mpsc, Mutex, RwLock};
+use tracing::{debug, info, warn};
+use crate::config::Config;
+use crate::context::{estimate_tokens, fit_to_budget};
+use crate::embedding_cache;
+use crate::images::user_content;
+use crate::openai_stream::{ChatProvider, OpenAiChat};
+use crate::retry::with_retry;
+use crate::summary::{build_transcript, ChannelMessage, Transcript};
+use crate::tools::{Calculator, CoinPrice};
+
+/// Number of times the model may call tools before it has to answer
+const MAX_TOOL_ROUNDS: usize = 3;
+
+/// Looked up by `set_model` to check a model exists before switching to it
+const OPENAI_MODELS_URL: &str = "https://api.openai.com/v1/models";
+
+/// Number of knowledge base documents retrieved for each question
+const CONTEXT_DOCUMENTS: usize = 2;
+
+/// System prompt used when `RIG_PREAMBLE_PATH` is not set
+const DEFAULT_PREAMBLE: &str = "You are an advanced AI assistant powered by Rig, a Rust library for building LLM applications. Your primary function is to provide accurate, helpful, and context-aware responses by leveraging both your general knowledge and specific information retrieved from a curated knowledge base.
+
+                    Key responsibilities and behaviors:
+                    1. Information Retrieval: You have access to a vast knowledge base. When answering questions, always consider the context provided by the retrieved information.
+                    2. Clarity and Conciseness: Provide clear and concise answers. Ensure responses are short and concise. Use bullet points or numbered lists for complex information when appropriate.
+                    3. Technical Proficiency: You have deep knowledge about Rig and its capabilities. When discussing Rig or answering related questions, provide detailed and technically accurate information.
+                    4. Code Examples: When appropriate, provide Rust code examples to illustrate concepts, especially when discussing Rig's functionalities. Always format code examples for proper rendering in Discord by wrapping them in triple backticks and specifying the language as 'rust'. For example:
+                        ```rust
+                        let example_code = \"This is how you format Rust code for Discord\";
+                        println!(\"{}\", example_code);
+                        ```
+                    5. Keep your responses short and concise. If the user needs more information, they can ask follow-up questions.
+                    6. Sources: Each retrieved document is labeled with its file id. When your answer uses one, mention it by name (e.g. \"according to Rig_guide\").
+                    ";
+
+/// System prompt for `/summarize`. The transcript is sent as the user message.
+const SUMMARY_PREAMBLE: &str = "You summarize Discord conversations. The user message is a transcript of a channel's recent messages, oldest first, one per line as `[time] author: message`. Write a short summary for someone catching up: the main topics, any questions that were answered or are still open, and any decisions. Use bullet points and mention who said what where it matters. Only summarize what is in the transcript; don't answer the questions yourself.";
+
+/// A summary of a channel's recent messages
+pub struct Summary {
+    pub response: String,
+    /// Number of messages summarized
+    pub messages: usize,
+    /// Number of older messages left out to fit the context budget
+    pub omitted: usize,
+}
+
+/// A complete answer and the knowledge base documents that were retrieved for it
+pub struct Answer {
+    pub response: String,
+    /// Ids of the retrieved documents, i.e. their paths relative to the
+    /// documents directory, in retrieval order
+    pub sources: Vec<String>,
+    /// Number of tool calls the model made while answering
+    pub tool_calls: usize,
+    /// The completion model that generated the response
+    pub model: String,
+}
+
+impl Answer {
+    /// The response with a "Sources:" footer listing the retrieved documents
+    pub fn with_sources(&self) -> String {
+        if self.sources.is_empty() {
+            return self.response.clone();
+        }
+        let names: Vec<String> = self.sources.iter().map(|source| format!("`{}`", source)).collect();
+        format!("{}\n\nSources: {}", self.response.trim_end(), names.join(", "))
+    }
+}
+
+/// An agent, the knowledge base index it answers from and the completion
+/// model it was built for
+struct ActiveAgent {
+    agent: Agent<openai::CompletionModel>,
+    /// Searched directly rather than through the agent's dynamic context, so
+    /// the retrieved documents can be ranked and trimmed to the token budget
+    index: InMemoryVectorIndex<openai::EmbeddingModel>,
+    model: String,
+}
 
 pub struct RigAgent {
-    agent: Arc<Agent<openai::CompletionModel>>,
+    /// Swapped out as a whole by `reload` and `set_model`; requests clone the
+    /// `Arc` so they keep using the agent they started with.
+    active: RwLock<Arc<ActiveAgent>>,
+    /// Held while a new agent is built so a reload and a model switch can't
+    /// undo each other
+    rebuilding: Mutex<()>,
+    openai_client: openai::Client,
+    http_client: reqwest::Client,
+    api_key: String,
+    /// Streams the answers; OpenAI outside of tests
+    chat: Box<dyn ChatProvider>,
+    preamble: String,
+    /// System prompt for direct messages (`RIG_DM_PREAMBLE_PATH`); `None`
+    /// uses `preamble` everywhere
+    dm_preamble: Option<String>,
+    /// Tokens the preamble and retrieved documents may use (`RIG_CONTEXT_TOKENS`)
+    context_tokens: usize,
+    /// Where the document embeddings are cached (`RIG_EMBEDDINGS_CACHE`)
+    embeddings_cache: PathBuf,
 }
 
 impl RigAgent {
-    pub async fn new() -> Result<Self> {
+    /// Build the agent and its RAG index from `config`.
+    ///
+    /// The system prompt is loaded from `config.preamble_path` when set instead
+    /// of using the built-in one, and `config.dm_preamble_path` sets a
+    /// different one for direct messages.
+    ///
+    /// Embeddings are cached in `config.embeddings_cache` and only rebuilt when
+    /// the documents change.
+    pub async fn new(config: &Config) -> Result<Self> {
         // Initialize OpenAI client
-        let openai_client = openai::Client::from_env();
-        let embedding_model = openai_client.embedding_model(openai::TEXT_EMBEDDING_3_SMALL);
+        let api_key = config.openai_api_key.clone();
+        let openai_client = openai::Client::new(&api_key);
 
-        // Create vector store
-        let mut vector_store = InMemoryVectorStore::default();
+        let preamble = match &config.preamble_path {
+            Some(path) => fs::read_to_string(path)
+                .with_context(|| format!("Failed to read preamble file: {:?}", path))?,
+            None => DEFAULT_PREAMBLE.to_string(),
+        };
+        let dm_preamble = match &config.dm_preamble_path {
+            Some(path) => Some(
+                fs::read_to_string(path)
+                    .with_context(|| format!("Failed to read DM preamble file: {:?}", path))?,
+            ),
+            None => None,
+        };
+        let embeddings_cache = config.embeddings_cache.clone();
 
-        // Get the current directory and construct paths to markdown files
-        let current_dir = std::env::current_dir()?;
-        let documents_dir = current_dir.join("documents");
+        let active = Self::build_agent(&openai_client, &embeddings_cache, &config.model, &preamble).await?;
+        let http_client = reqwest::Client::new();
 
-        let md1_path = documents_dir.join("Rig_guide.md");
-        let md2_path = documents_dir.join("Rig_faq.md");
-        let md3_path = documents_dir.join("Rig_examples.md");
+        Ok(Self {
+            active: RwLock::new(Arc::new(active)),
+            rebuilding: Mutex::new(()),
+            openai_client,
+            chat: Box::new(OpenAiChat {
+                http_client: http_client.clone(),
+                api_key: api_key.clone(),
+            }),
+            http_client,
+            api_key,
+            preamble,
+            dm_preamble,
+            context_tokens: config.context_tokens,
+            embeddings_cache,
+        })
+    }
 
-        // Load markdown documents
-        let md1_content = Self::load_md_content(&md1_path)?;
-        let md2_content = Self::load_md_content(&md2_path)?;
-        let md3_content = Self::load_md_content(&md3_path)?;
+    /// The completion model new answers are generated with
+    pub async fn model(&self) -> String {
+        self.active.read().await.model.clone()
+    }
+
+    /// Rebuild the embeddings and vector index from the `documents` directory
+    /// and swap in the new agent. Requests already in flight finish with the
+    /// previous agent.
+    pub async fn reload(&self) -> Result<()> {
+        let _rebuilding = self.rebuilding.lock().await;
+        let model = self.model().await;
+        let active = Self::build_agent(&self.openai_client, &self.embeddings_cache, &model, &self.preamble).await?;
+        *self.active.write().await = Arc::new(active);
+        info!("Reloaded knowledge base");
+        Ok(())
+    }
 
-        // Create embeddings and add to vector store
-        let embeddings = EmbeddingsBuilder::new(embedding_model.clone())
-            .simple_document("Rig_guide", &md1_content)
-            .simple_document("Rig_faq", &md2_content)
-            .simple_document("Rig_examples", &md3_content)
-            .build()
+    /// Switch to another completion model, e.g. to compare `gpt-4o` with
+    /// `gpt-4o-mini` without restarting. The model is checked against the
+    /// OpenAI API first, so a typo leaves the current agent in place. Returns
+    /// the previous model.
+    pub async fn set_model(&self, model: &str) -> Result<String> {
+        let response = self
+            .http_client
+            .get(format!("{}/{}", OPENAI_MODELS_URL, model))
+            .bearer_auth(&self.api_key)
+            .send()
             .await?;
+        if response.status() == reqwest::StatusCode::NOT_FOUND {
+            anyhow::bail!("Unknown model: {}", model);
+        }
+        response.error_for_status()?;
 
-        vector_store.add_documents(embeddings).await?;
+        let _rebuilding = self.rebuilding.lock().await;
+        let active = Self::build_agent(&self.openai_client, &self.embeddings_cache, model, &self.preamble).await?;
+        let previous = std::mem::replace(&mut *self.active.write().await, Arc::new(active));
+        info!("Switched model from {} to {}", previous.model, model);
+        Ok(previous.model.clone())
+    }
 
-        // Create index
-        let index = vector_store.index(embedding_model);
+    /// Search the knowledge base for the `n` documents closest to `query`,
+    /// best match first. Only the query is embedded; no completion is made.
+    pub async fn search_documents(&self, query: &str, n: usize) -> Result<Vec<DocumentEmbeddings>> {
+        let active = Arc::clone(&*self.active.read().await);
+        if active.index.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        // Create Agent
-        let agent = Arc::new(openai_client.agent(openai::GPT_4O)
-            .preamble("You are an advanced AI assistant powered by Rig, a Rust library for building LLM applications. Your primary function is to provide accurate, helpful, and context-aware responses by leveraging both your general knowledge and specific information retrieved from a curated knowledge base.
+        let mut retrieved = with_retry("Knowledge base search", || async {
+            Ok(active.index.top_n_from_query(query, n).await?)
+        })
+        .await?;
+        retrieved.sort_by(|a, b| b.0.total_cmp(&a.0));
+        Ok(retrieved.into_iter().map(|(_, document)| document).collect())
+    }
 
-                    Key responsibilities and behaviors:
-                    1. Information Retrieval: You have access to a vast knowledge base. When answering questions, always consider the context provided by the retrieved information.
-                    2. Clarity and Conciseness: Provide clear and concise answers. Ensure responses are short and concise. Use bullet points or numbered lists for complex information when appropriate.
-                    3. Technical Proficiency: You have deep knowledge about Rig and its capabilities. When discussing Rig or answering related questions, provide detailed and technically accurate information.
-                    4. Code Examples: When appropriate, provide Rust code examples to illustrate concepts, especially when discussing Rig's functionalities. Always format code examples for proper rendering in Discord by wrapping them in triple backticks and specifying the language as 'rust'. For example:
-                        ```rust
-                        let example_code = \"This is how you format Rust code for Discord\";
-                        println!(\"{}\", example_code);
-                        ```
-                    5. Keep your responses short and concise. If the user needs more information, they can ask follow-up questions.
-                    ")
-            .dynamic_context(2, index)
-            .build());
+    /// Summarize a channel's recent `messages`, given newest first. Returns
+    /// `None` if none of them has anything to summarize.
+    ///
+    /// The transcript takes the place of the retrieved documents in the
+    /// `RIG_CONTEXT_TOKENS` budget, so the oldest messages are left out when a
+    /// channel is busy. No documents are retrieved and no tools are offered:
+    /// the summary only covers what was said.
+    pub async fn summarize(&self, messages: &[ChannelMessage]) -> Result<Option<Summary>> {
+        let budget = self.context_tokens.saturating_sub(estimate_tokens(SUMMARY_PREAMBLE));
+        let Transcript { text, included, omitted } = build_transcript(messages, budget);
+        if included == 0 {
+            return Ok(None);
+        }
+        if omitted > 0 {
+            warn!(
+                "Left {} older messages out of the summary to fit the {}-token context budget",
+                omitted, self.context_tokens
+            );
+        }
+
+        let body = json!({
+            "model": self.model().await,
+            "messages": [
+                { "role": "system", "content": SUMMARY_PREAMBLE },
+                { "role": "user", "content": text },
+            ],
+        });
+        // The summary is sent once it is complete, so nobody reads the stream
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let turn = self.chat.stream_turn(body, &tx).await?;
+
+        Ok(Some(Summary {
+            response: turn.content,
+            messages: included,
+            omitted,
+        }))
+    }
 
-        Ok(Self { agent })
+    async fn build_agent(
+        openai_client: &openai::Client,
+        cache_path: &Path,
+        model: &str,
+        preamble: &str,
+    ) -> Result<ActiveAgent> {
+        let index = Self::build_index(openai_client, cache_path).await?;
+        Ok(Self::assemble_agent(openai_client, model, preamble, index))
+    }
+
+    /// Embed the markdown documents, or load their embeddings from the cache
+    /// at `cache_path`
+    async fn build_index(
+        openai_client: &openai::Client,
+        cache_path: &Path,
+    ) -> Result<InMemoryVectorIndex<openai::EmbeddingModel>> {
+        let embedding_model = openai_client.embedding_model(openai::TEXT_EMBEDDING_3_SMALL);
+
+        // Load every markdown file under the documents directory
+        let documents_dir = std::env::current_dir()?.join("documents");
+        let documents = Self::load_md_documents(&documents_dir)?;
+        if documents.is_empty() {
+            anyhow::bail!("No markdown documents found in {:?}", documents_dir);
+        }
+
+        // Reuse the cached vector store unless the documents changed
+        let source_hash = embedding_cache::source_hash(openai::TEXT_EMBEDDING_3_SMALL, &documents);
+
+        let vector_store = match embedding_cache::load(cache_path, &source_hash) {
+            Some(vector_store) => vector_store,
+            None => {
+                // Create embeddings and add to vector store
+                let mut builder = EmbeddingsBuilder::new(embedding_model.clone());
+                for (id, content) in &documents {
+                    builder = builder.simple_document(id, content);
+                }
+                let embeddings = builder.build().await?;
+
+                let mut vector_store = InMemoryVectorStore::default();
+                vector_store.add_documents(embeddings).await?;
+
+                // A failed write only costs a rebuild next time
+                if let Err(e) = embedding_cache::save(cache_path, &source_hash, &vector_store) {
+                    warn!("{:?}", e);
+                }
+                vector_store
+            }
+        };
+
+        Ok(vector_store.index(embedding_model))
+    }
+
+    fn assemble_agent(
+        openai_client: &openai::Client,
+        model: &str,
+        preamble: &str,
+        index: InMemoryVectorIndex<openai::EmbeddingModel>,
+    ) -> ActiveAgent {
+        // Create Agent. Every tool registered here is described to the model on
+        // each request, and the model decides whether to call it.
+        let agent = openai_client.agent(model)
+            .preamble(preamble)
+            .tool(Calculator)
+            .tool(CoinPrice)
+            .build();
+
+        ActiveAgent {
+            agent,
+            index,
+            model: model.to_string(),
+        }
     }
 
     fn load_md_content<P: AsRef<Path>>(file_path: P) -> Result<String> {
@@ -76,7 +334,405 @@ impl RigAgent {
             .with_context(|| format!("Failed to read markdown file: {:?}", file_path.as_ref()))
     }
 
-    pub async fn process_message(&self, message: &str) -> Result<String> {
-        self.agent.prompt(message).await.map_err(anyhow::Error::from)
+    /// Recursively collect every `.md` file under `dir` as `(id, content)`
+    /// pairs, where the id is the path relative to `dir` without the extension
+    /// (e.g. `Rig_guide` or `guides/Getting_started`).
+    fn load_md_documents(dir: &Path) -> Result<Vec<(String, String)>> {
+        let mut paths = Vec::new();
+        Self::collect_md_paths(dir, &mut paths)?;
+        paths.sort();
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let id = path
+                    .strip_prefix(dir)
+                    .unwrap_or(&path)
+                    .with_extension("")
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                Ok((id, Self::load_md_content(&path)?))
+            })
+            .collect()
     }
-}
\ No newline at end of file
+
+    fn collect_md_paths(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+        let entries = fs::read_dir(dir)
+            .with_context(|| format!("Failed to read documents directory: {:?}", dir))?;
+
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                Self::collect_md_paths(&path, paths)?;
+            } else if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("md")) {
+                paths.push(path);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Stream the agent's answer to `message` given the earlier conversation in
+    /// `chat_history`, sending each piece of text over `tx` as it arrives, and
+    /// return the complete answer once the stream ends.
+    ///
+    /// `images` are data URLs of images attached to the message. They are sent
+    /// along with the text, which requires a vision-capable model such as
+    /// `gpt-4o`; the knowledge base is still searched using the text alone.
+    ///
+    /// `private` is set for direct messages, which use the DM preamble when
+    /// one is configured.
+    ///
+    /// The agent assembles the request (preamble, retrieved RAG context and
+    /// tool definitions). When the model calls a tool, the tool runs through the
+    /// agent's toolset and its output is sent back so the model can finish the
+    /// answer.
+    pub async fn process_message_streaming(
+        &self,
+        message: &str,
+        chat_history: Vec<Message>,
+        images: &[String],
+        private: bool,
+        tx: mpsc::UnboundedSender<String>,
+    ) -> Result<Answer> {
+        let active = Arc::clone(&*self.active.read().await);
+        let agent = &active.agent;
+
+        // The agent is built with the server preamble; swap it for DMs
+        let preamble = match &self.dm_preamble {
+            Some(dm_preamble) if private => dm_preamble,
+            _ => &self.preamble,
+        };
+
+        // Retrieving the context embeds the message to search the knowledge
+        // base, which is pointless when it's empty
+        let retrieved = if active.index.is_empty() {
+            Vec::new()
+        } else {
+            with_retry("Context retrieval", || async {
+                Ok(active.index.top_n_from_query(message, CONTEXT_DOCUMENTS).await?)
+            })
+            .await?
+        };
+
+        // Large documents could push the prompt past the model's context
+        // window, so the lowest-scoring ones are cut short or left out
+        let budget = self.context_tokens.saturating_sub(estimate_tokens(preamble));
+        let context = fit_to_budget(retrieved, budget);
+        if context.truncated.is_some() || !context.dropped.is_empty() {
+            warn!(
+                "Retrieved documents exceed the {}-token context budget: truncated {:?}, dropped {:?}",
+                self.context_tokens, context.truncated, context.dropped
+            );
+        }
+
+        let request = agent
+            .completion(message, chat_history)
+            .await?
+            .documents(context.documents)
+            .build();
+
+        // The ids of the documents the dynamic context lookup returned
+        let mut sources: Vec<String> = Vec::new();
+        for document in &request.documents {
+            if !sources.contains(&document.id) {
+                sources.push(document.id.clone());
+            }
+        }
+
+        let mut messages: Vec<Value> = Vec::new();
+        messages.push(json!({ "role": "system", "content": preamble }));
+        for message in &request.chat_history {
+            messages.push(json!({ "role": message.role, "content": message.content }));
+        }
+        messages.push(json!({
+            "role": "user",
+            "content": user_content(&request.prompt_with_context(), images),
+        }));
+
+        let tools: Vec<Value> = request
+            .tools
+            .iter()
+            .map(|tool| {
+                json!({
+                    "type": "function",
+                    "function": {
+                        "name": tool.name,
+                        "description": tool.description,
+                        "parameters": tool.parameters,
+                    },
+                })
+            })
+            .collect();
+
+        let mut full_response = String::new();
+        let mut tool_calls = 0;
+        for round in 0..=MAX_TOOL_ROUNDS {
+            let mut body = json!({
+                "model": active.model,
+                "messages": messages,
+                "temperature": request.temperature,
+            });
+            // On the last round the model has to answer with what it has
+            if round < MAX_TOOL_ROUNDS && !tools.is_empty() {
+                body["tools"] = json!(tools);
+            }
+
+            let turn = self.chat.stream_turn(body, &tx).await?;
+            full_response.push_str(&turn.content);
+            if turn.tool_calls.is_empty() {
+                break;
+            }
+
+            messages.push(turn.assistant_message());
+            tool_calls += turn.tool_calls.len();
+            for call in &turn.tool_calls {
+                debug!("Calling tool {} with {}", call.name, call.arguments);
+                let output = match agent.tools.call(&call.name, call.arguments.clone()).await {
+                    Ok(output) => output,
+                    Err(e) => {
+                        warn!("Tool {} failed: {}", call.name, e);
+                        format!("Error: {}", e)
+                    }
+                };
+                messages.push(json!({ "role": "tool", "tool_call_id": call.id, "content": output }));
+            }
+        }
+
+        Ok(Answer {
+            response: full_response,
+            sources,
+            tool_calls,
+            model: active.model.clone(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::DEFAULT_CONTEXT_TOKENS;
+    use crate::message_utils::{chunk_message, DISCORD_MESSAGE_LIMIT};
+    use crate::openai_stream::{StreamedTurn, ToolCall};
+    use async_trait::async_trait;
+    use std::collections::VecDeque;
+
+    /// Replays scripted turns instead of calling OpenAI and keeps the request
+    /// bodies it was sent
+    struct ScriptedChat {
+        turns: std::sync::Mutex<VecDeque<StreamedTurn>>,
+        requests: Arc<std::sync::Mutex<Vec<Value>>>,
+    }
+
+    #[async_trait]
+    impl ChatProvider for ScriptedChat {
+        async fn stream_turn(&self, body: Value, tx: &mpsc::UnboundedSender<String>) -> Result<StreamedTurn> {
+            self.requests.lock().unwrap().push(body);
+            let turn = self.turns.lock().unwrap().pop_front().context("No scripted turn left")?;
+            // Streamed in small pieces, like OpenAI does
+            let chars: Vec<char> = turn.content.chars().collect();
+            for piece in chars.chunks(50) {
+                tx.send(piece.iter().collect()).unwrap();
+            }
+            Ok(turn)
+        }
+    }
+
+    /// An agent with an empty knowledge base that answers with `turns`, and
+    /// the requests it sends
+    fn scripted_agent(turns: Vec<StreamedTurn>) -> (RigAgent, Arc<std::sync::Mutex<Vec<Value>>>) {
+        // Building the client and the empty index makes no requests
+        let openai_client = openai::Client::new("test-key");
+        let index = InMemoryVectorStore::default()
+            .index(openai_client.embedding_model(openai::TEXT_EMBEDDING_3_SMALL));
+        let active = RigAgent::assemble_agent(&openai_client, openai::GPT_4O, "You are a test.", index);
+        let requests = Arc::new(std::sync::Mutex::new(Vec::new()));
+
+        let agent = RigAgent {
+            active: RwLock::new(Arc::new(active)),
+            rebuilding: Mutex::new(()),
+            openai_client,
+            http_client: reqwest::Client::new(),
+            api_key: "test-key".to_string(),
+            chat: Box::new(ScriptedChat {
+                turns: std::sync::Mutex::new(turns.into()),
+                requests: Arc::clone(&requests),
+            }),
+            preamble: "You are a test.".to_string(),
+            dm_preamble: None,
+            context_tokens: DEFAULT_CONTEXT_TOKENS,
+            embeddings_cache: PathBuf::from("embeddings_cache.json"),
+        };
+        (agent, requests)
+    }
+
+    fn text_turn(content: &str) -> StreamedTurn {
+        StreamedTurn {
+            content: content.to_string(),
+            tool_calls: vec![],
+        }
+    }
+
+    fn tool_turn(id: &str, expression: &str) -> StreamedTurn {
+        StreamedTurn {
+            content: String::new(),
+            tool_calls: vec![ToolCall {
+                id: id.to_string(),
+                name: "calculator".to_string(),
+                arguments: json!({ "expression": expression }).to_string(),
+            }],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_long_answer_is_streamed_and_split_for_discord() {
+        let paragraphs: Vec<String> = (0..40)
+            .map(|i| format!("Paragraph {}: {}", i, "Rig makes LLM apps easy. ".repeat(4).trim()))
+            .collect();
+        let long_answer = paragraphs.join("\n\n");
+        assert!(long_answer.chars().count() > DISCORD_MESSAGE_LIMIT * 2);
+
+        let (agent, requests) = scripted_agent(vec![text_turn(&long_answer)]);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let answer = agent
+            .process_message_streaming("Tell me about Rig", vec![], &[], false, tx)
+            .await
+            .unwrap();
+
+        assert_eq!(answer.response, long_answer);
+        assert_eq!(answer.tool_calls, 0);
+        assert!(answer.sources.is_empty());
+        assert_eq!(answer.model, openai::GPT_4O);
+
+        let mut streamed = String::new();
+        while let Ok(text) = rx.try_recv() {
+            streamed.push_str(&text);
+        }
+        assert_eq!(streamed, long_answer);
+
+        let requests = requests.lock().unwrap();
+        let messages = requests[0]["messages"].as_array().unwrap();
+        assert_eq!(messages[0], json!({ "role": "system", "content": "You are a test." }));
+        assert_eq!(messages.last().unwrap()["role"], "user");
+
+        // Every piece fits in a Discord message and no paragraph is cut
+        let chunks = chunk_message(&answer.with_sources(), DISCORD_MESSAGE_LIMIT);
+        assert!(chunks.len() >= 3);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= DISCORD_MESSAGE_LIMIT));
+        for paragraph in &paragraphs {
+            assert_eq!(chunks.iter().filter(|chunk| chunk.contains(paragraph.as_str())).count(), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_tool_calls_are_run_and_their_output_sent_back() {
+        let (agent, requests) = scripted_agent(vec![tool_turn("call_1", "6 * 7"), text_turn("6 × 7 = 42")]);
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let answer = agent
+            .process_message_streaming("What is 6 times 7?", vec![], &[], false, tx)
+            .await
+            .unwrap();
+
+        assert_eq!(answer.response, "6 × 7 = 42");
+        assert_eq!(answer.tool_calls, 1);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 2);
+        let messages = requests[1]["messages"].as_array().unwrap();
+        let tool_result = messages.last().unwrap();
+        assert_eq!(tool_result["role"], "tool");
+        assert_eq!(tool_result["tool_call_id"], "call_1");
+        assert!(tool_result["content"].as_str().unwrap().starts_with("42"));
+    }
+
+    #[tokio::test]
+    async fn test_tools_are_withheld_after_max_rounds() {
+        let mut turns: Vec<StreamedTurn> = (0..MAX_TOOL_ROUNDS)
+            .map(|i| tool_turn(&format!("call_{}", i), "1 + 1"))
+            .collect();
+        turns.push(text_turn("Done"));
+        let (agent, requests) = scripted_agent(turns);
+        let (tx, _rx) = mpsc::unbounded_channel();
+        let answer = agent
+            .process_message_streaming("Keep adding", vec![], &[], false, tx)
+            .await
+            .unwrap();
+
+        assert_eq!(answer.response, "Done");
+        assert_eq!(answer.tool_calls, MAX_TOOL_ROUNDS);
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), MAX_TOOL_ROUNDS + 1);
+        assert!(requests[..MAX_TOOL_ROUNDS].iter().all(|body| body.get("tools").is_some()));
+        assert!(requests[MAX_TOOL_ROUNDS].get("tools").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_summary_is_a_single_completion_without_tools() {
+        let (agent, requests) = scripted_agent(vec![text_turn("- alice asked how to embed documents")]);
+
+        // Nothing to summarize, so no request
+        assert!(agent.summarize(&[]).await.unwrap().is_none());
+
+        let messages = [ChannelMessage {
+            author: "alice".to_string(),
+            bot: false,
+            content: "How do I embed documents?".to_string(),
+            sent_at: serenity::model::Timestamp::from_unix_timestamp(1_728_388_800).unwrap(),
+        }];
+
+        let summary = agent.summarize(&messages).await.unwrap().unwrap();
+        assert_eq!(summary.response, "- alice asked how to embed documents");
+        assert_eq!((summary.messages, summary.omitted), (1, 0));
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].get("tools").is_none());
+        assert_eq!(
+            requests[0]["messages"][1]["content"],
+            "[2024-10-08T12:00:00Z] alice: How do I embed documents?"
+        );
+    }
+
+    #[test]
+    fn test_answer_with_sources() {
+        let answer = Answer {
+            response: "Rig is a Rust library.\n".to_string(),
+            sources: vec!["Rig_faq".to_string(), "guides/Getting_started".to_string()],
+            tool_calls: 0,
+            model: openai::GPT_4O.to_string(),
+        };
+        assert_eq!(
+            answer.with_sources(),
+            "Rig is a Rust library.\n\nSources: `Rig_faq`, `guides/Getting_started`"
+        );
+
+        let no_sources = Answer {
+            response: "Hello!".to_string(),
+            sources: vec![],
+            tool_calls: 0,
+            model: openai::GPT_4O.to_string(),
+        };
+        assert_eq!(no_sources.with_sources(), "Hello!");
+    }
+
+    #[test]
+    fn test_load_md_documents_recurses_and_skips_other_files() {
+        let dir = std::env::temp_dir().join(format!("rig_docs_{}", std::process::id()));
+        fs::create_dir_all(dir.join("guides")).unwrap();
+        fs::write(dir.join("Rig_faq.md"), "faq").unwrap();
+        fs::write(dir.join("guides").join("Getting_started.md"), "start").unwrap();
+        fs::write(dir.join("backup.rs"), "fn main() {}").unwrap();
+
+        let documents = RigAgent::load_md_documents(&dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            documents,
+            vec![
+                ("Rig_faq".to_string(), "faq".to_string()),
+                ("guides/Getting_started".to_string(), "start".to_string()),
+            ]
+        );
+    }
+}
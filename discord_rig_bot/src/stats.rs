@@ -0,0 +1,93 @@
+// stats.rs
+
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+
+/// Usage counters for `/stats`, kept in memory for the lifetime of the process
+pub struct Stats {
+    started: Instant,
+    counters: Mutex<Counters>,
+}
+
+#[derive(Default, Clone, Copy)]
+struct Counters {
+    questions_answered: u64,
+    total_response_time: Duration,
+    tool_calls: u64,
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self {
+            started: Instant::now(),
+            counters: Mutex::new(Counters::default()),
+        }
+    }
+}
+
+impl Stats {
+    /// Count an answered question that took `response_time` and made
+    /// `tool_calls` tool calls
+    pub async fn record(&self, response_time: Duration, tool_calls: usize) {
+        let mut counters = self.counters.lock().await;
+        counters.questions_answered += 1;
+        counters.total_response_time += response_time;
+        counters.tool_calls += tool_calls as u64;
+    }
+
+    /// The counters formatted as a short report
+    pub async fn summary(&self) -> String {
+        let counters = *self.counters.lock().await;
+        let average = match counters.questions_answered {
+            0 => "n/a".to_string(),
+            n => format!("{:.1}s", counters.total_response_time.as_secs_f64() / n as f64),
+        };
+
+        format!(
+            "**Questions answered:** {}\n**Average response time:** {}\n**Tool calls:** {}\n**Uptime:** {}",
+            counters.questions_answered,
+            average,
+            counters.tool_calls,
+            format_uptime(self.started.elapsed())
+        )
+    }
+}
+
+/// e.g. `2d 3h 4m`; seconds are only shown for the first minute
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    let (days, hours, minutes) = (secs / 86_400, secs / 3_600 % 24, secs / 60 % 60);
+    match (days, hours, minutes) {
+        (0, 0, 0) => format!("{}s", secs),
+        (0, 0, _) => format!("{}m", minutes),
+        (0, _, _) => format!("{}h {}m", hours, minutes),
+        _ => format!("{}d {}h {}m", days, hours, minutes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_summary_averages_response_times() {
+        let stats = Stats::default();
+        assert!(stats.summary().await.contains("**Average response time:** n/a"));
+
+        stats.record(Duration::from_millis(1500), 0).await;
+        stats.record(Duration::from_millis(2500), 2).await;
+
+        let summary = stats.summary().await;
+        assert!(summary.contains("**Questions answered:** 2"));
+        assert!(summary.contains("**Average response time:** 2.0s"));
+        assert!(summary.contains("**Tool calls:** 2"));
+    }
+
+    #[test]
+    fn test_format_uptime() {
+        assert_eq!(format_uptime(Duration::from_secs(42)), "42s");
+        assert_eq!(format_uptime(Duration::from_secs(5 * 60 + 9)), "5m");
+        assert_eq!(format_uptime(Duration::from_secs(3 * 3_600 + 4 * 60)), "3h 4m");
+        assert_eq!(format_uptime(Duration::from_secs(2 * 86_400 + 3 * 3_600 + 4 * 60)), "2d 3h 4m");
+    }
+}
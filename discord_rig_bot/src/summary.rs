@@ -0,0 +1,118 @@
+// summary.rs
+
+use crate::context::estimate_tokens;
+use serenity::model::Timestamp;
+use std::fmt::Write;
+
+/// A message fetched from a channel for `/summarize`
+pub struct ChannelMessage {
+    /// Display name of the sender
+    pub author: String,
+    /// Set for bots, including this one
+    pub bot: bool,
+    pub content: String,
+    pub sent_at: Timestamp,
+}
+
+/// The part of a channel's recent messages that is sent to the model
+#[derive(Debug, PartialEq)]
+pub struct Transcript {
+    /// One line per message, oldest first
+    pub text: String,
+    /// Number of messages in `text`
+    pub included: usize,
+    /// Number of older messages left out to stay within the token budget
+    pub omitted: usize,
+}
+
+/// Build the transcript of `messages`, given newest first as Discord returns
+/// them. Messages from bots and messages without text (e.g. attachments only)
+/// are skipped. The newest messages are kept and older ones are left out once
+/// the transcript would use more than `budget` tokens.
+pub fn build_transcript(messages: &[ChannelMessage], budget: usize) -> Transcript {
+    let mut lines = Vec::new();
+    let mut remaining = budget;
+    let mut omitted = 0;
+
+    for message in messages {
+        let content = message.content.trim();
+        if message.bot || content.is_empty() {
+            continue;
+        }
+        if omitted > 0 {
+            omitted += 1;
+            continue;
+        }
+
+        let mut line = String::new();
+        let _ = write!(line, "[{}] {}: {}", message.sent_at, message.author, content);
+        let tokens = estimate_tokens(&line);
+        if tokens > remaining {
+            omitted += 1;
+            continue;
+        }
+        remaining -= tokens;
+        lines.push(line);
+    }
+
+    lines.reverse();
+    Transcript {
+        included: lines.len(),
+        text: lines.join("\n"),
+        omitted,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn message(author: &str, bot: bool, content: &str, minute: i64) -> ChannelMessage {
+        ChannelMessage {
+            author: author.to_string(),
+            bot,
+            content: content.to_string(),
+            sent_at: Timestamp::from_unix_timestamp(1_728_388_800 + minute * 60).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_bots_and_empty_messages_are_skipped() {
+        // Newest first
+        let messages = [
+            message("bob", false, "Thanks, that works!", 3),
+            message("Rig Bot", true, "Use `EmbeddingsBuilder`.", 2),
+            message("bob", false, "  ", 1),
+            message("alice", false, "How do I embed documents?", 0),
+        ];
+
+        assert_eq!(
+            build_transcript(&messages, 1_000),
+            Transcript {
+                text: "[2024-10-08T12:00:00Z] alice: How do I embed documents?\n\
+                       [2024-10-08T12:03:00Z] bob: Thanks, that works!"
+                    .to_string(),
+                included: 2,
+                omitted: 0,
+            }
+        );
+        assert_eq!(build_transcript(&messages[1..3], 1_000).included, 0);
+    }
+
+    #[test]
+    fn test_oldest_messages_are_left_out_past_the_budget() {
+        // Each line is 30 characters of prefix plus the content, 100 in all,
+        // so 25 tokens
+        let text = "x".repeat(70);
+        let messages: Vec<ChannelMessage> = (0..10)
+            .rev()
+            .map(|minute| message("alice", false, &text, minute))
+            .collect();
+
+        let transcript = build_transcript(&messages, 80);
+        assert_eq!(transcript.included, 3);
+        assert_eq!(transcript.omitted, 7);
+        assert!(transcript.text.starts_with("[2024-10-08T12:07:00Z]"));
+        assert!(estimate_tokens(&transcript.text) <= 80);
+    }
+}
@@ -0,0 +1,259 @@
+// tools.rs
+
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::Duration;
+
+const SIMPLE_PRICE_URL: &str = "https://api.coingecko.com/api/v3/simple/price";
+
+// Maximum time to wait for CoinGecko before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// CoinGecko identifies coins by id rather than ticker; these are the tickers
+// people ask about most. Anything else is passed through as an id.
+const TICKER_IDS: &[(&str, &str)] = &[
+    ("btc", "bitcoin"),
+    ("eth", "ethereum"),
+    ("usdt", "tether"),
+    ("usdc", "usd-coin"),
+    ("bnb", "binancecoin"),
+    ("sol", "solana"),
+    ("xrp", "ripple"),
+    ("doge", "dogecoin"),
+    ("ada", "cardano"),
+    ("avax", "avalanche-2"),
+    ("dot", "polkadot"),
+    ("link", "chainlink"),
+    ("ltc", "litecoin"),
+    ("atom", "cosmos"),
+    ("hype", "hyperliquid"),
+];
+
+#[derive(Deserialize)]
+pub struct CalculatorArgs {
+    expression: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CalculatorError {
+    #[error("Invalid expression: {0}")]
+    InvalidExpression(String),
+    #[error("The result is not a finite number (division by zero?)")]
+    NonFiniteResult,
+}
+
+/// Evaluates arithmetic so the model doesn't have to do it "in its head"
+pub struct Calculator;
+
+impl Tool for Calculator {
+    const NAME: &'static str = "calculator";
+
+    type Args = CalculatorArgs;
+    type Output = f64;
+    type Error = CalculatorError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Evaluate an arithmetic expression. Supports + - * / ^ %, parentheses, \
+                          and functions such as sqrt, sin, cos, ln and abs."
+                .to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "expression": { "type": "string", "description": "The expression to evaluate (e.g., '(1920 * 1080) / 2')" },
+                },
+                "required": ["expression"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let value = meval::eval_str(&args.expression)
+            .map_err(|e| CalculatorError::InvalidExpression(e.to_string()))?;
+
+        if !value.is_finite() {
+            return Err(CalculatorError::NonFiniteResult);
+        }
+
+        Ok(value)
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CoinPriceArgs {
+    pub coin: String,
+    pub currency: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CoinPriceError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Unknown coin '{0}': use a CoinGecko coin id such as 'bitcoin' or a common ticker such as 'btc'")]
+    UnknownCoin(String),
+    #[error("No {currency} price available for '{coin}'")]
+    UnsupportedCurrency { coin: String, currency: String },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PriceQuote {
+    coin_id: String,
+    currency: String,
+    price: f64,
+}
+
+impl fmt::Display for PriceQuote {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Show cents, or four significant digits for coins worth less than 1
+        let decimals = if self.price >= 1.0 || self.price <= 0.0 {
+            2
+        } else {
+            (3.0 - self.price.log10().floor()) as usize
+        };
+        write!(f, "**{}**: {:.*} {}", self.coin_id, decimals, self.price, self.currency)
+    }
+}
+
+/// Looks up current crypto prices on CoinGecko. Besides being available to the
+/// agent, it backs the `/price` command, which calls it directly without the LLM.
+pub struct CoinPrice;
+
+// Map a ticker or name to a CoinGecko id, e.g. "BTC" -> "bitcoin"
+fn coin_id(coin: &str) -> String {
+    let coin = coin.trim().to_lowercase();
+    TICKER_IDS
+        .iter()
+        .find(|(ticker, _)| *ticker == coin)
+        .map_or(coin, |(_, id)| id.to_string())
+}
+
+// Pull the price out of a `{ "<id>": { "<currency>": <price> } }` response
+fn parse_price(text: &str, coin_id: &str, currency: &str) -> Result<PriceQuote, CoinPriceError> {
+    let prices: HashMap<String, HashMap<String, f64>> =
+        serde_json::from_str(text).map_err(|e| CoinPriceError::InvalidResponse(e.to_string()))?;
+
+    // CoinGecko answers unknown ids with an empty object rather than an error
+    let coin_prices = prices
+        .get(coin_id)
+        .ok_or_else(|| CoinPriceError::UnknownCoin(coin_id.to_string()))?;
+    let price = coin_prices
+        .get(currency)
+        .ok_or_else(|| CoinPriceError::UnsupportedCurrency {
+            coin: coin_id.to_string(),
+            currency: currency.to_uppercase(),
+        })?;
+
+    Ok(PriceQuote {
+        coin_id: coin_id.to_string(),
+        currency: currency.to_uppercase(),
+        price: *price,
+    })
+}
+
+impl From<BodyError> for CoinPriceError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => CoinPriceError::TooLarge,
+            BodyError::Read(e) => CoinPriceError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for CoinPrice {
+    const NAME: &'static str = "get_coin_price";
+
+    type Args = CoinPriceArgs;
+    type Output = PriceQuote;
+    type Error = CoinPriceError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: Self::NAME.to_string(),
+            description: "Get the current price of a cryptocurrency in a fiat currency from CoinGecko".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "coin": { "type": "string", "description": "A ticker (e.g., 'btc', 'eth') or CoinGecko coin id (e.g., 'bitcoin', 'arbitrum')" },
+                    "currency": { "type": "string", "description": "Fiat currency code to price the coin in (default 'usd')" },
+                },
+                "required": ["coin"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let coin_id = coin_id(&args.coin);
+        let currency = args
+            .currency
+            .unwrap_or_else(|| "usd".to_string())
+            .to_lowercase();
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| CoinPriceError::HttpRequestFailed(e.to_string()))?;
+
+        // The public API needs no key
+        let response = client
+            .get(SIMPLE_PRICE_URL)
+            .query(&[("ids", coin_id.as_str()), ("vs_currencies", currency.as_str())])
+            .send()
+            .await
+            .map_err(|e| CoinPriceError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        let text = read_body(response).await?;
+
+        if !status.is_success() {
+            return Err(CoinPriceError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        parse_price(&text, &coin_id, &currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_price() {
+        assert_eq!(coin_id(" BTC "), "bitcoin");
+        assert_eq!(coin_id("arbitrum"), "arbitrum");
+
+        let quote = parse_price(r#"{"bitcoin":{"usd":61234.5}}"#, "bitcoin", "usd").unwrap();
+        assert_eq!(quote.to_string(), "**bitcoin**: 61234.50 USD");
+
+        assert!(matches!(
+            parse_price("{}", "notacoin", "usd"),
+            Err(CoinPriceError::UnknownCoin(_))
+        ));
+    }
+
+    #[test]
+    fn test_small_prices_keep_precision() {
+        let quote = PriceQuote {
+            coin_id: "shiba-inu".into(),
+            currency: "USD".into(),
+            price: 0.0000184,
+        };
+        assert_eq!(quote.to_string(), "**shiba-inu**: 0.00001840 USD");
+    }
+}
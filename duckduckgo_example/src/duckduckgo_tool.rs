@@ -0,0 +1,287 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+const DUCKDUCKGO_URL: &str = "https://api.duckduckgo.com/";
+
+// Maximum time to wait for DuckDuckGo before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Number of related topics returned for each query
+const MAX_RELATED_TOPICS: usize = 8;
+
+#[derive(Deserialize)]
+pub struct DuckDuckGoArgs {
+    query: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DuckDuckGoError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("The query must not be empty")]
+    EmptyQuery,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct RelatedTopic {
+    text: String,
+    url: String,
+}
+
+/// The abstract when DuckDuckGo has one, otherwise the related topics, or a
+/// message saying there was nothing, so the model never receives empty fields
+/// it might misread
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum InstantAnswer {
+    Abstract {
+        heading: String,
+        abstract_text: String,
+        source: String,
+        url: String,
+        related_topics: Vec<RelatedTopic>,
+    },
+    RelatedTopics {
+        heading: String,
+        related_topics: Vec<RelatedTopic>,
+    },
+    NoResults(String),
+}
+
+// The parts of the instant-answer response we use. Every field is present but
+// may be an empty string or list.
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DuckDuckGoResponse {
+    #[serde(default)]
+    heading: String,
+    #[serde(default)]
+    abstract_text: String,
+    #[serde(default)]
+    abstract_source: String,
+    #[serde(rename = "AbstractURL", default)]
+    abstract_url: String,
+    #[serde(default)]
+    related_topics: Vec<DuckDuckGoTopic>,
+}
+
+// Related topics are either a single topic or a named group of topics
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DuckDuckGoTopic {
+    Topic {
+        #[serde(rename = "Text")]
+        text: String,
+        #[serde(rename = "FirstURL")]
+        first_url: String,
+    },
+    Group {
+        #[serde(rename = "Topics")]
+        topics: Vec<DuckDuckGoTopic>,
+    },
+}
+
+pub struct DuckDuckGoTool;
+
+// Flatten topic groups into a single list, in the order DuckDuckGo gives them
+fn flatten_topics(topics: Vec<DuckDuckGoTopic>, related: &mut Vec<RelatedTopic>) {
+    for topic in topics {
+        match topic {
+            DuckDuckGoTopic::Topic { text, first_url } if !text.is_empty() => {
+                related.push(RelatedTopic {
+                    text,
+                    url: first_url,
+                })
+            }
+            DuckDuckGoTopic::Topic { .. } => {}
+            DuckDuckGoTopic::Group { topics } => flatten_topics(topics, related),
+        }
+    }
+}
+
+// Turn the instant-answer response into the abstract, the related topics, or a
+// message saying DuckDuckGo had nothing for `query`
+fn parse_instant_answer(text: &str, query: &str) -> Result<InstantAnswer, DuckDuckGoError> {
+    let response: DuckDuckGoResponse =
+        serde_json::from_str(text).map_err(|e| DuckDuckGoError::InvalidResponse(e.to_string()))?;
+
+    let mut related_topics = Vec::new();
+    flatten_topics(response.related_topics, &mut related_topics);
+    related_topics.truncate(MAX_RELATED_TOPICS);
+
+    if !response.abstract_text.is_empty() {
+        return Ok(InstantAnswer::Abstract {
+            heading: response.heading,
+            abstract_text: response.abstract_text,
+            source: response.abstract_source,
+            url: response.abstract_url,
+            related_topics,
+        });
+    }
+
+    if !related_topics.is_empty() {
+        return Ok(InstantAnswer::RelatedTopics {
+            heading: response.heading,
+            related_topics,
+        });
+    }
+
+    Ok(InstantAnswer::NoResults(format!(
+        "DuckDuckGo has no instant answer for '{}'. It only covers definitions and well-known topics, so try a shorter or more general query.",
+        query
+    )))
+}
+
+impl From<BodyError> for DuckDuckGoError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => DuckDuckGoError::TooLarge,
+            BodyError::Read(e) => DuckDuckGoError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for DuckDuckGoTool {
+    const NAME: &'static str = "instant_answer";
+
+    type Args = DuckDuckGoArgs;
+    type Output = InstantAnswer;
+    type Error = DuckDuckGoError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "instant_answer".to_string(),
+            description: "Look up a topic with the DuckDuckGo Instant Answer API. Returns a short summary (usually from Wikipedia) and related topics. Good for definitions and quick facts about well-known people, places, things and concepts; it does not return general web search results or news".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "The topic to look up, as a short phrase (e.g., 'Rust programming language')" },
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let query = args.query.trim();
+        if query.is_empty() {
+            return Err(DuckDuckGoError::EmptyQuery);
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| DuckDuckGoError::HttpRequestFailed(e.to_string()))?;
+
+        // The Instant Answer API needs no key. `no_redirect` stops "!bang"
+        // queries from returning a redirect instead of an answer.
+        let response = client
+            .get(DUCKDUCKGO_URL)
+            .query(&[
+                ("q", query),
+                ("format", "json"),
+                ("no_html", "1"),
+                ("no_redirect", "1"),
+            ])
+            .send()
+            .await
+            .map_err(|e| DuckDuckGoError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        let text = read_body(response).await?;
+
+        if !status.is_success() {
+            return Err(DuckDuckGoError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        parse_instant_answer(&text, query)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_abstract() {
+        let text = r#"{
+            "Heading": "Rust (programming language)",
+            "AbstractText": "Rust is a general-purpose programming language.",
+            "AbstractSource": "Wikipedia",
+            "AbstractURL": "https://en.wikipedia.org/wiki/Rust_(programming_language)",
+            "RelatedTopics": [
+                {"Text": "Cargo - The Rust package manager.", "FirstURL": "https://duckduckgo.com/Cargo"},
+                {"Name": "See also", "Topics": [
+                    {"Text": "Mozilla - A free software community.", "FirstURL": "https://duckduckgo.com/Mozilla"}
+                ]}
+            ]
+        }"#;
+
+        let InstantAnswer::Abstract {
+            heading,
+            source,
+            related_topics,
+            ..
+        } = parse_instant_answer(text, "rust").unwrap()
+        else {
+            panic!("expected an abstract");
+        };
+        assert_eq!(heading, "Rust (programming language)");
+        assert_eq!(source, "Wikipedia");
+        assert_eq!(
+            related_topics,
+            vec![
+                RelatedTopic {
+                    text: "Cargo - The Rust package manager.".into(),
+                    url: "https://duckduckgo.com/Cargo".into(),
+                },
+                RelatedTopic {
+                    text: "Mozilla - A free software community.".into(),
+                    url: "https://duckduckgo.com/Mozilla".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_without_abstract() {
+        let text = r#"{
+            "Heading": "Mercury",
+            "AbstractText": "",
+            "RelatedTopics": [
+                {"Text": "Mercury (planet) - The smallest planet in the Solar System.", "FirstURL": "https://duckduckgo.com/Mercury_(planet)"}
+            ]
+        }"#;
+        assert_eq!(
+            parse_instant_answer(text, "mercury").unwrap(),
+            InstantAnswer::RelatedTopics {
+                heading: "Mercury".into(),
+                related_topics: vec![RelatedTopic {
+                    text: "Mercury (planet) - The smallest planet in the Solar System.".into(),
+                    url: "https://duckduckgo.com/Mercury_(planet)".into(),
+                }],
+            }
+        );
+
+        let empty = r#"{"Heading": "", "AbstractText": "", "RelatedTopics": []}"#;
+        assert!(matches!(
+            parse_instant_answer(empty, "qwertyuiop").unwrap(),
+            InstantAnswer::NoResults(_)
+        ));
+    }
+}
@@ -0,0 +1,35 @@
+mod duckduckgo_tool;
+
+use crate::duckduckgo_tool::DuckDuckGoTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the DuckDuckGoTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a helpful assistant. Use the instant_answer tool to look up definitions and facts, and mention the source of the information. If the tool returns only related topics, pick the one that matches the question or ask the user which they meant.")
+        .tool(DuckDuckGoTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What is the Rust programming language and who created it?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
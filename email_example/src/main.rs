@@ -0,0 +1,47 @@
+mod send_email_tool;
+
+use crate::send_email_tool::SendEmailTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Emails are only validated unless `--send` is given
+    let mut args: Vec<String> = std::env::args().skip(1).collect();
+    let send = args.iter().any(|arg| arg == "--send");
+    args.retain(|arg| arg != "--send");
+
+    let tool = if send {
+        SendEmailTool::from_env()?
+    } else {
+        println!("Dry run: emails will be validated but not sent. Pass --send to send them.");
+        SendEmailTool::dry_run()?
+    };
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the SendEmailTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are an assistant that writes and sends emails for the user with the send_email tool. Only send an email when the user asks you to, to the recipient they name. Afterwards, tell the user whether it was sent or only validated in a dry run, and give its Message-ID.")
+        .tool(tool)
+        .build();
+
+    // Take the request from the command line, or fall back to an example
+    let query = args.join(" ");
+    let query = if query.is_empty() {
+        "Send a short email to alice@example.com reminding her that the project demo moved to Friday at 3pm.".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
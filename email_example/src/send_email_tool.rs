@@ -0,0 +1,253 @@
+use lettre::message::header::ContentType;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+// Maximum time to wait for the SMTP server before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// SMTP submission port, upgraded to TLS with STARTTLS
+const DEFAULT_SMTP_PORT: u16 = 587;
+
+// Longest body accepted, in bytes
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+#[derive(Deserialize)]
+pub struct SendEmailArgs {
+    to: String,
+    subject: String,
+    body: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SendEmailError {
+    #[error("{0} not set")]
+    MissingConfig(&'static str),
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+    #[error("Invalid recipient '{0}': use an email address such as 'alice@example.com'")]
+    InvalidRecipient(String),
+    #[error("The subject must not be empty or contain line breaks")]
+    InvalidSubject,
+    #[error("The body must not be empty")]
+    EmptyBody,
+    #[error("The body is larger than the {} KiB limit", MAX_BODY_SIZE / 1024)]
+    BodyTooLarge,
+    #[error("Could not build the message: {0}")]
+    InvalidMessage(String),
+    #[error("SMTP error: {0}")]
+    SmtpError(String),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Delivery {
+    /// Accepted by the SMTP server
+    Sent,
+    /// Validated but not sent
+    DryRun,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EmailReceipt {
+    delivery: Delivery,
+    message_id: String,
+    to: String,
+    subject: String,
+}
+
+/// Sends plain-text emails over SMTP. Sending can't be undone, so the tool can
+/// also run in dry-run mode, where every check is made and the message is
+/// built but never sent.
+pub struct SendEmailTool {
+    from: Mailbox,
+    /// `None` in dry-run mode
+    transport: Option<AsyncSmtpTransport<Tokio1Executor>>,
+}
+
+fn env_var(name: &'static str) -> Result<String, SendEmailError> {
+    std::env::var(name).map_err(|_| SendEmailError::MissingConfig(name))
+}
+
+fn parse_from(from: &str) -> Result<Mailbox, SendEmailError> {
+    from.parse()
+        .map_err(|e| SendEmailError::InvalidConfig(format!("EMAIL_FROM {:?}: {}", from, e)))
+}
+
+impl SendEmailTool {
+    /// A tool that validates messages without sending them. Only `EMAIL_FROM`
+    /// is needed.
+    pub fn dry_run() -> Result<Self, SendEmailError> {
+        Ok(Self {
+            from: parse_from(&env_var("EMAIL_FROM")?)?,
+            transport: None,
+        })
+    }
+
+    /// A tool that sends messages through the SMTP server configured with
+    /// `SMTP_HOST`, `SMTP_USERNAME`, `SMTP_PASSWORD` and, optionally,
+    /// `SMTP_PORT`
+    pub fn from_env() -> Result<Self, SendEmailError> {
+        let from = parse_from(&env_var("EMAIL_FROM")?)?;
+        let host = env_var("SMTP_HOST")?;
+        let credentials = Credentials::new(env_var("SMTP_USERNAME")?, env_var("SMTP_PASSWORD")?);
+        let port = match std::env::var("SMTP_PORT") {
+            Ok(port) => port
+                .parse()
+                .map_err(|_| SendEmailError::InvalidConfig(format!("SMTP_PORT {:?}", port)))?,
+            Err(_) => DEFAULT_SMTP_PORT,
+        };
+
+        let transport = AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&host)
+            .map_err(|e| SendEmailError::InvalidConfig(e.to_string()))?
+            .port(port)
+            .credentials(credentials)
+            .timeout(Some(REQUEST_TIMEOUT))
+            .build();
+
+        Ok(Self {
+            from,
+            transport: Some(transport),
+        })
+    }
+}
+
+// Check the arguments and build the message, with a generated Message-ID
+fn build_message(from: &Mailbox, args: &SendEmailArgs) -> Result<Message, SendEmailError> {
+    let to: Mailbox = args
+        .to
+        .trim()
+        .parse()
+        .map_err(|_| SendEmailError::InvalidRecipient(args.to.clone()))?;
+
+    let subject = args.subject.trim();
+    if subject.is_empty() || subject.contains(['\r', '\n']) {
+        return Err(SendEmailError::InvalidSubject);
+    }
+    if args.body.trim().is_empty() {
+        return Err(SendEmailError::EmptyBody);
+    }
+    if args.body.len() > MAX_BODY_SIZE {
+        return Err(SendEmailError::BodyTooLarge);
+    }
+
+    Message::builder()
+        .from(from.clone())
+        .to(to)
+        .subject(subject)
+        .message_id(None)
+        .header(ContentType::TEXT_PLAIN)
+        .body(args.body.clone())
+        .map_err(|e| SendEmailError::InvalidMessage(e.to_string()))
+}
+
+impl Tool for SendEmailTool {
+    const NAME: &'static str = "send_email";
+
+    type Args = SendEmailArgs;
+    type Output = EmailReceipt;
+    type Error = SendEmailError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "send_email".to_string(),
+            description: "Send a plain-text email. The email is sent immediately and cannot be recalled, so only call this when the user has asked for an email to be sent and has given the recipient. Returns whether the email was sent or only validated (dry run), and its Message-ID".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "to": { "type": "string", "description": "The recipient's email address (e.g., 'alice@example.com' or 'Alice <alice@example.com>')" },
+                    "subject": { "type": "string", "description": "A single-line subject" },
+                    "body": { "type": "string", "description": "The plain-text body of the email" },
+                },
+                "required": ["to", "subject", "body"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let message = build_message(&self.from, &args)?;
+        let message_id = message
+            .headers()
+            .get_raw("Message-ID")
+            .unwrap_or_default()
+            .to_string();
+
+        let delivery = match &self.transport {
+            Some(transport) => {
+                // rig requires the tool's future to be `Sync` and lettre's isn't,
+                // so the send runs as its own task
+                let transport = transport.clone();
+                let response = tokio::spawn(async move { transport.send(message).await })
+                    .await
+                    .map_err(|e| SendEmailError::SmtpError(e.to_string()))?
+                    .map_err(|e| SendEmailError::SmtpError(e.to_string()))?;
+                if !response.is_positive() {
+                    return Err(SendEmailError::SmtpError(
+                        response.message().collect::<Vec<_>>().join(" "),
+                    ));
+                }
+                Delivery::Sent
+            }
+            None => Delivery::DryRun,
+        };
+
+        Ok(EmailReceipt {
+            delivery,
+            message_id,
+            to: args.to.trim().to_string(),
+            subject: args.subject.trim().to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(to: &str, subject: &str, body: &str) -> SendEmailArgs {
+        SendEmailArgs {
+            to: to.into(),
+            subject: subject.into(),
+            body: body.into(),
+        }
+    }
+
+    #[test]
+    fn test_build_message() {
+        let from: Mailbox = "Rig Agent <agent@example.com>".parse().unwrap();
+        let message = build_message(
+            &from,
+            &args(" Alice <alice@example.com> ", "Meeting notes", "Hi Alice,\n\nHere are the notes."),
+        )
+        .unwrap();
+
+        assert_eq!(message.headers().get_raw("To"), Some("Alice <alice@example.com>"));
+        assert_eq!(message.headers().get_raw("Subject"), Some("Meeting notes"));
+        assert!(message
+            .headers()
+            .get_raw("Message-ID")
+            .is_some_and(|id| id.starts_with('<') && id.ends_with('>')));
+    }
+
+    #[test]
+    fn test_invalid_arguments_are_rejected() {
+        let from: Mailbox = "agent@example.com".parse().unwrap();
+        assert!(matches!(
+            build_message(&from, &args("alice", "Hi", "Hello")),
+            Err(SendEmailError::InvalidRecipient(_))
+        ));
+        assert!(matches!(
+            build_message(&from, &args("alice@example.com", "Hi\nBcc: eve@example.com", "Hello")),
+            Err(SendEmailError::InvalidSubject)
+        ));
+        assert!(matches!(
+            build_message(&from, &args("alice@example.com", "Hi", "  ")),
+            Err(SendEmailError::EmptyBody)
+        ));
+    }
+}
@@ -0,0 +1,115 @@
+use dotenv::dotenv;
+use rig::extractor::ExtractionError;
+use rig::providers::openai;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Contact details found in a piece of text.
+///
+/// The doc comments end up in the JSON schema the model fills in, so they
+/// double as instructions. Every field is optional because real input rarely
+/// contains all of them, and a required field would push the model to make
+/// one up.
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema)]
+struct ContactInfo {
+    /// The person's full name
+    name: Option<String>,
+    /// Email address, exactly as written
+    email: Option<String>,
+    /// Phone number, including the country code if one is given
+    phone: Option<String>,
+}
+
+impl ContactInfo {
+    fn missing_fields(&self) -> Vec<&'static str> {
+        [
+            ("name", self.name.is_none()),
+            ("email", self.email.is_none()),
+            ("phone", self.phone.is_none()),
+        ]
+        .into_iter()
+        .filter_map(|(field, missing)| missing.then_some(field))
+        .collect()
+    }
+}
+
+const SAMPLES: &[&str] = &[
+    "Hi, I'm Jane Doe. You can reach me at jane.doe@example.com or call +1 555 0100 after 5pm.",
+    "Thanks for the meeting! Ping me at marco@example.org - Marco",
+    "Call the front desk at 020 7946 0000 to book a room.",
+    "The weather is lovely today.",
+];
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // The extractor gives the model a tool whose arguments follow the schema
+    // of `ContactInfo`, then deserializes whatever the model passes to it
+    let extractor = openai_client
+        .extractor::<ContactInfo>(openai::GPT_4O)
+        .preamble("Only extract details that appear in the text. Leave a field empty rather than guessing.")
+        .build();
+
+    // Extract from the text given on the command line, or from the samples
+    let input = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let inputs: Vec<&str> = if input.is_empty() {
+        SAMPLES.to_vec()
+    } else {
+        vec![input.as_str()]
+    };
+
+    for text in inputs {
+        println!("Input: {}", text);
+
+        match extractor.extract(text).await {
+            Ok(contact) => {
+                println!("Extracted: {}", serde_json::to_string_pretty(&contact)?);
+
+                let missing = contact.missing_fields();
+                if missing.len() == 3 {
+                    println!("No contact details found");
+                } else if !missing.is_empty() {
+                    println!("Incomplete contact, missing: {}", missing.join(", "));
+                }
+            }
+            Err(ExtractionError::NoData) => println!("No contact details found"),
+            // The model answered in prose instead of calling the extraction
+            // tool, which can happen when there is nothing to extract
+            Err(ExtractionError::DeserializationError(e)) => {
+                println!("The model did not return contact details: {}", e)
+            }
+            Err(e) => eprintln!("Extraction failed: {}", e),
+        }
+
+        println!();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_fields() {
+        let contact = ContactInfo {
+            name: Some("Marco".into()),
+            email: Some("marco@example.org".into()),
+            phone: None,
+        };
+        assert_eq!(contact.missing_fields(), vec!["phone"]);
+        assert_eq!(ContactInfo::default().missing_fields(), vec!["name", "email", "phone"]);
+    }
+
+    #[test]
+    fn test_partial_model_output_deserializes() {
+        // Models often omit fields they have no value for instead of sending null
+        let contact: ContactInfo = serde_json::from_str(r#"{"email": "jane@example.com"}"#).unwrap();
+        assert_eq!(contact.missing_fields(), vec!["name", "phone"]);
+    }
+}
@@ -0,0 +1,294 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use scraper::{ElementRef, Html, Selector};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+// Maximum time to wait for the page before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+// Largest page downloaded, in bytes. Bigger pages are refused rather than cut
+// off, since a truncated HTML document may lose its main content.
+const MAX_PAGE_SIZE: usize = 2 * 1024 * 1024;
+
+// Most characters of extracted text returned to the model
+const MAX_TEXT_LENGTH: usize = 20_000;
+
+// Elements whose text is never part of the main content
+const SKIPPED_ELEMENTS: &[&str] = &[
+    "script", "style", "noscript", "template", "svg", "nav", "header", "footer", "aside", "form",
+];
+
+// Elements that each become one paragraph of the extracted text
+const BLOCK_ELEMENTS: &str = "h1, h2, h3, h4, h5, h6, p, li, pre, blockquote, dt, dd, th, td, figcaption";
+
+#[derive(Deserialize)]
+pub struct FetchUrlArgs {
+    url: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum FetchUrlError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("Invalid URL '{0}': {1}")]
+    InvalidUrl(String, String),
+    #[error("Unsupported URL scheme '{0}': only http and https URLs can be fetched")]
+    UnsupportedScheme(String),
+    #[error("The page is larger than the {} KiB limit", MAX_PAGE_SIZE / 1024)]
+    TooLarge,
+    #[error("Unsupported content type '{0}': only HTML and plain text pages can be read")]
+    UnsupportedContentType(String),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct PageText {
+    /// The URL the page was fetched from, after redirects
+    url: String,
+    title: Option<String>,
+    text: String,
+    /// Whether `text` was cut at `MAX_TEXT_LENGTH` characters
+    truncated: bool,
+}
+
+pub struct FetchUrlTool;
+
+// Parse `url`, refusing anything but http(s) so the model can't read local
+// files or talk to other protocols
+fn check_url(url: &str) -> Result<reqwest::Url, FetchUrlError> {
+    let parsed = reqwest::Url::parse(url.trim())
+        .map_err(|e| FetchUrlError::InvalidUrl(url.to_string(), e.to_string()))?;
+    match parsed.scheme() {
+        "http" | "https" => Ok(parsed),
+        scheme => Err(FetchUrlError::UnsupportedScheme(scheme.to_string())),
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// The text of one block element, with headings and list items marked up like
+// markdown so the model can see the structure of the page
+fn block_text(element: ElementRef) -> String {
+    let name = element.value().name();
+    if name == "pre" {
+        return element.text().collect::<String>().trim().to_string();
+    }
+
+    let text = collapse_whitespace(&element.text().collect::<String>());
+    match name.strip_prefix('h').and_then(|level| level.parse::<usize>().ok()) {
+        Some(level) => format!("{} {}", "#".repeat(level), text),
+        None if name == "li" => format!("- {}", text),
+        None => text,
+    }
+}
+
+// Extract the title and main text of an HTML page. The text is taken from the
+// first `<article>` or `<main>` element if there is one, otherwise from the
+// whole body, leaving out navigation, scripts and other page furniture.
+fn extract_main_text(html: &str) -> (Option<String>, String) {
+    let document = Html::parse_document(html);
+
+    let title = Selector::parse("title")
+        .ok()
+        .and_then(|selector| document.select(&selector).next())
+        .map(|title| collapse_whitespace(&title.text().collect::<String>()))
+        .filter(|title| !title.is_empty());
+
+    let root = ["article", "main", "[role=main]", "body"]
+        .iter()
+        .filter_map(|selector| Selector::parse(selector).ok())
+        .find_map(|selector| document.select(&selector).next());
+    let Some(root) = root else {
+        return (title, String::new());
+    };
+
+    let blocks = Selector::parse(BLOCK_ELEMENTS).expect("valid selector");
+    let mut paragraphs = Vec::new();
+    for element in root.select(&blocks) {
+        // Skip page furniture, and blocks nested in another block (such as a
+        // paragraph inside a list item) whose text is already included
+        let nested_or_skipped = element
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .take_while(|ancestor| ancestor.id() != root.id())
+            .any(|ancestor| {
+                SKIPPED_ELEMENTS.contains(&ancestor.value().name()) || blocks.matches(&ancestor)
+            });
+        if nested_or_skipped {
+            continue;
+        }
+
+        let text = block_text(element);
+        if !text.trim_start_matches(['#', '-', ' ']).is_empty() {
+            paragraphs.push(text);
+        }
+    }
+
+    // Pages built from bare `<div>`s have no block elements to pick out
+    if paragraphs.is_empty() {
+        return (title, collapse_whitespace(&root.text().collect::<String>()));
+    }
+
+    (title, paragraphs.join("\n\n"))
+}
+
+// Cut `text` to at most `MAX_TEXT_LENGTH` characters
+fn truncate(text: String) -> (String, bool) {
+    match text.char_indices().nth(MAX_TEXT_LENGTH) {
+        Some((end, _)) => (text[..end].to_string(), true),
+        None => (text, false),
+    }
+}
+
+impl Tool for FetchUrlTool {
+    const NAME: &'static str = "fetch_url";
+
+    type Args = FetchUrlArgs;
+    type Output = PageText;
+    type Error = FetchUrlError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "fetch_url".to_string(),
+            description: "Download a web page and return its title and main text, without navigation, scripts or styling. Use it to read an article or documentation page the user links to. Only http and https URLs are supported".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "The full URL of the page, including the scheme (e.g., 'https://www.rust-lang.org/learn')" },
+                },
+                "required": ["url"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let url = check_url(&args.url)?;
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("rig-examples/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| FetchUrlError::HttpRequestFailed(e.to_string()))?;
+
+        let mut response = client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| FetchUrlError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+        let final_url = response.url().to_string();
+
+        if !status.is_success() {
+            return Err(FetchUrlError::ApiError(format!("Status: {}", status)));
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("text/html")
+            .to_ascii_lowercase();
+        let is_html = content_type.starts_with("text/html")
+            || content_type.starts_with("application/xhtml+xml");
+        if !is_html && !content_type.starts_with("text/plain") {
+            return Err(FetchUrlError::UnsupportedContentType(content_type));
+        }
+
+        if response
+            .content_length()
+            .is_some_and(|length| length > MAX_PAGE_SIZE as u64)
+        {
+            return Err(FetchUrlError::TooLarge);
+        }
+
+        // The length header is optional, so the cap is also enforced while reading
+        let mut body = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| FetchUrlError::HttpRequestFailed(e.to_string()))?
+        {
+            if body.len() + chunk.len() > MAX_PAGE_SIZE {
+                return Err(FetchUrlError::TooLarge);
+            }
+            body.extend_from_slice(&chunk);
+        }
+        let body = String::from_utf8_lossy(&body);
+
+        let (title, text) = if is_html {
+            extract_main_text(&body)
+        } else {
+            (None, body.trim().to_string())
+        };
+        if text.is_empty() {
+            return Err(FetchUrlError::InvalidResponse(
+                "the page has no readable text".to_string(),
+            ));
+        }
+        let (text, truncated) = truncate(text);
+
+        Ok(PageText {
+            url: final_url,
+            title,
+            text,
+            truncated,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_url() {
+        assert!(check_url("https://www.rust-lang.org/learn").is_ok());
+        assert!(check_url(" http://example.com ").is_ok());
+        assert!(matches!(
+            check_url("file:///etc/passwd"),
+            Err(FetchUrlError::UnsupportedScheme(scheme)) if scheme == "file"
+        ));
+        assert!(matches!(
+            check_url("www.rust-lang.org"),
+            Err(FetchUrlError::InvalidUrl(..))
+        ));
+    }
+
+    #[test]
+    fn test_extract_main_text() {
+        let html = r#"
+            <html>
+              <head><title>  Learning   Rust </title><style>p { color: red; }</style></head>
+              <body>
+                <nav><ul><li>Home</li><li>Blog</li></ul></nav>
+                <article>
+                  <h1>Getting started</h1>
+                  <p>Rust is a   systems programming
+                     language.</p>
+                  <ul><li><p>Fast</p></li><li>Reliable</li></ul>
+                  <script>trackPageView();</script>
+                  <pre>fn main() {
+    println!("Hello");
+}</pre>
+                </article>
+                <footer><p>Copyright</p></footer>
+              </body>
+            </html>"#;
+
+        let (title, text) = extract_main_text(html);
+        assert_eq!(title.as_deref(), Some("Learning Rust"));
+        assert_eq!(
+            text,
+            "# Getting started\n\nRust is a systems programming language.\n\n- Fast\n\n- Reliable\n\nfn main() {\n    println!(\"Hello\");\n}"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+mod fetch_url_tool;
+
+use crate::fetch_url_tool::FetchUrlTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Rig answers a prompt with the output of the first tool call the model
+    // makes, so reading the page and answering from it are two steps. The
+    // reader only fetches the page with the FetchUrlTool.
+    let reader = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("Call the fetch_url tool on the URL in the user's request.")
+        .tool(FetchUrlTool)
+        .build();
+
+    // The assistant never sees the tool; it answers from the page the reader fetched
+    let assistant = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a research assistant. Answer the user's request from the page you are given, as returned by a fetch_url tool. If its `truncated` field is true, say that your answer only covers the beginning of the page.")
+        .build();
+
+    // Take the request from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "Summarize https://www.rust-lang.org/learn in three bullet points.".to_string()
+    } else {
+        query
+    };
+
+    // Step 1: fetch the page
+    let page = reader.prompt(&query).await?;
+
+    // Step 2: the page becomes part of the assistant's prompt
+    let response = assistant
+        .prompt(&format!("Request: {}\n\nPage:\n{}", query, page))
+        .await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
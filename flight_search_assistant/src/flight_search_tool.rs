@@ -1,10 +1,16 @@
+use crate::retry::RetryPolicy;
 use chrono::Utc;
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
 use rig::completion::ToolDefinition;
 use rig::tool::Tool;
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::env;
+use std::time::Duration;
+
+// Maximum time to wait for the flight search API before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
 
 #[derive(Deserialize)]
 pub struct FlightSearchArgs {
@@ -29,6 +35,8 @@ pub enum FlightSearchError {
     InvalidResponse,
     #[error("API error: {0}")]
     ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
     #[error("Missing API key")]
     MissingApiKey,
 }
@@ -48,6 +56,32 @@ pub struct FlightOption {
 
 pub struct FlightSearchTool;
 
+// Build an HTTP client that gives up on requests after `timeout`
+fn http_client(timeout: Duration) -> Result<reqwest::Client, FlightSearchError> {
+    reqwest::Client::builder()
+        .timeout(timeout)
+        .build()
+        .map_err(|e| FlightSearchError::HttpRequestFailed(e.to_string()))
+}
+
+// Map a reqwest error, calling out timeouts explicitly
+fn request_error(e: reqwest::Error) -> FlightSearchError {
+    if e.is_timeout() {
+        FlightSearchError::HttpRequestFailed(format!("Request timed out: {}", e))
+    } else {
+        FlightSearchError::HttpRequestFailed(e.to_string())
+    }
+}
+
+impl From<BodyError> for FlightSearchError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => FlightSearchError::TooLarge,
+            BodyError::Read(e) => request_error(e),
+        }
+    }
+}
+
 impl Tool for FlightSearchTool {
     const NAME: &'static str = "search_flights";
 
@@ -114,31 +148,30 @@ impl Tool for FlightSearchTool {
         query_params.insert("nonstop", nonstop);
 
         // Make the API request
-        let client = reqwest::Client::new();
-        let response = client
-            .get("https://tripadvisor16.p.rapidapi.com/api/v1/flights/searchFlights")
-            .headers({
-                let mut headers = reqwest::header::HeaderMap::new();
-                headers.insert(
-                    "X-RapidAPI-Host",
-                    "tripadvisor16.p.rapidapi.com".parse().unwrap(),
-                );
-                headers.insert("X-RapidAPI-Key", api_key.parse().unwrap());
-                headers
+        let client = http_client(REQUEST_TIMEOUT)?;
+        let response = RetryPolicy::default()
+            .send(|| {
+                client
+                    .get("https://tripadvisor16.p.rapidapi.com/api/v1/flights/searchFlights")
+                    .headers({
+                        let mut headers = reqwest::header::HeaderMap::new();
+                        headers.insert(
+                            "X-RapidAPI-Host",
+                            "tripadvisor16.p.rapidapi.com".parse().unwrap(),
+                        );
+                        headers.insert("X-RapidAPI-Key", api_key.parse().unwrap());
+                        headers
+                    })
+                    .query(&query_params)
             })
-            .query(&query_params)
-            .send()
             .await
-            .map_err(|e| FlightSearchError::HttpRequestFailed(e.to_string()))?;
+            .map_err(request_error)?;
 
         // Get the status code before consuming `response`
         let status = response.status();
 
         // Read the response text (this consumes `response`)
-        let text = response
-            .text()
-            .await
-            .map_err(|e| FlightSearchError::HttpRequestFailed(e.to_string()))?;
+        let text = read_body(response).await?;
 
         // Print the raw API response for debugging
         // println!("Raw API response:\n{}", text);
@@ -181,11 +214,11 @@ impl Tool for FlightSearchTool {
                 if let Some(segments) = flight
                     .get("segments")
                     .and_then(|s| s.as_array())
-                    .and_then(|s| s.get(0))
+                    .and_then(|s| s.first())
                 {
                     // Extract legs from the first segment
                     if let Some(legs) = segments.get("legs").and_then(|l| l.as_array()) {
-                        let first_leg = legs.get(0).unwrap_or(&empty_leg);
+                        let first_leg = legs.first().unwrap_or(&empty_leg);
                         let last_leg = legs.last().unwrap_or(&empty_leg); 
                         
                         // Extract airline name
@@ -332,3 +365,26 @@ impl Tool for FlightSearchTool {
         Ok(output)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    #[tokio::test]
+    async fn test_timeout_maps_to_http_request_failed() {
+        // Accepts connections but never answers, so the request can only time out
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let client = http_client(Duration::from_millis(1)).unwrap();
+        let error = client.get(&url).send().await.map_err(request_error).unwrap_err();
+
+        match error {
+            FlightSearchError::HttpRequestFailed(message) => {
+                assert!(message.starts_with("Request timed out"), "{}", message)
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+    }
+}
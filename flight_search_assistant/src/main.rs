@@ -1,4 +1,5 @@
 mod flight_search_tool;
+mod retry;
 
 use crate::flight_search_tool::FlightSearchTool;
 use rig::completion::Prompt;
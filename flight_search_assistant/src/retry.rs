@@ -0,0 +1,133 @@
+use rand::Rng;
+use std::time::Duration;
+
+/// How tools retry requests that fail for reasons likely to go away on their
+/// own: timeouts, dropped connections and 5xx responses. 4xx responses mean
+/// the request itself is wrong, so they are returned right away.
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    /// Delay before the first retry; doubled after every failed attempt
+    pub initial_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Send the request built by `build` until it succeeds or the attempts run
+    /// out. A fresh request is built for every attempt since a
+    /// `RequestBuilder` can only be sent once.
+    ///
+    /// After the last attempt the final response is returned as-is, even if it
+    /// is a 5xx, so callers handle error statuses the same way as before.
+    pub async fn send<F>(&self, mut build: F) -> Result<reqwest::Response, reqwest::Error>
+    where
+        F: FnMut() -> reqwest::RequestBuilder,
+    {
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 1;
+
+        loop {
+            let result = build().send().await;
+            let retryable = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(e) => e.is_timeout() || e.is_connect() || e.is_request(),
+            };
+
+            if !retryable || attempt >= self.max_attempts {
+                return result;
+            }
+
+            tokio::time::sleep(with_jitter(backoff)).await;
+            backoff *= 2;
+            attempt += 1;
+        }
+    }
+}
+
+/// Add up to 50% random jitter so clients that failed together don't all
+/// retry at the same moment
+fn with_jitter(backoff: Duration) -> Duration {
+    let max_jitter = backoff.as_millis() as u64 / 2;
+    backoff + Duration::from_millis(rand::thread_rng().gen_range(0..=max_jitter))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve `statuses` in order, one per connection, and count the requests
+    async fn flaky_server(statuses: Vec<u16>) -> (String, Arc<AtomicU32>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(AtomicU32::new(0));
+
+        let counter = Arc::clone(&requests);
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buffer = [0; 1024];
+                let _ = socket.read(&mut buffer).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                let response = format!(
+                    "HTTP/1.1 {} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    status
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+            }
+        });
+
+        (url, requests)
+    }
+
+    fn fast_policy() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_are_retried() {
+        let (url, requests) = flaky_server(vec![503, 502, 200]).await;
+        let client = reqwest::Client::new();
+
+        let response = fast_policy().send(|| client.get(&url)).await.unwrap();
+
+        assert_eq!(response.status(), 200);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_attempts() {
+        let (url, requests) = flaky_server(vec![500, 500, 500, 200]).await;
+        let client = reqwest::Client::new();
+
+        let response = fast_policy().send(|| client.get(&url)).await.unwrap();
+
+        assert_eq!(response.status(), 500);
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let (url, requests) = flaky_server(vec![429, 200]).await;
+        let client = reqwest::Client::new();
+
+        let response = fast_policy().send(|| client.get(&url)).await.unwrap();
+
+        assert_eq!(response.status(), 429);
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
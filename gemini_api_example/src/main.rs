@@ -0,0 +1,60 @@
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::gemini::{self, completion::gemini_api_types::GenerationConfig};
+use std::io::{self, Write};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Gemini keys come from Google AI Studio and are read from GEMINI_API_KEY
+    // rather than OPENAI_API_KEY. `gemini::Client::from_env` panics when the
+    // variable is missing, so read it ourselves to fail with a clear error.
+    let api_key = std::env::var("GEMINI_API_KEY").map_err(|_| "GEMINI_API_KEY not set")?;
+    let gemini_client = gemini::Client::new(&api_key);
+
+    // Gemini-specific sampling options are passed through `additional_params`
+    // as a `GenerationConfig`. `temperature` and `max_tokens` set on the
+    // builder take precedence over the values in it.
+    let generation_config = GenerationConfig {
+        top_k: Some(40),
+        top_p: Some(0.95),
+        ..Default::default()
+    };
+
+    // Model names are Gemini's own (e.g. `gemini-1.5-flash`) instead of the
+    // `gpt-*` names used with the OpenAI client.
+    let agent = gemini_client
+        .agent(gemini::completion::GEMINI_1_5_FLASH)
+        .preamble("You are a helpful assistant. Keep your answers short and to the point.")
+        .temperature(0.7)
+        .additional_params(serde_json::to_value(generation_config)?)
+        .build();
+
+    println!("Ask Gemini anything (type 'exit' to quit)");
+
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            break;
+        }
+
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        if input.eq_ignore_ascii_case("exit") {
+            break;
+        }
+
+        match agent.prompt(input).await {
+            Ok(response) => println!("{}\n", response),
+            Err(e) => eprintln!("Error: {}\n", e),
+        }
+    }
+
+    Ok(())
+}
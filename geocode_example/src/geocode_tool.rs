@@ -0,0 +1,278 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+const SEARCH_URL: &str = "https://nominatim.openstreetmap.org/search";
+const REVERSE_URL: &str = "https://nominatim.openstreetmap.org/reverse";
+
+// Nominatim's usage policy asks every application to identify itself with its
+// own User-Agent rather than a library default
+const USER_AGENT: &str = concat!(
+    "rig-examples-geocode/",
+    env!("CARGO_PKG_VERSION"),
+    " (+https://github.com/0xPlaygrounds/rig)"
+);
+
+// Maximum time to wait for Nominatim before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// How many candidate places a search returns, so the model can tell the
+// user when a name is ambiguous
+const MAX_CANDIDATES: usize = 5;
+
+#[derive(Deserialize)]
+pub struct GeocodeArgs {
+    place: Option<String>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GeocodeError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Give either a place name, or both lat and lon to look up an address")]
+    InvalidArgs,
+    #[error("Invalid coordinates {0}, {1}: lat must be between -90 and 90 and lon between -180 and 180")]
+    InvalidCoordinates(f64, f64),
+    #[error("No place found for '{0}'")]
+    NotFound(String),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Place {
+    /// Full address, e.g. "Paris, Île-de-France, France métropolitaine, France"
+    name: String,
+    lat: f64,
+    lon: f64,
+    /// What the place is, e.g. "city", "administrative" or "museum"
+    kind: String,
+}
+
+// A result as Nominatim returns it, with coordinates as strings
+#[derive(Deserialize)]
+struct NominatimPlace {
+    display_name: String,
+    lat: String,
+    lon: String,
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+// The reverse endpoint answers 200 with an error object when there is nothing
+// at the coordinates, e.g. in the middle of the ocean
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ReverseResponse {
+    Found(NominatimPlace),
+    Error { error: String },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct GeocodeOutput {
+    /// Best match first
+    places: Vec<Place>,
+}
+
+pub struct GeocodeTool;
+
+// What the arguments ask for
+enum Lookup<'a> {
+    Search(&'a str),
+    Reverse(f64, f64),
+}
+
+fn lookup(args: &GeocodeArgs) -> Result<Lookup<'_>, GeocodeError> {
+    let place = args.place.as_deref().map(str::trim).filter(|place| !place.is_empty());
+    match (place, args.lat, args.lon) {
+        (Some(place), None, None) => Ok(Lookup::Search(place)),
+        (None, Some(lat), Some(lon)) => {
+            if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lon) {
+                return Err(GeocodeError::InvalidCoordinates(lat, lon));
+            }
+            Ok(Lookup::Reverse(lat, lon))
+        }
+        _ => Err(GeocodeError::InvalidArgs),
+    }
+}
+
+impl TryFrom<NominatimPlace> for Place {
+    type Error = GeocodeError;
+
+    fn try_from(place: NominatimPlace) -> Result<Self, Self::Error> {
+        let coordinate = |value: &str| {
+            value
+                .parse()
+                .map_err(|_| GeocodeError::InvalidResponse(format!("invalid coordinate '{}'", value)))
+        };
+        Ok(Place {
+            lat: coordinate(&place.lat)?,
+            lon: coordinate(&place.lon)?,
+            name: place.display_name,
+            kind: place.kind,
+        })
+    }
+}
+
+// Parse a search response, which lists matches from most to least important
+fn parse_search(place: &str, text: &str) -> Result<GeocodeOutput, GeocodeError> {
+    let results: Vec<NominatimPlace> =
+        serde_json::from_str(text).map_err(|e| GeocodeError::InvalidResponse(e.to_string()))?;
+    if results.is_empty() {
+        return Err(GeocodeError::NotFound(place.to_string()));
+    }
+
+    Ok(GeocodeOutput {
+        places: results
+            .into_iter()
+            .take(MAX_CANDIDATES)
+            .map(Place::try_from)
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn parse_reverse(lat: f64, lon: f64, text: &str) -> Result<GeocodeOutput, GeocodeError> {
+    match serde_json::from_str(text).map_err(|e| GeocodeError::InvalidResponse(e.to_string()))? {
+        ReverseResponse::Found(place) => Ok(GeocodeOutput {
+            places: vec![place.try_into()?],
+        }),
+        ReverseResponse::Error { error } => Err(GeocodeError::NotFound(format!("{}, {} ({})", lat, lon, error))),
+    }
+}
+
+impl From<BodyError> for GeocodeError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => GeocodeError::TooLarge,
+            BodyError::Read(e) => GeocodeError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for GeocodeTool {
+    const NAME: &'static str = "geocode";
+
+    type Args = GeocodeArgs;
+    type Output = GeocodeOutput;
+    type Error = GeocodeError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "geocode".to_string(),
+            description: "Find the coordinates of a place from its name or address, or the address at a latitude and longitude, using OpenStreetMap. Searching a name returns up to 5 candidates, best match first; if they are different places, ask the user which one they meant".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "place": { "type": "string", "description": "A place name or address to look up (e.g., 'Springfield, Illinois' or 'Eiffel Tower')" },
+                    "lat": { "type": "number", "description": "Latitude to find the address of, together with lon" },
+                    "lon": { "type": "number", "description": "Longitude to find the address of, together with lat" },
+                },
+                "required": []
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let lookup = lookup(&args)?;
+        let (url, query) = match lookup {
+            Lookup::Search(place) => (
+                SEARCH_URL,
+                vec![
+                    ("q", place.to_string()),
+                    ("format", "jsonv2".to_string()),
+                    ("limit", MAX_CANDIDATES.to_string()),
+                ],
+            ),
+            Lookup::Reverse(lat, lon) => (
+                REVERSE_URL,
+                vec![
+                    ("lat", lat.to_string()),
+                    ("lon", lon.to_string()),
+                    ("format", "jsonv2".to_string()),
+                ],
+            ),
+        };
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(USER_AGENT)
+            .build()
+            .map_err(|e| GeocodeError::HttpRequestFailed(e.to_string()))?;
+
+        let response = client
+            .get(url)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| GeocodeError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        let text = read_body(response).await?;
+
+        if !status.is_success() {
+            return Err(GeocodeError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        match lookup {
+            Lookup::Search(place) => parse_search(place, &text),
+            Lookup::Reverse(lat, lon) => parse_reverse(lat, lon, &text),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_search_returns_candidates() {
+        let text = r#"[
+            {"place_id": 1, "lat": "39.7990175", "lon": "-89.6439575", "category": "boundary", "type": "administrative", "importance": 0.6, "display_name": "Springfield, Sangamon County, Illinois, United States"},
+            {"place_id": 2, "lat": "37.2081729", "lon": "-93.2922715", "category": "place", "type": "city", "importance": 0.5, "display_name": "Springfield, Greene County, Missouri, United States"}
+        ]"#;
+
+        let output = parse_search("Springfield", text).unwrap();
+        assert_eq!(output.places.len(), 2);
+        assert_eq!(
+            output.places[0],
+            Place {
+                name: "Springfield, Sangamon County, Illinois, United States".into(),
+                lat: 39.7990175,
+                lon: -89.6439575,
+                kind: "administrative".into(),
+            }
+        );
+
+        assert!(matches!(
+            parse_search("Atlantis", "[]"),
+            Err(GeocodeError::NotFound(place)) if place == "Atlantis"
+        ));
+    }
+
+    #[test]
+    fn test_parse_reverse() {
+        let text = r#"{"place_id": 3, "lat": "48.8582599", "lon": "2.2945006", "category": "tourism", "type": "attraction", "display_name": "Tour Eiffel, 5, Avenue Anatole France, Paris, France", "address": {"city": "Paris"}}"#;
+        let output = parse_reverse(48.8584, 2.2945, text).unwrap();
+        assert_eq!(output.places[0].name, "Tour Eiffel, 5, Avenue Anatole France, Paris, France");
+        assert_eq!(output.places[0].kind, "attraction");
+
+        assert!(matches!(
+            parse_reverse(0.0, -30.0, r#"{"error": "Unable to geocode"}"#),
+            Err(GeocodeError::NotFound(_))
+        ));
+    }
+}
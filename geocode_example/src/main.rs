@@ -0,0 +1,37 @@
+mod geocode_tool;
+
+use crate::geocode_tool::GeocodeTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the GeocodeTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a helpful assistant. Use the geocode tool to find coordinates of places and addresses at coordinates instead of relying on memory. If a name matches several different places, list them and ask which one the user meant.")
+        .tool(GeocodeTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example.
+    // Rig answers with the output of the first tool call, so the example needs
+    // only one.
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What are the coordinates of the Colosseum?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::env;
+use std::time::Duration;
+
+const SEARCH_REPOSITORIES_URL: &str = "https://api.github.com/search/repositories";
+
+// Maximum time to wait for the GitHub API before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Number of results returned when the model doesn't ask for a specific count
+const DEFAULT_RESULT_COUNT: usize = 5;
+const MAX_RESULT_COUNT: usize = 20;
+
+#[derive(Deserialize)]
+pub struct GitHubSearchArgs {
+    query: String,
+    count: Option<usize>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GitHubSearchError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("GitHub rate limit exceeded; try again later or set GITHUB_TOKEN for a higher limit")]
+    RateLimited,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Repository {
+    name: String,
+    description: Option<String>,
+    stars: u64,
+    url: String,
+}
+
+// The parts of GitHub's search response we use
+#[derive(Deserialize)]
+struct SearchResponse {
+    items: Vec<SearchItem>,
+}
+
+#[derive(Deserialize)]
+struct SearchItem {
+    full_name: String,
+    description: Option<String>,
+    stargazers_count: u64,
+    html_url: String,
+}
+
+pub struct GitHubSearchTool;
+
+// GitHub reports an exhausted rate limit as 403 (or 429) with no requests
+// remaining; other 403s are permission problems
+fn is_rate_limited(status: StatusCode, headers: &HeaderMap) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || (status == StatusCode::FORBIDDEN
+            && headers
+                .get("x-ratelimit-remaining")
+                .is_some_and(|remaining| remaining == "0"))
+}
+
+fn parse_repositories(text: &str, count: usize) -> Result<Vec<Repository>, GitHubSearchError> {
+    let response: SearchResponse = serde_json::from_str(text)
+        .map_err(|e| GitHubSearchError::InvalidResponse(e.to_string()))?;
+
+    Ok(response
+        .items
+        .into_iter()
+        .take(count)
+        .map(|item| Repository {
+            name: item.full_name,
+            description: item.description,
+            stars: item.stargazers_count,
+            url: item.html_url,
+        })
+        .collect())
+}
+
+impl From<BodyError> for GitHubSearchError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => GitHubSearchError::TooLarge,
+            BodyError::Read(e) => GitHubSearchError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for GitHubSearchTool {
+    const NAME: &'static str = "search_github_repositories";
+
+    type Args = GitHubSearchArgs;
+    type Output = Vec<Repository>;
+    type Error = GitHubSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "search_github_repositories".to_string(),
+            description: "Search GitHub for repositories and return the most starred matches with their description and URL".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "GitHub search query; supports qualifiers such as 'language:rust' or 'topic:llm' (e.g., 'vector database language:rust')" },
+                    "count": { "type": "integer", "description": "Number of repositories to return, from 1 to 20 (default 5)" },
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let count = args
+            .count
+            .unwrap_or(DEFAULT_RESULT_COUNT)
+            .clamp(1, MAX_RESULT_COUNT);
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| GitHubSearchError::HttpRequestFailed(e.to_string()))?;
+
+        // GitHub rejects requests without a User-Agent
+        let mut request = client
+            .get(SEARCH_REPOSITORIES_URL)
+            .header("User-Agent", "rig-github-search-example")
+            .header("Accept", "application/vnd.github+json")
+            .query(&[
+                ("q", args.query.as_str()),
+                ("sort", "stars"),
+                ("per_page", &count.to_string()),
+            ]);
+
+        // The token is optional: it only raises the rate limit
+        if let Ok(token) = env::var("GITHUB_TOKEN") {
+            request = request.bearer_auth(token);
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| GitHubSearchError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status and headers before consuming `response`
+        let status = response.status();
+        let rate_limited = is_rate_limited(status, response.headers());
+
+        let text = read_body(response).await?;
+
+        if rate_limited {
+            return Err(GitHubSearchError::RateLimited);
+        }
+        if !status.is_success() {
+            return Err(GitHubSearchError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        parse_repositories(&text, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_repositories() {
+        let text = r#"{
+            "total_count": 2,
+            "items": [
+                { "full_name": "0xPlaygrounds/rig", "description": "Build LLM apps in Rust", "stargazers_count": 3000, "html_url": "https://github.com/0xPlaygrounds/rig" },
+                { "full_name": "someone/other", "description": null, "stargazers_count": 12, "html_url": "https://github.com/someone/other" }
+            ]
+        }"#;
+
+        let repositories = parse_repositories(text, 5).unwrap();
+        assert_eq!(repositories.len(), 2);
+        assert_eq!(
+            repositories[0],
+            Repository {
+                name: "0xPlaygrounds/rig".into(),
+                description: Some("Build LLM apps in Rust".into()),
+                stars: 3000,
+                url: "https://github.com/0xPlaygrounds/rig".into(),
+            }
+        );
+        assert_eq!(repositories[1].description, None);
+    }
+
+    #[test]
+    fn test_rate_limit_detection() {
+        let mut exhausted = HeaderMap::new();
+        exhausted.insert("x-ratelimit-remaining", "0".parse().unwrap());
+        let mut remaining = HeaderMap::new();
+        remaining.insert("x-ratelimit-remaining", "42".parse().unwrap());
+
+        assert!(is_rate_limited(StatusCode::FORBIDDEN, &exhausted));
+        assert!(is_rate_limited(StatusCode::TOO_MANY_REQUESTS, &HeaderMap::new()));
+        assert!(!is_rate_limited(StatusCode::FORBIDDEN, &remaining));
+        assert!(!is_rate_limited(StatusCode::OK, &exhausted));
+    }
+}
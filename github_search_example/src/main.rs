@@ -0,0 +1,35 @@
+mod github_search_tool;
+
+use crate::github_search_tool::GitHubSearchTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the GitHubSearchTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a coding assistant. Use the search_github_repositories tool to recommend open source projects, and include their URLs.")
+        .tool(GitHubSearchTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What are the most popular Rust web frameworks?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
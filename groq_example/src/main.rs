@@ -0,0 +1,56 @@
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+use std::io::{self, Write};
+use std::time::Instant;
+
+/// Groq's OpenAI-compatible API lives under `/openai`. rig's OpenAI client
+/// appends `/v1/chat/completions` itself, so the base URL stops before `/v1`.
+const GROQ_API_BASE_URL: &str = "https://api.groq.com/openai";
+const DEFAULT_GROQ_MODEL: &str = "llama-3.3-70b-versatile";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let api_key = std::env::var("GROQ_API_KEY").map_err(|_| "GROQ_API_KEY not set")?;
+    let model = std::env::var("GROQ_MODEL").unwrap_or_else(|_| DEFAULT_GROQ_MODEL.to_string());
+
+    // Groq serves an OpenAI-compatible API, so rig's OpenAI client works
+    // as-is once it points at Groq with a Groq key
+    let groq_client = openai::Client::from_url(&api_key, GROQ_API_BASE_URL);
+
+    let agent = groq_client
+        .agent(&model)
+        .preamble("You are a helpful assistant. Keep your answers short and to the point.")
+        .build();
+
+    println!("Chatting with {} on Groq (type 'exit' to quit)", model);
+
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            break;
+        }
+
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        if input.eq_ignore_ascii_case("exit") {
+            break;
+        }
+
+        // Timed to show off Groq's low latency
+        let started = Instant::now();
+        match agent.prompt(input).await {
+            Ok(response) => println!("{}\n({:.2}s)\n", response, started.elapsed().as_secs_f64()),
+            Err(e) => eprintln!("Error: {}\n", e),
+        }
+    }
+
+    Ok(())
+}
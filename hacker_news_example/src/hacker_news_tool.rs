@@ -0,0 +1,325 @@
+use futures::future::join_all;
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+const API_BASE_URL: &str = "https://hacker-news.firebaseio.com/v0";
+
+// Where an item is discussed on the site
+const ITEM_URL: &str = "https://news.ycombinator.com/item?id=";
+
+// Maximum time to wait for the API before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Stories listed unless the model asks for a different number, and the most
+// it may ask for. Each story is a request of its own.
+const DEFAULT_LIMIT: usize = 10;
+const MAX_LIMIT: usize = 30;
+
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StoryList {
+    #[default]
+    Top,
+    New,
+    Best,
+}
+
+impl StoryList {
+    fn endpoint(self) -> &'static str {
+        match self {
+            StoryList::Top => "topstories",
+            StoryList::New => "newstories",
+            StoryList::Best => "beststories",
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct HackerNewsArgs {
+    #[serde(default)]
+    list: StoryList,
+    limit: Option<usize>,
+    id: Option<u64>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum HackerNewsError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("No item with id {0}")]
+    NotFound(u64),
+    #[error("Item {0} is a {1}, not a story or comment")]
+    UnsupportedItem(u64, String),
+}
+
+/// An item as the API returns it. Every field but `id` is missing from some
+/// kinds of items, and from deleted ones.
+#[derive(Deserialize)]
+struct HnItem {
+    id: u64,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    by: Option<String>,
+    title: Option<String>,
+    url: Option<String>,
+    score: Option<i64>,
+    descendants: Option<u64>,
+    /// HTML
+    text: Option<String>,
+    parent: Option<u64>,
+    #[serde(default)]
+    kids: Vec<u64>,
+    #[serde(default)]
+    deleted: bool,
+    #[serde(default)]
+    dead: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum Item {
+    /// A story, Ask HN, job or poll
+    Story {
+        id: u64,
+        title: String,
+        /// The linked page; Ask HN posts have none
+        url: Option<String>,
+        score: i64,
+        by: Option<String>,
+        comments: u64,
+        discussion: String,
+    },
+    Comment {
+        id: u64,
+        by: Option<String>,
+        /// Plain text; `None` once deleted or killed
+        text: Option<String>,
+        /// The story or comment it replies to
+        parent: Option<u64>,
+        replies: usize,
+        discussion: String,
+    },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct HackerNewsOutput {
+    items: Vec<Item>,
+}
+
+pub struct HackerNewsTool;
+
+// Comments are HTML with paragraphs, links, italics and escaped characters;
+// the model reads plain text more reliably
+fn html_to_text(html: &str) -> String {
+    let html = html.replace("<p>", "\n\n");
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&quot;", "\"")
+        .replace("&#x27;", "'")
+        .replace("&#x2F;", "/")
+        .replace("&gt;", ">")
+        .replace("&lt;", "<")
+        .replace("&amp;", "&")
+}
+
+impl TryFrom<HnItem> for Item {
+    type Error = HackerNewsError;
+
+    fn try_from(item: HnItem) -> Result<Self, Self::Error> {
+        let discussion = format!("{}{}", ITEM_URL, item.id);
+        let removed = item.deleted || item.dead;
+        match item.kind.as_deref() {
+            Some("story" | "job" | "poll") => Ok(Item::Story {
+                id: item.id,
+                title: item.title.unwrap_or_default(),
+                url: item.url,
+                score: item.score.unwrap_or(0),
+                by: item.by,
+                comments: item.descendants.unwrap_or(0),
+                discussion,
+            }),
+            Some("comment") => Ok(Item::Comment {
+                id: item.id,
+                by: item.by,
+                text: item.text.filter(|_| !removed).map(|text| html_to_text(&text)),
+                parent: item.parent,
+                replies: item.kids.len(),
+                discussion,
+            }),
+            kind => Err(HackerNewsError::UnsupportedItem(
+                item.id,
+                kind.unwrap_or("deleted item").to_string(),
+            )),
+        }
+    }
+}
+
+// Parse an item response. The API answers `null` for ids that don't exist.
+fn parse_item(id: u64, text: &str) -> Result<Item, HackerNewsError> {
+    let item: Option<HnItem> =
+        serde_json::from_str(text).map_err(|e| HackerNewsError::InvalidResponse(e.to_string()))?;
+    item.ok_or(HackerNewsError::NotFound(id))?.try_into()
+}
+
+fn parse_story_ids(text: &str) -> Result<Vec<u64>, HackerNewsError> {
+    serde_json::from_str(text).map_err(|e| HackerNewsError::InvalidResponse(e.to_string()))
+}
+
+impl From<BodyError> for HackerNewsError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => HackerNewsError::TooLarge,
+            BodyError::Read(e) => HackerNewsError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+async fn get(client: &reqwest::Client, url: &str) -> Result<String, HackerNewsError> {
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| HackerNewsError::HttpRequestFailed(e.to_string()))?;
+
+    // Get the status code before consuming `response`
+    let status = response.status();
+
+    let text = read_body(response).await?;
+
+    if !status.is_success() {
+        return Err(HackerNewsError::ApiError(format!(
+            "Status: {}, Response: {}",
+            status, text
+        )));
+    }
+
+    Ok(text)
+}
+
+async fn fetch_item(client: &reqwest::Client, id: u64) -> Result<Item, HackerNewsError> {
+    let text = get(client, &format!("{}/item/{}.json", API_BASE_URL, id)).await?;
+    parse_item(id, &text)
+}
+
+impl Tool for HackerNewsTool {
+    const NAME: &'static str = "hacker_news";
+
+    type Args = HackerNewsArgs;
+    type Output = HackerNewsOutput;
+    type Error = HackerNewsError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "hacker_news".to_string(),
+            description: "Get the current top, new or best stories on Hacker News with their titles, scores, links and comment counts, or fetch a single story or comment by its id".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "list": { "type": "string", "enum": ["top", "new", "best"], "description": "Which stories to list. Defaults to top" },
+                    "limit": { "type": "integer", "description": "How many stories to list, from 1 to 30. Defaults to 10" },
+                    "id": { "type": "integer", "description": "Fetch this item instead of listing stories (e.g., 8863)" },
+                },
+                "required": []
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| HackerNewsError::HttpRequestFailed(e.to_string()))?;
+
+        if let Some(id) = args.id {
+            return Ok(HackerNewsOutput {
+                items: vec![fetch_item(&client, id).await?],
+            });
+        }
+
+        let limit = args.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+        let text = get(&client, &format!("{}/{}.json", API_BASE_URL, args.list.endpoint())).await?;
+        let ids = parse_story_ids(&text)?;
+
+        // The lists only hold ids, so the stories are fetched one by one, all
+        // at once. A story deleted since the list was built is left out.
+        let stories = join_all(ids.into_iter().take(limit).map(|id| fetch_item(&client, id))).await;
+        let mut items = Vec::new();
+        for story in stories {
+            match story {
+                Ok(item) => items.push(item),
+                Err(HackerNewsError::NotFound(_) | HackerNewsError::UnsupportedItem(..)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(HackerNewsOutput { items })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_story_and_comment() {
+        let text = r#"{"by": "dhouston", "descendants": 71, "id": 8863, "kids": [9224, 8917], "score": 104, "time": 1175714200, "title": "My YC app: Dropbox - Throw away your USB drive", "type": "story", "url": "http://www.getdropbox.com/u/2/screencast.html"}"#;
+        assert_eq!(
+            parse_item(8863, text).unwrap(),
+            Item::Story {
+                id: 8863,
+                title: "My YC app: Dropbox - Throw away your USB drive".into(),
+                url: Some("http://www.getdropbox.com/u/2/screencast.html".into()),
+                score: 104,
+                by: Some("dhouston".into()),
+                comments: 71,
+                discussion: "https://news.ycombinator.com/item?id=8863".into(),
+            }
+        );
+
+        let text = r#"{"by": "norvig", "id": 2921983, "kids": [2922097, 2922429], "parent": 2921506, "text": "Aw shucks, guys ... you make me blush with your compliments.<p>Tell you what, Ill make a deal: I&#x27;ll keep writing if you keep <i>reading</i>. K?", "time": 1314211127, "type": "comment"}"#;
+        assert_eq!(
+            parse_item(2921983, text).unwrap(),
+            Item::Comment {
+                id: 2921983,
+                by: Some("norvig".into()),
+                text: Some("Aw shucks, guys ... you make me blush with your compliments.\n\nTell you what, Ill make a deal: I'll keep writing if you keep reading. K?".into()),
+                parent: Some(2921506),
+                replies: 2,
+                discussion: "https://news.ycombinator.com/item?id=2921983".into(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_missing_and_unsupported_items() {
+        assert!(matches!(parse_item(99_999_999_999, "null"), Err(HackerNewsError::NotFound(99_999_999_999))));
+        assert!(matches!(
+            parse_item(160705, r#"{"by": "pg", "id": 160705, "parent": 160704, "score": 335, "text": "Yes, ban them; I'm tired of seeing Valleywag stories on News.YC.", "time": 1207886576, "type": "pollopt"}"#),
+            Err(HackerNewsError::UnsupportedItem(160705, kind)) if kind == "pollopt"
+        ));
+        // Deleted comments keep their id and place in the thread only
+        assert!(matches!(
+            parse_item(1, r#"{"id": 1, "deleted": true, "type": "comment", "parent": 8863, "time": 1175714200}"#),
+            Ok(Item::Comment { text: None, .. })
+        ));
+        assert_eq!(parse_story_ids("[41000000, 40999999]").unwrap(), vec![41000000, 40999999]);
+    }
+}
@@ -0,0 +1,35 @@
+mod hacker_news_tool;
+
+use crate::hacker_news_tool::HackerNewsTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the HackerNewsTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a helpful assistant. Use the hacker_news tool to answer questions about what's on Hacker News instead of relying on memory. Include each story's link and score.")
+        .tool(HackerNewsTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What are the top 5 stories on Hacker News right now?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
@@ -0,0 +1,100 @@
+//! Response size limit shared by the HTTP tools in these examples
+
+/// Largest response body read, in bytes
+pub const MAX_RESPONSE_SIZE: usize = 5 * 1024 * 1024;
+
+#[derive(Debug, thiserror::Error)]
+pub enum BodyError {
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Reading the response failed: {0}")]
+    Read(#[from] reqwest::Error),
+}
+
+/// Read the body of `response`, giving up past `MAX_RESPONSE_SIZE` bytes. The
+/// length header is optional, so the cap is also enforced while reading.
+pub async fn read_body(mut response: reqwest::Response) -> Result<String, BodyError> {
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_RESPONSE_SIZE as u64)
+    {
+        return Err(BodyError::TooLarge);
+    }
+
+    let mut body = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if body.len() + chunk.len() > MAX_RESPONSE_SIZE {
+            return Err(BodyError::TooLarge);
+        }
+        body.extend_from_slice(&chunk);
+    }
+    Ok(String::from_utf8_lossy(&body).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Answer one request with a body of `size` bytes, either announced in a
+    /// Content-Length header or sent in chunks without one
+    async fn serve(size: usize, chunked: bool) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let mut buffer = [0; 1024];
+            let _ = socket.read(&mut buffer).await;
+
+            // The client hangs up once it gives up, so write errors are expected
+            if chunked {
+                let _ = socket
+                    .write_all(b"HTTP/1.1 200 OK\r\ntransfer-encoding: chunked\r\n\r\n")
+                    .await;
+                let chunk = vec![b'x'; 64 * 1024];
+                let mut remaining = size;
+                while remaining > 0 {
+                    let chunk = &chunk[..remaining.min(chunk.len())];
+                    let frame = [format!("{:x}\r\n", chunk.len()).as_bytes(), chunk, b"\r\n"].concat();
+                    if socket.write_all(&frame).await.is_err() {
+                        return;
+                    }
+                    remaining -= chunk.len();
+                }
+                let _ = socket.write_all(b"0\r\n\r\n").await;
+            } else {
+                let header = format!("HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n", size);
+                let _ = socket.write_all(header.as_bytes()).await;
+                let _ = socket.write_all(&vec![b'x'; size]).await;
+            }
+        });
+
+        url
+    }
+
+    async fn get(size: usize, chunked: bool) -> reqwest::Response {
+        reqwest::get(serve(size, chunked).await).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_bodies_up_to_the_limit_are_read() {
+        assert_eq!(read_body(get(5, false).await).await.unwrap(), "xxxxx");
+
+        let response = get(MAX_RESPONSE_SIZE, true).await;
+        assert_eq!(read_body(response).await.unwrap().len(), MAX_RESPONSE_SIZE);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_bodies_are_refused() {
+        let response = get(MAX_RESPONSE_SIZE + 1, false).await;
+        assert_eq!(response.content_length(), Some(MAX_RESPONSE_SIZE as u64 + 1));
+        assert!(matches!(read_body(response).await, Err(BodyError::TooLarge)));
+
+        // Without a length header the cap is only hit while reading
+        let response = get(MAX_RESPONSE_SIZE + 1, true).await;
+        assert_eq!(response.content_length(), None);
+        assert!(matches!(read_body(response).await, Err(BodyError::TooLarge)));
+    }
+}
@@ -0,0 +1,297 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::time::Duration;
+
+// The free endpoint is HTTP only; HTTPS needs a paid key
+const API_URL: &str = "http://ip-api.com/json";
+
+// Only the fields the tool returns, plus `status` and `message`
+const FIELDS: &str = "status,message,query,country,countryCode,regionName,city,zip,lat,lon,timezone,isp,org";
+
+// Maximum time to wait for ip-api.com before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+pub struct IpGeolocationArgs {
+    ip: Option<String>,
+}
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum IpGeolocationError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("'{0}' is not an IPv4 or IPv6 address")]
+    InvalidIp(String),
+    #[error("{0} is a {1} address, which has no location on the public internet")]
+    NonPublicIp(String, &'static str),
+    #[error("Lookup of {0} failed: {1}")]
+    LookupFailed(String, String),
+}
+
+// A response as ip-api.com returns it. Failed lookups have only `status`,
+// `message` and `query`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ApiResponse {
+    status: String,
+    message: Option<String>,
+    query: String,
+    country: Option<String>,
+    country_code: Option<String>,
+    region_name: Option<String>,
+    city: Option<String>,
+    zip: Option<String>,
+    lat: Option<f64>,
+    lon: Option<f64>,
+    timezone: Option<String>,
+    isp: Option<String>,
+    org: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct IpLocation {
+    ip: String,
+    country: String,
+    /// ISO 3166-1 alpha-2, e.g. `DE`
+    country_code: String,
+    region: String,
+    city: String,
+    zip: Option<String>,
+    lat: f64,
+    lon: f64,
+    /// IANA timezone, e.g. `Europe/Berlin`
+    timezone: String,
+    isp: String,
+    /// The organization the address is assigned to, when it differs from the ISP
+    org: Option<String>,
+}
+
+/// Looks up the approximate location and network of an IP address with
+/// ip-api.com. Locations are usually right about the country, often about the
+/// region and only sometimes about the city.
+pub struct IpGeolocationTool;
+
+// The kind of non-public address `ip` is, if it is one. Such addresses are
+// answered locally: they are the same on every network, so no service can
+// locate them.
+fn non_public_kind(ip: IpAddr) -> Option<&'static str> {
+    match ip {
+        IpAddr::V4(ip) => ipv4_kind(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ipv4) => ipv4_kind(ipv4),
+            None => ipv6_kind(ip),
+        },
+    }
+}
+
+fn ipv4_kind(ip: Ipv4Addr) -> Option<&'static str> {
+    let [a, b, ..] = ip.octets();
+    if ip.is_private() {
+        Some("private")
+    } else if ip.is_loopback() {
+        Some("loopback")
+    } else if ip.is_link_local() {
+        Some("link-local")
+    } else if a == 100 && (64..128).contains(&b) {
+        Some("carrier-grade NAT")
+    } else if ip.is_documentation() {
+        Some("documentation")
+    } else if ip.is_multicast() {
+        Some("multicast")
+    } else if ip.is_unspecified() || ip.is_broadcast() || a == 0 || a >= 240 {
+        Some("reserved")
+    } else {
+        None
+    }
+}
+
+fn ipv6_kind(ip: Ipv6Addr) -> Option<&'static str> {
+    let first = ip.segments()[0];
+    if ip.is_loopback() {
+        Some("loopback")
+    } else if first & 0xfe00 == 0xfc00 {
+        Some("unique local (private)")
+    } else if first & 0xffc0 == 0xfe80 {
+        Some("link-local")
+    } else if first == 0x2001 && ip.segments()[1] == 0x0db8 {
+        Some("documentation")
+    } else if ip.is_multicast() {
+        Some("multicast")
+    } else if ip.is_unspecified() {
+        Some("reserved")
+    } else {
+        None
+    }
+}
+
+// Check the address before anything is sent
+fn check_ip(ip: &str) -> Result<IpAddr, IpGeolocationError> {
+    let ip = ip.trim();
+    let addr: IpAddr = ip
+        .parse()
+        .map_err(|_| IpGeolocationError::InvalidIp(ip.to_string()))?;
+    match non_public_kind(addr) {
+        Some(kind) => Err(IpGeolocationError::NonPublicIp(ip.to_string(), kind)),
+        None => Ok(addr),
+    }
+}
+
+fn parse_response(text: &str) -> Result<IpLocation, IpGeolocationError> {
+    let response: ApiResponse =
+        serde_json::from_str(text).map_err(|e| IpGeolocationError::InvalidResponse(e.to_string()))?;
+
+    // Failures are reported with a 200 and a reason such as "private range",
+    // "reserved range" or "invalid query"
+    if response.status != "success" {
+        return Err(IpGeolocationError::LookupFailed(
+            response.query,
+            response.message.unwrap_or_else(|| "unknown error".to_string()),
+        ));
+    }
+
+    let missing = |field: &str| IpGeolocationError::InvalidResponse(format!("missing {}", field));
+    Ok(IpLocation {
+        country: response.country.ok_or_else(|| missing("country"))?,
+        country_code: response.country_code.ok_or_else(|| missing("countryCode"))?,
+        region: response.region_name.unwrap_or_default(),
+        city: response.city.unwrap_or_default(),
+        zip: response.zip.filter(|zip| !zip.is_empty()),
+        lat: response.lat.ok_or_else(|| missing("lat"))?,
+        lon: response.lon.ok_or_else(|| missing("lon"))?,
+        timezone: response.timezone.unwrap_or_default(),
+        org: response.org.filter(|org| !org.is_empty() && Some(org) != response.isp.as_ref()),
+        isp: response.isp.unwrap_or_default(),
+        ip: response.query,
+    })
+}
+
+impl From<BodyError> for IpGeolocationError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => IpGeolocationError::TooLarge,
+            BodyError::Read(e) => IpGeolocationError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for IpGeolocationTool {
+    const NAME: &'static str = "ip_geolocation";
+
+    type Args = IpGeolocationArgs;
+    type Output = IpLocation;
+    type Error = IpGeolocationError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "ip_geolocation".to_string(),
+            description: "Find the approximate location (country, region, city, coordinates, timezone) and the ISP of a public IPv4 or IPv6 address. The city is often only approximate".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "ip": { "type": "string", "description": "The IP address to look up (e.g., '8.8.8.8' or '2001:4860:4860::8888'). Omit to look up this machine's public IP" },
+                },
+                "required": []
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // Without an address, ip-api.com locates the address the request
+        // comes from
+        let url = match args.ip.as_deref().filter(|ip| !ip.trim().is_empty()) {
+            Some(ip) => format!("{}/{}", API_URL, check_ip(ip)?),
+            None => API_URL.to_string(),
+        };
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| IpGeolocationError::HttpRequestFailed(e.to_string()))?;
+
+        let response = client
+            .get(url)
+            .query(&[("fields", FIELDS)])
+            .send()
+            .await
+            .map_err(|e| IpGeolocationError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        let text = read_body(response).await?;
+
+        // The free endpoint allows 45 requests per minute and answers 429 past that
+        if !status.is_success() {
+            return Err(IpGeolocationError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        parse_response(&text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_ip() {
+        assert!(check_ip("8.8.8.8").is_ok());
+        assert!(check_ip(" 2001:4860:4860::8888 ").is_ok());
+        assert_eq!(check_ip("999.1.1.1"), Err(IpGeolocationError::InvalidIp("999.1.1.1".into())));
+        assert_eq!(check_ip("google.com"), Err(IpGeolocationError::InvalidIp("google.com".into())));
+
+        let kind = |ip: &str| match check_ip(ip) {
+            Err(IpGeolocationError::NonPublicIp(_, kind)) => kind,
+            other => panic!("{} should be non-public, got {:?}", ip, other),
+        };
+        assert_eq!(kind("192.168.1.10"), "private");
+        assert_eq!(kind("10.0.0.1"), "private");
+        assert_eq!(kind("127.0.0.1"), "loopback");
+        assert_eq!(kind("100.72.1.1"), "carrier-grade NAT");
+        assert_eq!(kind("203.0.113.5"), "documentation");
+        assert_eq!(kind("255.255.255.255"), "reserved");
+        assert_eq!(kind("::1"), "loopback");
+        assert_eq!(kind("fd12:3456::1"), "unique local (private)");
+        assert_eq!(kind("fe80::1"), "link-local");
+        assert_eq!(kind("::ffff:192.168.0.1"), "private");
+    }
+
+    #[test]
+    fn test_parse_response() {
+        let text = r#"{"status": "success", "country": "United States", "countryCode": "US", "regionName": "Virginia", "city": "Ashburn", "zip": "20149", "lat": 39.03, "lon": -77.5, "timezone": "America/New_York", "isp": "Google LLC", "org": "Google Public DNS", "query": "8.8.8.8"}"#;
+        assert_eq!(
+            parse_response(text).unwrap(),
+            IpLocation {
+                ip: "8.8.8.8".into(),
+                country: "United States".into(),
+                country_code: "US".into(),
+                region: "Virginia".into(),
+                city: "Ashburn".into(),
+                zip: Some("20149".into()),
+                lat: 39.03,
+                lon: -77.5,
+                timezone: "America/New_York".into(),
+                isp: "Google LLC".into(),
+                org: Some("Google Public DNS".into()),
+            }
+        );
+
+        assert_eq!(
+            parse_response(r#"{"status": "fail", "message": "reserved range", "query": "198.18.0.1"}"#),
+            Err(IpGeolocationError::LookupFailed("198.18.0.1".into(), "reserved range".into()))
+        );
+    }
+}
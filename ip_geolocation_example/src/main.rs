@@ -0,0 +1,35 @@
+mod ip_geolocation_tool;
+
+use crate::ip_geolocation_tool::IpGeolocationTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the IpGeolocationTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a helpful network assistant. Use the ip_geolocation tool to find where IP addresses are and who operates them. Mention that IP locations are approximate.")
+        .tool(IpGeolocationTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "Where is the IP address 1.1.1.1 located, and which company runs it?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
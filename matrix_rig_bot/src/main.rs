@@ -0,0 +1,225 @@
+// main.rs
+
+mod rig_agent;
+
+use anyhow::{Context, Result};
+use dotenv::dotenv;
+use matrix_sdk::config::SyncSettings;
+use matrix_sdk::event_handler::Ctx;
+use matrix_sdk::ruma::events::room::member::StrippedRoomMemberEvent;
+use matrix_sdk::ruma::events::room::message::{
+    MessageType, OriginalSyncRoomMessageEvent, RoomMessageEventContent,
+};
+use matrix_sdk::{Client, Room, RoomState};
+use rig_agent::RigAgent;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Matrix clears the typing notice after four seconds, so it is sent again at
+/// this interval until the answer is ready
+const TYPING_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Invites can arrive before the homeserver lets us join, so joining is retried
+const JOIN_ATTEMPTS: u32 = 5;
+
+/// The names the bot answers to in group rooms
+#[derive(Clone)]
+struct BotNames(Vec<String>);
+
+/// Characters that continue a word, so a name next to one is part of a longer
+/// word rather than a mention
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// If `body` addresses the bot by one of `names`, return the question with the
+/// mention removed. Element puts the display name at the start of the body
+/// (`Rig Bot: what is Rig?`); users may also type the full user ID. A name only
+/// counts as a whole word, so a bot called `rig` ignores "trigger".
+fn mentioned_query(body: &str, names: &[String]) -> Option<String> {
+    let (start, end) = names.iter().filter(|name| !name.is_empty()).find_map(|name| {
+        (0..body.len())
+            .find(|&i| {
+                let end = i + name.len();
+                body.get(i..end)
+                    .is_some_and(|candidate| candidate.eq_ignore_ascii_case(name))
+                    && !body[..i].chars().next_back().is_some_and(is_word_char)
+                    && !body[end..].chars().next().is_some_and(is_word_char)
+            })
+            .map(|start| (start, start + name.len()))
+    })?;
+
+    let rest = body[end..].trim_start_matches([':', ',']);
+    let query = format!("{} {}", &body[..start], rest);
+    Some(query.split_whitespace().collect::<Vec<_>>().join(" "))
+}
+
+/// Accept every invite so the bot can be added to rooms without restarting
+async fn on_invite(event: StrippedRoomMemberEvent, client: Client, room: Room) {
+    if Some(event.state_key.as_ref()) != client.user_id() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        for attempt in 1..=JOIN_ATTEMPTS {
+            match room.join().await {
+                Ok(()) => {
+                    info!("Joined room {}", room.room_id());
+                    return;
+                }
+                Err(e) => {
+                    warn!("Failed to join room {} (attempt {}): {:?}", room.room_id(), attempt, e);
+                    tokio::time::sleep(Duration::from_secs(2u64.pow(attempt))).await;
+                }
+            }
+        }
+        error!("Giving up on joining room {}", room.room_id());
+    });
+}
+
+async fn on_message(
+    event: OriginalSyncRoomMessageEvent,
+    room: Room,
+    client: Client,
+    rig_agent: Ctx<Arc<RigAgent>>,
+    Ctx(names): Ctx<BotNames>,
+) {
+    if room.state() != RoomState::Joined || Some(event.sender.as_ref()) == client.user_id() {
+        return;
+    }
+    let MessageType::Text(text) = event.content.msgtype else {
+        return;
+    };
+    debug!("Received message in room {}: {}", room.room_id(), text.body);
+
+    // In a direct chat every message is for the bot; in group rooms only the
+    // ones that mention it
+    let query = if room.joined_members_count() <= 2 {
+        text.body.trim().to_string()
+    } else {
+        match mentioned_query(&text.body, &names.0) {
+            Some(query) => query,
+            None => return,
+        }
+    };
+    if query.is_empty() {
+        return;
+    }
+
+    // Keep the typing notice up while the agent works
+    let typing = tokio::spawn({
+        let room = room.clone();
+        async move {
+            loop {
+                if let Err(e) = room.typing_notice(true).await {
+                    debug!("Error sending typing notice: {:?}", e);
+                }
+                tokio::time::sleep(TYPING_INTERVAL).await;
+            }
+        }
+    });
+
+    let response = rig_agent.process_message(&query).await;
+    typing.abort();
+    let _ = room.typing_notice(false).await;
+
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            error!("Error processing message: {:?}", e);
+            format!("Error processing request: {}", e)
+        }
+    };
+
+    // Sent as a notice, which by convention bots never respond to, so two bots
+    // in a room can't get into a loop. The Markdown is rendered to HTML with a
+    // plain-text fallback for clients that don't support formatting.
+    if let Err(e) = room.send(RoomMessageEventContent::notice_markdown(response)).await {
+        error!("Error sending response: {:?}", e);
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    let homeserver = env::var("MATRIX_HOMESERVER").context("MATRIX_HOMESERVER not set")?;
+    let username = env::var("MATRIX_USERNAME").context("MATRIX_USERNAME not set")?;
+    let password = env::var("MATRIX_PASSWORD").context("MATRIX_PASSWORD not set")?;
+
+    let rig_agent = Arc::new(RigAgent::new().await?);
+
+    let client = Client::builder()
+        .homeserver_url(&homeserver)
+        .build()
+        .await
+        .context("Failed to connect to the homeserver")?;
+
+    client
+        .matrix_auth()
+        .login_username(&username, &password)
+        .initial_device_display_name("Rig bot")
+        .await
+        .context("Failed to log in")?;
+
+    let user_id = client.user_id().context("Not logged in")?.to_owned();
+    info!("Logged in as {}", user_id);
+
+    let mut names = vec![user_id.to_string(), user_id.localpart().to_string()];
+    if let Some(display_name) = client.account().get_display_name().await? {
+        names.push(display_name);
+    }
+
+    // Sync once before listening so messages sent while the bot was offline
+    // aren't answered all at once
+    client.add_event_handler(on_invite);
+    let response = client.sync_once(SyncSettings::default()).await?;
+
+    client.add_event_handler_context(rig_agent);
+    client.add_event_handler_context(BotNames(names));
+    client.add_event_handler(on_message);
+
+    // Runs until the process is stopped, reconnecting as needed
+    client
+        .sync(SyncSettings::default().token(response.next_batch))
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names() -> Vec<String> {
+        vec!["@rigbot:example.org".into(), "rigbot".into(), "Rig Bot".into()]
+    }
+
+    #[test]
+    fn test_mentioned_query() {
+        assert_eq!(
+            mentioned_query("Rig Bot: what is Rig?", &names()).as_deref(),
+            Some("what is Rig?")
+        );
+        assert_eq!(
+            mentioned_query("hey @rigbot:example.org, how do agents work?", &names()).as_deref(),
+            Some("hey how do agents work?")
+        );
+        assert_eq!(mentioned_query("what is Rig?", &names()), None);
+
+        // Names inside longer words aren't mentions
+        let names = vec!["@rig:example.org".to_string(), "rig".to_string()];
+        assert_eq!(mentioned_query("configuring the trigger", &names), None);
+        assert_eq!(mentioned_query("rig-based agents", &names), None);
+        assert_eq!(
+            mentioned_query("rig, what is a trigger?", &names).as_deref(),
+            Some("what is a trigger?")
+        );
+    }
+}
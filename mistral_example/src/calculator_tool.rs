@@ -0,0 +1,62 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+#[derive(Deserialize)]
+pub struct CalculatorArgs {
+    x: f64,
+    y: f64,
+    operation: Operation,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CalculatorError {
+    #[error("Division by zero")]
+    DivisionByZero,
+}
+
+pub struct CalculatorTool;
+
+impl Tool for CalculatorTool {
+    const NAME: &'static str = "calculate";
+
+    type Args = CalculatorArgs;
+    type Output = f64;
+    type Error = CalculatorError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "calculate".to_string(),
+            description: "Apply an arithmetic operation to two numbers".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "x": { "type": "number", "description": "The first operand" },
+                    "y": { "type": "number", "description": "The second operand" },
+                    "operation": { "type": "string", "description": "The operation to apply", "enum": ["add", "subtract", "multiply", "divide"] },
+                },
+                "required": ["x", "y", "operation"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        match args.operation {
+            Operation::Add => Ok(args.x + args.y),
+            Operation::Subtract => Ok(args.x - args.y),
+            Operation::Multiply => Ok(args.x * args.y),
+            Operation::Divide if args.y == 0.0 => Err(CalculatorError::DivisionByZero),
+            Operation::Divide => Ok(args.x / args.y),
+        }
+    }
+}
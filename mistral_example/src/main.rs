@@ -0,0 +1,51 @@
+mod calculator_tool;
+mod mistral;
+
+use crate::calculator_tool::CalculatorTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use std::io::{self, Write};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the Mistral client from MISTRAL_API_KEY
+    let mistral_client = mistral::Client::from_env()?;
+    let model = std::env::var("MISTRAL_MODEL").unwrap_or_else(|_| mistral::MISTRAL_LARGE.to_string());
+
+    // Build the agent with the CalculatorTool. Mistral's models support
+    // function calling, so the model decides when to use it.
+    let agent = mistral_client
+        .agent(&model)
+        .preamble("You are a helpful assistant. Use the calculate tool for any arithmetic instead of working it out yourself.")
+        .tool(CalculatorTool)
+        .build();
+
+    println!("Chatting with {} on Mistral (type 'exit' to quit)", model);
+
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            break;
+        }
+
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        if input.eq_ignore_ascii_case("exit") {
+            break;
+        }
+
+        match agent.prompt(input).await {
+            Ok(response) => println!("{}\n", response),
+            Err(e) => eprintln!("Error: {}\n", e),
+        }
+    }
+
+    Ok(())
+}
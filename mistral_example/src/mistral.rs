@@ -0,0 +1,313 @@
+// mistral.rs
+
+use rig::agent::AgentBuilder;
+use rig::completion::{self, CompletionError, CompletionRequest, ModelChoice};
+use rig::json_utils;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+/// rig-style base URL: the client appends `/v1/chat/completions`
+pub const MISTRAL_API_BASE_URL: &str = "https://api.mistral.ai";
+
+/// `mistral-large-latest` completion model, Mistral's flagship model. Set
+/// `MISTRAL_MODEL` to use another, such as `mistral-small-latest`.
+pub const MISTRAL_LARGE: &str = "mistral-large-latest";
+
+/// A client for Mistral's chat completions API.
+///
+/// Mistral's API is OpenAI-compatible, but its tool calls don't always carry
+/// the `"type": "function"` field rig's OpenAI response type requires, and a
+/// message calling a tool comes with an empty `content` string that rig would
+/// take for the answer. This client sends the requests itself and parses the
+/// response with types that allow for both.
+#[derive(Clone)]
+pub struct Client {
+    base_url: String,
+    http_client: reqwest::Client,
+}
+
+impl Client {
+    pub fn new(api_key: &str) -> Result<Self, String> {
+        Self::from_url(api_key, MISTRAL_API_BASE_URL)
+    }
+
+    pub fn from_url(api_key: &str, base_url: &str) -> Result<Self, String> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        let authorization = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", api_key))
+            .map_err(|_| "The Mistral API key contains invalid characters".to_string())?;
+        headers.insert(reqwest::header::AUTHORIZATION, authorization);
+
+        let http_client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http_client,
+        })
+    }
+
+    /// Create a client from the `MISTRAL_API_KEY` environment variable
+    pub fn from_env() -> Result<Self, String> {
+        let api_key =
+            std::env::var("MISTRAL_API_KEY").map_err(|_| "MISTRAL_API_KEY not set".to_string())?;
+        Self::new(&api_key)
+    }
+
+    pub fn completion_model(&self, model: &str) -> CompletionModel {
+        CompletionModel {
+            client: self.clone(),
+            model: model.to_string(),
+        }
+    }
+
+    /// Start building an agent for `model`
+    pub fn agent(&self, model: &str) -> AgentBuilder<CompletionModel> {
+        AgentBuilder::new(self.completion_model(model))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompletionResponse {
+    pub id: String,
+    pub model: String,
+    pub choices: Vec<Choice>,
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Choice {
+    pub index: usize,
+    pub message: Message,
+    pub finish_reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Message {
+    pub role: String,
+    pub content: Option<String>,
+    pub tool_calls: Option<Vec<ToolCall>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ToolCall {
+    pub id: String,
+    pub function: Function,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Function {
+    pub name: String,
+    /// The arguments as a JSON string
+    pub arguments: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Usage {
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+    pub total_tokens: usize,
+}
+
+impl TryFrom<CompletionResponse> for completion::CompletionResponse<CompletionResponse> {
+    type Error = CompletionError;
+
+    fn try_from(value: CompletionResponse) -> Result<Self, Self::Error> {
+        let message = &value
+            .choices
+            .first()
+            .ok_or_else(|| CompletionError::ResponseError("Response contained no choices".into()))?
+            .message;
+
+        // Tool calls are checked first: they come with `"content": ""`
+        let choice = match (&message.tool_calls, &message.content) {
+            (Some(calls), _) if !calls.is_empty() => {
+                let call = &calls[0];
+                ModelChoice::ToolCall(
+                    call.function.name.clone(),
+                    serde_json::from_str(&call.function.arguments)?,
+                )
+            }
+            (_, Some(content)) => ModelChoice::Message(content.clone()),
+            _ => {
+                return Err(CompletionError::ResponseError(
+                    "Response did not contain a message or tool call".into(),
+                ))
+            }
+        };
+
+        Ok(completion::CompletionResponse {
+            choice,
+            raw_response: value,
+        })
+    }
+}
+
+#[derive(Clone)]
+pub struct CompletionModel {
+    client: Client,
+    /// Name of the model (e.g.: mistral-large-latest)
+    pub model: String,
+}
+
+impl CompletionModel {
+    /// Build the chat completions body for `request`, with the tools in the
+    /// OpenAI format Mistral also uses
+    fn request_body(&self, mut request: CompletionRequest) -> Value {
+        let mut messages = Vec::new();
+        if let Some(preamble) = &request.preamble {
+            messages.push(completion::Message {
+                role: "system".into(),
+                content: preamble.clone(),
+            });
+        }
+        messages.append(&mut request.chat_history);
+        messages.push(completion::Message {
+            role: "user".into(),
+            content: request.prompt_with_context(),
+        });
+
+        // Optional parameters are only sent when set, rather than as `null`
+        let mut body = json!({
+            "model": self.model,
+            "messages": messages,
+        });
+        if !request.tools.is_empty() {
+            let tools: Vec<Value> = request
+                .tools
+                .into_iter()
+                .map(|tool| json!({ "type": "function", "function": tool }))
+                .collect();
+            body["tools"] = json!(tools);
+            body["tool_choice"] = json!("auto");
+        }
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(max_tokens) = request.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+
+        match request.additional_params {
+            Some(params) => json_utils::merge(body, params),
+            None => body,
+        }
+    }
+}
+
+impl completion::CompletionModel for CompletionModel {
+    type Response = CompletionResponse;
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<CompletionResponse>, CompletionError> {
+        let response = self
+            .client
+            .http_client
+            .post(format!("{}/v1/chat/completions", self.client.base_url))
+            .json(&self.request_body(request))
+            .send()
+            .await?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+        let text = response.text().await?;
+
+        // Mistral explains what went wrong (unknown model, invalid key,
+        // malformed tool schema...) in the body of the error response
+        if !status.is_success() {
+            return Err(CompletionError::ProviderError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        serde_json::from_str::<CompletionResponse>(&text)?.try_into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_body_with_tools() {
+        let model = Client::new("key").unwrap().completion_model(MISTRAL_LARGE);
+        let request = CompletionRequest {
+            prompt: "What is 2 + 2?".into(),
+            preamble: None,
+            chat_history: vec![],
+            documents: vec![],
+            tools: vec![completion::ToolDefinition {
+                name: "calculate".into(),
+                description: "Apply an arithmetic operation to two numbers".into(),
+                parameters: json!({ "type": "object" }),
+            }],
+            temperature: Some(0.2),
+            max_tokens: None,
+            additional_params: None,
+        };
+
+        assert_eq!(
+            model.request_body(request),
+            json!({
+                "model": "mistral-large-latest",
+                "messages": [{ "role": "user", "content": "What is 2 + 2?" }],
+                "tools": [{
+                    "type": "function",
+                    "function": {
+                        "name": "calculate",
+                        "description": "Apply an arithmetic operation to two numbers",
+                        "parameters": { "type": "object" },
+                    },
+                }],
+                "tool_choice": "auto",
+                "temperature": 0.2,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_tool_call_response() {
+        // As Mistral returns it: no `type` on the call and an empty `content`
+        let text = r#"{
+            "id": "cmpl-e5cc70bb28c444948073e77776eb30ef",
+            "object": "chat.completion",
+            "created": 1728000000,
+            "model": "mistral-large-latest",
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": "",
+                    "tool_calls": [{
+                        "id": "D681PevKs",
+                        "function": { "name": "calculate", "arguments": "{\"x\": 2, \"y\": 2, \"operation\": \"add\"}" },
+                        "index": 0
+                    }]
+                },
+                "finish_reason": "tool_calls"
+            }],
+            "usage": { "prompt_tokens": 120, "completion_tokens": 24, "total_tokens": 144 }
+        }"#;
+
+        let response: completion::CompletionResponse<CompletionResponse> =
+            serde_json::from_str::<CompletionResponse>(text).unwrap().try_into().unwrap();
+        match response.choice {
+            ModelChoice::ToolCall(name, args) => {
+                assert_eq!(name, "calculate");
+                assert_eq!(args, json!({ "x": 2, "y": 2, "operation": "add" }));
+            }
+            ModelChoice::Message(text) => panic!("Expected a tool call, got message {:?}", text),
+        }
+
+        let text = text
+            .replace(r#""content": "","#, r#""content": "4","#)
+            .replace(r#""tool_calls": [{"#, r#""unused": [{"#);
+        let response: completion::CompletionResponse<CompletionResponse> =
+            serde_json::from_str::<CompletionResponse>(&text).unwrap().try_into().unwrap();
+        assert!(matches!(response.choice, ModelChoice::Message(text) if text == "4"));
+    }
+}
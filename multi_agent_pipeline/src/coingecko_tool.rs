@@ -0,0 +1,237 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const SIMPLE_PRICE_URL: &str = "https://api.coingecko.com/api/v3/simple/price";
+
+// Maximum time to wait for CoinGecko before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// CoinGecko identifies coins by id rather than ticker; these are the tickers
+// people ask about most. Anything else is passed through as an id.
+const TICKER_IDS: &[(&str, &str)] = &[
+    ("btc", "bitcoin"),
+    ("eth", "ethereum"),
+    ("usdt", "tether"),
+    ("usdc", "usd-coin"),
+    ("bnb", "binancecoin"),
+    ("sol", "solana"),
+    ("xrp", "ripple"),
+    ("doge", "dogecoin"),
+    ("ada", "cardano"),
+    ("trx", "tron"),
+    ("avax", "avalanche-2"),
+    ("dot", "polkadot"),
+    ("link", "chainlink"),
+    ("ltc", "litecoin"),
+    ("matic", "matic-network"),
+    ("atom", "cosmos"),
+    ("hype", "hyperliquid"),
+];
+
+#[derive(Deserialize)]
+pub struct CoinGeckoArgs {
+    coins: Vec<String>,
+    currency: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CoinGeckoError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("No coins given: pass at least one ticker or CoinGecko coin id")]
+    NoCoins,
+    #[error("Unknown coin '{0}': use a CoinGecko coin id such as 'bitcoin' or a common ticker such as 'btc'")]
+    UnknownCoin(String),
+    #[error("No {currency} price available for '{coin}'")]
+    UnsupportedCurrency { coin: String, currency: String },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CoinPrice {
+    coin_id: String,
+    currency: String,
+    price: f64,
+}
+
+pub struct CoinGeckoTool;
+
+// Map a ticker or name to a CoinGecko id, e.g. "BTC" -> "bitcoin"
+fn coin_id(coin: &str) -> String {
+    let coin = coin.trim().to_lowercase();
+    TICKER_IDS
+        .iter()
+        .find(|(ticker, _)| *ticker == coin)
+        .map_or(coin, |(_, id)| id.to_string())
+}
+
+// Map each of `coins` to a CoinGecko id, dropping repeats such as "btc" and
+// "bitcoin" so each coin is priced once
+fn coin_ids(coins: &[String]) -> Vec<String> {
+    let mut ids = Vec::new();
+    for id in coins.iter().map(|coin| coin_id(coin)) {
+        if !ids.contains(&id) {
+            ids.push(id);
+        }
+    }
+    ids
+}
+
+// Pull the price of each of `coin_ids` out of a
+// `{ "<id>": { "<currency>": <price> }, ... }` response, in the order asked for
+fn parse_prices(text: &str, coin_ids: &[String], currency: &str) -> Result<Vec<CoinPrice>, CoinGeckoError> {
+    let prices: HashMap<String, HashMap<String, f64>> =
+        serde_json::from_str(text).map_err(|e| CoinGeckoError::InvalidResponse(e.to_string()))?;
+
+    coin_ids
+        .iter()
+        .map(|coin_id| {
+            // CoinGecko leaves unknown ids out of the response rather than failing
+            let coin_prices = prices
+                .get(coin_id)
+                .ok_or_else(|| CoinGeckoError::UnknownCoin(coin_id.to_string()))?;
+            let price = coin_prices
+                .get(currency)
+                .ok_or_else(|| CoinGeckoError::UnsupportedCurrency {
+                    coin: coin_id.to_string(),
+                    currency: currency.to_uppercase(),
+                })?;
+
+            Ok(CoinPrice {
+                coin_id: coin_id.to_string(),
+                currency: currency.to_uppercase(),
+                price: *price,
+            })
+        })
+        .collect()
+}
+
+impl From<BodyError> for CoinGeckoError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => CoinGeckoError::TooLarge,
+            BodyError::Read(e) => CoinGeckoError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for CoinGeckoTool {
+    const NAME: &'static str = "get_coin_prices";
+
+    type Args = CoinGeckoArgs;
+    type Output = Vec<CoinPrice>;
+    type Error = CoinGeckoError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "get_coin_prices".to_string(),
+            description: "Get the current prices of one or more cryptocurrencies in a fiat currency from CoinGecko, all in a single call".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "coins": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Every coin needed, as tickers (e.g., 'btc', 'eth') or CoinGecko coin ids (e.g., 'bitcoin', 'arbitrum')"
+                    },
+                    "currency": { "type": "string", "description": "Fiat currency code to price the coins in (default 'usd')" },
+                },
+                "required": ["coins"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let coin_ids = coin_ids(&args.coins);
+        if coin_ids.is_empty() {
+            return Err(CoinGeckoError::NoCoins);
+        }
+        let currency = args
+            .currency
+            .unwrap_or_else(|| "usd".to_string())
+            .to_lowercase();
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| CoinGeckoError::HttpRequestFailed(e.to_string()))?;
+
+        // The public API needs no key, and prices several ids in one request
+        let ids = coin_ids.join(",");
+        let response = client
+            .get(SIMPLE_PRICE_URL)
+            .query(&[("ids", ids.as_str()), ("vs_currencies", currency.as_str())])
+            .send()
+            .await
+            .map_err(|e| CoinGeckoError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        let text = read_body(response).await?;
+
+        if !status.is_success() {
+            return Err(CoinGeckoError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        parse_prices(&text, &coin_ids, &currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coin_id_maps_tickers() {
+        assert_eq!(coin_id("BTC"), "bitcoin");
+        assert_eq!(coin_id(" eth "), "ethereum");
+        assert_eq!(coin_id("arbitrum"), "arbitrum");
+
+        let coins = ["BTC", "eth", "bitcoin", "sol"].map(String::from);
+        assert_eq!(coin_ids(&coins), ["bitcoin", "ethereum", "solana"]);
+    }
+
+    #[test]
+    fn test_parse_prices() {
+        let ids = |ids: &[&str]| ids.iter().map(|id| id.to_string()).collect::<Vec<_>>();
+        let price = |coin_id: &str, price: f64| CoinPrice {
+            coin_id: coin_id.into(),
+            currency: "USD".into(),
+            price,
+        };
+
+        // Several coins come back in one response, keyed by id
+        let text = r#"{"solana":{"usd":142.1},"bitcoin":{"usd":67012.0},"ethereum":{"usd":2450.3}}"#;
+        assert_eq!(
+            parse_prices(text, &ids(&["bitcoin", "ethereum", "solana"]), "usd").unwrap(),
+            vec![
+                price("bitcoin", 67012.0),
+                price("ethereum", 2450.3),
+                price("solana", 142.1),
+            ]
+        );
+
+        assert!(matches!(
+            parse_prices(r#"{"bitcoin":{"usd":67012.0}}"#, &ids(&["bitcoin", "notacoin"]), "usd"),
+            Err(CoinGeckoError::UnknownCoin(id)) if id == "notacoin"
+        ));
+        assert!(matches!(
+            parse_prices(r#"{"bitcoin":{}}"#, &ids(&["bitcoin"]), "xyz"),
+            Err(CoinGeckoError::UnsupportedCurrency { .. })
+        ));
+    }
+}
@@ -0,0 +1,59 @@
+mod coingecko_tool;
+
+use crate::coingecko_tool::CoinGeckoTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // The researcher gathers data with the CoinGeckoTool. Rig answers a prompt
+    // with the output of the first tool call the model makes, so the tool takes
+    // every coin at once and its JSON result becomes the research notes.
+    let researcher = openai_client
+        .agent(openai::GPT_4O)
+        .preamble(
+            "You are a crypto market researcher. Call the get_coin_prices tool exactly once, \
+             passing every coin needed to answer the request in its coins list.",
+        )
+        .tool(CoinGeckoTool)
+        .build();
+
+    // The writer never sees the tools; it only works from the researcher's notes
+    let writer = openai_client
+        .agent(openai::GPT_4O)
+        .preamble(
+            "You are a financial writer. Turn the research notes you are given into a short, \
+             polished market summary for a general audience. Only use figures from the notes.",
+        )
+        .build();
+
+    // Take the request from the command line, or fall back to an example
+    let request = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let request = if request.is_empty() {
+        "Compare the current prices of BTC, ETH and SOL in USD.".to_string()
+    } else {
+        request
+    };
+
+    // Step 1: research
+    let findings = researcher.prompt(&request).await?;
+    println!("Researcher findings:\n{}\n", findings);
+
+    // Step 2: the researcher's output becomes the writer's prompt
+    let summary = writer
+        .prompt(&format!(
+            "Request: {}\n\nResearch notes:\n{}",
+            request, findings
+        ))
+        .await?;
+
+    println!("Agent response:\n{}", summary);
+
+    Ok(())
+}
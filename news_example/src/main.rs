@@ -0,0 +1,35 @@
+mod news_tool;
+
+use crate::news_tool::NewsTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the NewsTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a news assistant. Use the search_news tool to find recent headlines, then summarize them and link to the articles.")
+        .tool(NewsTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What's the latest news about the Rust programming language?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
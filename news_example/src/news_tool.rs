@@ -0,0 +1,243 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+const GDELT_DOC_URL: &str = "https://api.gdeltproject.org/api/v2/doc/doc";
+
+// Maximum time to wait for GDELT before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Number of headlines returned for each search
+const MAX_HEADLINES: usize = 10;
+
+#[derive(Deserialize)]
+pub struct NewsArgs {
+    topic: String,
+    from_date: Option<String>,
+    to_date: Option<String>,
+    language: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum NewsError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Invalid date '{0}': use the YYYY-MM-DD format")]
+    InvalidDate(String),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Headline {
+    title: String,
+    source: String,
+    url: String,
+    published: String,
+}
+
+/// Either the headlines found or a message saying there were none, so the
+/// model never receives an empty list it might misread
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum NewsOutput {
+    Headlines(Vec<Headline>),
+    NoResults(String),
+}
+
+// The parts of GDELT's article list we use. GDELT returns `{}` when nothing matches.
+#[derive(Deserialize)]
+struct GdeltResponse {
+    #[serde(default)]
+    articles: Vec<GdeltArticle>,
+}
+
+#[derive(Deserialize)]
+struct GdeltArticle {
+    url: String,
+    title: String,
+    #[serde(default)]
+    seendate: String,
+    #[serde(default)]
+    domain: String,
+}
+
+pub struct NewsTool;
+
+// Turn "2024-10-08" into GDELT's "20241008000000" (or "...235959" for the end of a range)
+fn gdelt_datetime(date: &str, end_of_day: bool) -> Result<String, NewsError> {
+    let parts: Vec<&str> = date.trim().split('-').collect();
+    let valid = matches!(parts.as_slice(), [year, month, day]
+        if year.len() == 4 && month.len() == 2 && day.len() == 2
+            && parts.iter().all(|part| part.chars().all(|c| c.is_ascii_digit())));
+    if !valid {
+        return Err(NewsError::InvalidDate(date.to_string()));
+    }
+
+    let time = if end_of_day { "235959" } else { "000000" };
+    Ok(format!("{}{}", parts.concat(), time))
+}
+
+// Turn the raw response body into headlines
+fn parse_headlines(text: &str, topic: &str) -> Result<NewsOutput, NewsError> {
+    // Query errors come back as plain text with a 200 status
+    if !text.trim_start().starts_with('{') {
+        return Err(NewsError::ApiError(text.trim().to_string()));
+    }
+
+    let response: GdeltResponse =
+        serde_json::from_str(text).map_err(|e| NewsError::InvalidResponse(e.to_string()))?;
+
+    if response.articles.is_empty() {
+        return Ok(NewsOutput::NoResults(format!(
+            "No recent news found about '{}'. Try a broader topic or a wider date range.",
+            topic
+        )));
+    }
+
+    Ok(NewsOutput::Headlines(
+        response
+            .articles
+            .into_iter()
+            .take(MAX_HEADLINES)
+            .map(|article| Headline {
+                title: article.title,
+                source: article.domain,
+                url: article.url,
+                published: article.seendate,
+            })
+            .collect(),
+    ))
+}
+
+impl From<BodyError> for NewsError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => NewsError::TooLarge,
+            BodyError::Read(e) => NewsError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for NewsTool {
+    const NAME: &'static str = "search_news";
+
+    type Args = NewsArgs;
+    type Output = NewsOutput;
+    type Error = NewsError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "search_news".to_string(),
+            description: "Search recent news headlines about a topic. Returns each headline's title, source, URL and publication time.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "topic": { "type": "string", "description": "What the news should be about (e.g., 'Rust programming language')" },
+                    "from_date": { "type": "string", "description": "Only include articles published on or after this date, in YYYY-MM-DD format. Defaults to the last 3 months." },
+                    "to_date": { "type": "string", "description": "Only include articles published on or before this date, in YYYY-MM-DD format" },
+                    "language": { "type": "string", "description": "Only include articles in this language, as an English language name (e.g., 'english', 'spanish')" },
+                },
+                "required": ["topic"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // GDELT filters by language with an operator inside the query itself
+        let query = match &args.language {
+            Some(language) => format!("{} sourcelang:{}", args.topic, language.trim().to_lowercase()),
+            None => args.topic.clone(),
+        };
+
+        let mut params = vec![
+            ("query", query),
+            ("mode", "artlist".to_string()),
+            ("format", "json".to_string()),
+            ("sort", "datedesc".to_string()),
+            ("maxrecords", MAX_HEADLINES.to_string()),
+        ];
+        if let Some(from_date) = &args.from_date {
+            params.push(("startdatetime", gdelt_datetime(from_date, false)?));
+        }
+        if let Some(to_date) = &args.to_date {
+            params.push(("enddatetime", gdelt_datetime(to_date, true)?));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| NewsError::HttpRequestFailed(e.to_string()))?;
+
+        // The GDELT API needs no key
+        let response = client
+            .get(GDELT_DOC_URL)
+            .query(&params)
+            .send()
+            .await
+            .map_err(|e| NewsError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        let text = read_body(response).await?;
+
+        if !status.is_success() {
+            return Err(NewsError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        parse_headlines(&text, &args.topic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_headlines() {
+        let text = r#"{"articles": [{
+            "url": "https://example.com/rust-2024",
+            "url_mobile": "",
+            "title": "Rust 2024 edition released",
+            "seendate": "20250220T141500Z",
+            "socialimage": "",
+            "domain": "example.com",
+            "language": "English",
+            "sourcecountry": "United States"
+        }]}"#;
+
+        assert_eq!(
+            parse_headlines(text, "rust").unwrap(),
+            NewsOutput::Headlines(vec![Headline {
+                title: "Rust 2024 edition released".into(),
+                source: "example.com".into(),
+                url: "https://example.com/rust-2024".into(),
+                published: "20250220T141500Z".into(),
+            }])
+        );
+        assert!(matches!(parse_headlines("{}", "rust").unwrap(), NewsOutput::NoResults(_)));
+        assert!(matches!(
+            parse_headlines("The specified phrase is too short.", "a"),
+            Err(NewsError::ApiError(_))
+        ));
+    }
+
+    #[test]
+    fn test_gdelt_datetime() {
+        assert_eq!(gdelt_datetime("2024-10-08", false).unwrap(), "20241008000000");
+        assert_eq!(gdelt_datetime("2024-10-08", true).unwrap(), "20241008235959");
+        assert!(matches!(gdelt_datetime("10/08/2024", false), Err(NewsError::InvalidDate(_))));
+        assert!(matches!(gdelt_datetime("2024-1-8", false), Err(NewsError::InvalidDate(_))));
+    }
+}
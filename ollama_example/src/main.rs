@@ -0,0 +1,54 @@
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+use std::io::{self, Write};
+
+/// Where `ollama serve` listens by default
+const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+const DEFAULT_OLLAMA_MODEL: &str = "llama3.2";
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    let base_url = std::env::var("OLLAMA_BASE_URL").unwrap_or_else(|_| DEFAULT_OLLAMA_URL.to_string());
+    let model = std::env::var("OLLAMA_MODEL").unwrap_or_else(|_| DEFAULT_OLLAMA_MODEL.to_string());
+
+    // Ollama serves an OpenAI-compatible API under `/v1`, so rig's OpenAI
+    // client works as-is once it points at the local server. The client
+    // appends `/v1/chat/completions` itself, so the base URL has no path.
+    // Ollama ignores the API key, but the client requires one.
+    let ollama_client = openai::Client::from_url("ollama", &base_url);
+
+    let agent = ollama_client
+        .agent(&model)
+        .preamble("You are a helpful assistant. Keep your answers short and to the point.")
+        .build();
+
+    println!("Chatting with {} at {} (type 'exit' to quit)", model, base_url);
+
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            break;
+        }
+
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        if input.eq_ignore_ascii_case("exit") {
+            break;
+        }
+
+        match agent.prompt(input).await {
+            Ok(response) => println!("{}\n", response),
+            Err(e) => eprintln!("Error: {} (is `ollama serve` running and the model pulled?)\n", e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,331 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+const API_URL: &str = "https://api.fda.gov/drug/label.json";
+
+// Maximum time to wait for OpenFDA before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Labels fetched per search. The same drug has a label per manufacturer and
+// product, so a handful is enough to tell whether a name is ambiguous.
+const MAX_LABELS: usize = 10;
+
+// Label sections run to many pages; each one is cut to this many characters
+// to keep the tool's output a reasonable size for the model
+const MAX_SECTION_CHARS: usize = 1500;
+
+#[derive(Deserialize)]
+pub struct DrugLabelArgs {
+    drug: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DrugLabelError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Give the brand or generic name of a drug")]
+    MissingDrugName,
+    #[error("No drug label found for '{0}'. Check the spelling or try the generic name")]
+    NotFound(String),
+}
+
+#[derive(Deserialize)]
+struct LabelResponse {
+    #[serde(default)]
+    results: Vec<Label>,
+}
+
+// A label as OpenFDA returns it. Every section is a list of paragraphs, and
+// which sections a label has depends on the drug: over-the-counter labels
+// have `purpose` and `warnings`, prescription labels `indications_and_usage`
+// and `warnings_and_cautions` or `warnings`.
+#[derive(Deserialize)]
+struct Label {
+    #[serde(default)]
+    openfda: OpenFdaFields,
+    effective_time: Option<String>,
+    purpose: Option<Vec<String>>,
+    indications_and_usage: Option<Vec<String>>,
+    boxed_warning: Option<Vec<String>>,
+    warnings_and_cautions: Option<Vec<String>>,
+    warnings: Option<Vec<String>>,
+    dosage_and_administration: Option<Vec<String>>,
+}
+
+// Harmonized fields OpenFDA adds to the label. Missing for some labels.
+#[derive(Default, Deserialize)]
+struct OpenFdaFields {
+    #[serde(default)]
+    brand_name: Vec<String>,
+    #[serde(default)]
+    generic_name: Vec<String>,
+    #[serde(default)]
+    manufacturer_name: Vec<String>,
+    #[serde(default)]
+    route: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct DrugLabel {
+    brand_names: Vec<String>,
+    generic_name: Option<String>,
+    manufacturer: Option<String>,
+    route: Vec<String>,
+    /// YYYYMMDD
+    label_date: Option<String>,
+    purpose: Option<String>,
+    indications: Option<String>,
+    /// The FDA's strongest warning, for serious or life-threatening risks
+    boxed_warning: Option<String>,
+    warnings: Option<String>,
+    dosage: Option<String>,
+}
+
+/// A drug the name could refer to
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Candidate {
+    brand_names: Vec<String>,
+    generic_name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "match", rename_all = "lowercase")]
+pub enum DrugLabelOutput {
+    /// The label of the drug the name refers to
+    Found(Box<DrugLabel>),
+    /// The name matches several different drugs, such as combination products
+    Ambiguous { candidates: Vec<Candidate> },
+}
+
+/// Looks up FDA drug labels for indications, warnings and dosage. The labels
+/// are free text written by manufacturers, so the tool passes the relevant
+/// sections along and leaves summarizing them to the model.
+pub struct DrugLabelTool;
+
+// Join a section's paragraphs and cut it to `MAX_SECTION_CHARS`
+fn section(paragraphs: Option<Vec<String>>) -> Option<String> {
+    let text = paragraphs?.join("\n").trim().to_string();
+    if text.is_empty() {
+        return None;
+    }
+    if text.chars().count() <= MAX_SECTION_CHARS {
+        return Some(text);
+    }
+    let mut truncated: String = text.chars().take(MAX_SECTION_CHARS).collect();
+    truncated.push_str("… (truncated)");
+    Some(truncated)
+}
+
+impl From<Label> for DrugLabel {
+    fn from(label: Label) -> Self {
+        DrugLabel {
+            brand_names: label.openfda.brand_name,
+            generic_name: label.openfda.generic_name.into_iter().next(),
+            manufacturer: label.openfda.manufacturer_name.into_iter().next(),
+            route: label.openfda.route,
+            label_date: label.effective_time,
+            purpose: section(label.purpose),
+            indications: section(label.indications_and_usage),
+            boxed_warning: section(label.boxed_warning),
+            warnings: section(label.warnings_and_cautions.or(label.warnings)),
+            dosage: section(label.dosage_and_administration),
+        }
+    }
+}
+
+// Pick the label for `drug` out of the search results: a label named exactly
+// `drug` wins, then the first label if they are all the same generic drug.
+// Otherwise the name is ambiguous and the candidates are returned.
+fn choose_label(drug: &str, mut labels: Vec<Label>) -> Result<DrugLabelOutput, DrugLabelError> {
+    let names_match = |names: &[String]| names.iter().any(|name| name.eq_ignore_ascii_case(drug));
+
+    let mut candidates: Vec<Candidate> = Vec::new();
+    for label in &labels {
+        let Some(generic_name) = label.openfda.generic_name.first() else {
+            continue;
+        };
+        match candidates.iter_mut().find(|candidate| &candidate.generic_name == generic_name) {
+            Some(candidate) => {
+                for brand in &label.openfda.brand_name {
+                    if !candidate.brand_names.contains(brand) {
+                        candidate.brand_names.push(brand.clone());
+                    }
+                }
+            }
+            None => candidates.push(Candidate {
+                brand_names: label.openfda.brand_name.clone(),
+                generic_name: generic_name.clone(),
+            }),
+        }
+    }
+
+    let exact = labels
+        .iter()
+        .position(|label| names_match(&label.openfda.brand_name) || names_match(&label.openfda.generic_name));
+
+    match exact {
+        Some(index) => Ok(DrugLabelOutput::Found(Box::new(labels.swap_remove(index).into()))),
+        None if labels.is_empty() => Err(DrugLabelError::NotFound(drug.to_string())),
+        None if candidates.len() <= 1 => Ok(DrugLabelOutput::Found(Box::new(labels.swap_remove(0).into()))),
+        None => Ok(DrugLabelOutput::Ambiguous { candidates }),
+    }
+}
+
+fn parse_response(drug: &str, text: &str) -> Result<DrugLabelOutput, DrugLabelError> {
+    let response: LabelResponse =
+        serde_json::from_str(text).map_err(|e| DrugLabelError::InvalidResponse(e.to_string()))?;
+    choose_label(drug, response.results)
+}
+
+impl From<BodyError> for DrugLabelError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => DrugLabelError::TooLarge,
+            BodyError::Read(e) => DrugLabelError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for DrugLabelTool {
+    const NAME: &'static str = "drug_label";
+
+    type Args = DrugLabelArgs;
+    type Output = DrugLabelOutput;
+    type Error = DrugLabelError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "drug_label".to_string(),
+            description: "Look up the FDA-approved label of a drug sold in the US by its brand or generic name, with its purpose, indications, boxed warning, warnings and dosage. If the name matches several different drugs, returns them as candidates instead; ask the user which one they meant".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "drug": { "type": "string", "description": "The brand or generic name of the drug (e.g., 'Advil' or 'ibuprofen')" },
+                },
+                "required": ["drug"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let drug = args.drug.trim().trim_matches('"');
+        if drug.is_empty() {
+            return Err(DrugLabelError::MissingDrugName);
+        }
+
+        // The two fields are searched together: a space means OR
+        let search = format!("openfda.brand_name:\"{0}\" openfda.generic_name:\"{0}\"", drug);
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| DrugLabelError::HttpRequestFailed(e.to_string()))?;
+
+        let response = client
+            .get(API_URL)
+            .query(&[("search", search), ("limit", MAX_LABELS.to_string())])
+            .send()
+            .await
+            .map_err(|e| DrugLabelError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        let text = read_body(response).await?;
+
+        // OpenFDA answers a search without results with a 404
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(DrugLabelError::NotFound(drug.to_string()));
+        }
+        if !status.is_success() {
+            return Err(DrugLabelError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        parse_response(drug, &text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RESPONSE: &str = r#"{
+        "meta": { "results": { "skip": 0, "limit": 10, "total": 3 } },
+        "results": [
+            {
+                "effective_time": "20240115",
+                "purpose": ["Purpose Pain reliever/fever reducer"],
+                "warnings": ["Warnings Allergy alert: Aspirin may cause a severe allergic reaction", "Reye's syndrome: Children and teenagers should not use this medicine"],
+                "openfda": { "brand_name": ["Bayer Back & Body"], "generic_name": ["ASPIRIN AND CAFFEINE"], "manufacturer_name": ["Bayer HealthCare LLC."], "route": ["ORAL"] }
+            },
+            {
+                "effective_time": "20231002",
+                "purpose": ["Purpose Pain reliever"],
+                "openfda": { "brand_name": ["Bayer Aspirin"], "generic_name": ["ASPIRIN"], "manufacturer_name": ["Bayer HealthCare LLC."], "route": ["ORAL"] }
+            },
+            {
+                "effective_time": "20220610",
+                "openfda": {}
+            }
+        ]
+    }"#;
+
+    #[test]
+    fn test_exact_name_wins() {
+        let output = parse_response("aspirin", RESPONSE).unwrap();
+        assert_eq!(
+            output,
+            DrugLabelOutput::Found(Box::new(DrugLabel {
+                brand_names: vec!["Bayer Aspirin".into()],
+                generic_name: Some("ASPIRIN".into()),
+                manufacturer: Some("Bayer HealthCare LLC.".into()),
+                route: vec!["ORAL".into()],
+                label_date: Some("20231002".into()),
+                purpose: Some("Purpose Pain reliever".into()),
+                indications: None,
+                boxed_warning: None,
+                warnings: None,
+                dosage: None,
+            }))
+        );
+
+        assert!(matches!(
+            parse_response("aspirin", r#"{"results": []}"#),
+            Err(DrugLabelError::NotFound(drug)) if drug == "aspirin"
+        ));
+        assert_eq!(section(Some(vec!["x".repeat(MAX_SECTION_CHARS + 1)])).unwrap().chars().count(), MAX_SECTION_CHARS + 13);
+    }
+
+    #[test]
+    fn test_ambiguous_name_returns_candidates() {
+        let output = parse_response("bayer", RESPONSE).unwrap();
+        assert_eq!(
+            output,
+            DrugLabelOutput::Ambiguous {
+                candidates: vec![
+                    Candidate {
+                        brand_names: vec!["Bayer Back & Body".into()],
+                        generic_name: "ASPIRIN AND CAFFEINE".into(),
+                    },
+                    Candidate {
+                        brand_names: vec!["Bayer Aspirin".into()],
+                        generic_name: "ASPIRIN".into(),
+                    },
+                ],
+            }
+        );
+    }
+}
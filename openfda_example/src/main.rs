@@ -0,0 +1,35 @@
+mod drug_label_tool;
+
+use crate::drug_label_tool::DrugLabelTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the DrugLabelTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a health information assistant. Answer questions about medicines from their FDA labels, using the drug_label tool rather than memory, and say which label you used. If a name matches several drugs, ask which one the user meant. You don't give medical advice: remind users to talk to a doctor or pharmacist about their own situation.")
+        .tool(DrugLabelTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What is ibuprofen used for, and what are its main warnings?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
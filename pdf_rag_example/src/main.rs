@@ -0,0 +1,90 @@
+// main.rs
+
+mod pdf_loader;
+
+use anyhow::{Context, Result};
+use dotenv::dotenv;
+use pdf_loader::load_pdf_chunks;
+use rig::completion::Prompt;
+use rig::embeddings::EmbeddingsBuilder;
+use rig::providers::openai;
+use rig::vector_store::in_memory_store::InMemoryVectorStore;
+use rig::vector_store::VectorStore;
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+/// Number of chunks retrieved for every question
+const CONTEXT_CHUNKS: usize = 4;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    let path: PathBuf = std::env::args()
+        .nth(1)
+        .context("Usage: cargo run -- <path/to/document.pdf>")?
+        .into();
+
+    // Initialize OpenAI client
+    let api_key = std::env::var("OPENAI_API_KEY").context("OPENAI_API_KEY not set")?;
+    let openai_client = openai::Client::new(&api_key);
+    let embedding_model = openai_client.embedding_model(openai::TEXT_EMBEDDING_3_SMALL);
+
+    // Extract and chunk the PDF
+    let (chunks, skipped) = load_pdf_chunks(&path)?;
+    if chunks.is_empty() {
+        anyhow::bail!("No readable text found in {:?}; scanned PDFs need OCR first", path);
+    }
+    println!("Loaded {} chunks from {:?}", chunks.len(), path);
+    if skipped > 0 {
+        println!("Skipped {} empty or unreadable pages", skipped);
+    }
+
+    // Create embeddings and add to vector store
+    let mut builder = EmbeddingsBuilder::new(embedding_model.clone());
+    for chunk in &chunks {
+        builder = builder.simple_document(&chunk.id, &chunk.text);
+    }
+    let embeddings = builder.build().await?;
+
+    let mut vector_store = InMemoryVectorStore::default();
+    vector_store.add_documents(embeddings).await?;
+
+    // Create index
+    let index = vector_store.index(embedding_model);
+
+    // Create Agent. Each retrieved chunk is labeled with its id, so the model
+    // can cite the page it came from.
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You answer questions about a PDF document using the excerpts provided as context. Each excerpt's id says which page it comes from; cite pages in your answers (e.g. 'see page 3'). If the excerpts don't contain the answer, say so.")
+        .dynamic_context(CONTEXT_CHUNKS, index)
+        .build();
+
+    println!("Ask a question about the document (type 'exit' to quit)");
+
+    loop {
+        print!("> ");
+        io::stdout().flush()?;
+
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            break;
+        }
+
+        let input = input.trim();
+        if input.is_empty() {
+            continue;
+        }
+        if input.eq_ignore_ascii_case("exit") {
+            break;
+        }
+
+        match agent.prompt(input).await {
+            Ok(response) => println!("{}\n", response),
+            Err(e) => eprintln!("Error: {}\n", e),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,112 @@
+// pdf_loader.rs
+
+use anyhow::{Context, Result};
+use std::path::Path;
+
+/// Upper bound on the size of a chunk. Small chunks keep the retrieved context
+/// focused; the agent pulls in several of them per question.
+pub const MAX_CHUNK_CHARS: usize = 2000;
+
+/// Share of characters that must be readable for a page to be kept. Scanned
+/// pages and PDFs with broken font encodings extract as mostly symbols or
+/// replacement characters, which only add noise to the index.
+const MIN_READABLE_RATIO: f64 = 0.8;
+
+/// A piece of a page, ready to be embedded
+#[derive(Debug, PartialEq)]
+pub struct PdfChunk {
+    /// e.g. `page-3-chunk-2`
+    pub id: String,
+    pub text: String,
+}
+
+/// Extract every page of the PDF at `path` and split it into chunks,
+/// skipping pages that are empty or can't be read as text. Returns the chunks
+/// and the number of pages that were skipped.
+pub fn load_pdf_chunks(path: &Path) -> Result<(Vec<PdfChunk>, usize)> {
+    let pages = pdf_extract::extract_text_by_pages(path)
+        .with_context(|| format!("Failed to extract text from PDF: {:?}", path))?;
+
+    let mut chunks = Vec::new();
+    let mut skipped = 0;
+
+    for (index, page) in pages.iter().enumerate() {
+        if !is_readable(page) {
+            skipped += 1;
+            continue;
+        }
+
+        for (chunk_index, text) in chunk_text(page, MAX_CHUNK_CHARS).into_iter().enumerate() {
+            chunks.push(PdfChunk {
+                id: format!("page-{}-chunk-{}", index + 1, chunk_index + 1),
+                text,
+            });
+        }
+    }
+
+    Ok((chunks, skipped))
+}
+
+/// Whether a page has enough real text to be worth indexing
+fn is_readable(text: &str) -> bool {
+    let chars: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if chars.is_empty() {
+        return false;
+    }
+
+    let readable = chars
+        .iter()
+        .filter(|c| c.is_alphanumeric() || c.is_ascii_punctuation())
+        .count();
+    readable as f64 / chars.len() as f64 >= MIN_READABLE_RATIO
+}
+
+/// Split `text` into chunks of at most `max_chars` characters on word
+/// boundaries, normalizing the whitespace PDF extraction leaves behind
+fn chunk_text(text: &str, max_chars: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+    let mut current_chars = 0;
+
+    for word in text.split_whitespace() {
+        let word_chars = word.chars().count();
+        if current_chars > 0 && current_chars + 1 + word_chars > max_chars {
+            chunks.push(std::mem::take(&mut current));
+            current_chars = 0;
+        }
+        if current_chars > 0 {
+            current.push(' ');
+            current_chars += 1;
+        }
+        current.push_str(word);
+        current_chars += word_chars;
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text_splits_on_words() {
+        let text = "The quick  brown\nfox jumps over\n\nthe lazy dog";
+        assert_eq!(
+            chunk_text(text, 16),
+            vec!["The quick brown", "fox jumps over", "the lazy dog"]
+        );
+        assert!(chunk_text(" \n\t ", 16).is_empty());
+    }
+
+    #[test]
+    fn test_is_readable() {
+        assert!(is_readable("Chapter 1: Getting started with Rig."));
+        assert!(!is_readable("   \n  "));
+        assert!(!is_readable("\u{FFFD}\u{FFFD}\u{FFFD} ■■■■ ab"));
+    }
+}
@@ -0,0 +1,50 @@
+mod perplexity;
+
+use dotenv::dotenv;
+use rig::completion::{Completion, ModelChoice};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the Perplexity client from PERPLEXITY_API_KEY
+    let perplexity_client = perplexity::Client::from_env()?;
+    let model = std::env::var("PERPLEXITY_MODEL").unwrap_or_else(|_| perplexity::SONAR.to_string());
+
+    // Sonar models search the web before answering, so the agent needs no
+    // search tool of its own
+    let agent = perplexity_client
+        .agent(&model)
+        .preamble("You are a helpful research assistant. Answer concisely and cite your sources with [n] markers.")
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What are the most recent releases of the Rust programming language and what did they add?".to_string()
+    } else {
+        query
+    };
+
+    // `prompt` would only return the text, so send the request directly to
+    // keep the raw response and its citations
+    let response = agent.completion(&query, vec![]).await?.send().await?;
+
+    let answer = match response.choice {
+        ModelChoice::Message(text) => text,
+        ModelChoice::ToolCall(name, _) => format!("Unexpected tool call: {}", name),
+    };
+    println!("Agent response:\n{}", answer);
+
+    if !response.raw_response.citations.is_empty() {
+        println!("\nSources:");
+        for (i, citation) in response.raw_response.citations.iter().enumerate() {
+            match &citation.title {
+                Some(title) => println!("[{}] {} - {}", i + 1, title, citation.url),
+                None => println!("[{}] {}", i + 1, citation.url),
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,309 @@
+// perplexity.rs
+
+use rig::agent::AgentBuilder;
+use rig::completion::{self, CompletionError, CompletionRequest};
+use rig::json_utils;
+use rig::providers::openai;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+pub const PERPLEXITY_API_BASE_URL: &str = "https://api.perplexity.ai";
+
+/// `sonar` completion model, a lightweight web-grounded model. Set
+/// `PERPLEXITY_MODEL` to use another, such as `sonar-pro`.
+pub const SONAR: &str = "sonar";
+
+/// A client for Perplexity's chat completions API.
+///
+/// Perplexity's API is OpenAI-compatible, but rig's `openai::Client` can't be
+/// used as-is: it posts to `{base_url}/v1/chat/completions` while Perplexity
+/// serves `/chat/completions`, and its response type has no room for the
+/// citations Perplexity adds next to the usual OpenAI fields. This client
+/// sends the requests itself and parses the message with rig's OpenAI types.
+#[derive(Clone)]
+pub struct Client {
+    base_url: String,
+    http_client: reqwest::Client,
+}
+
+impl Client {
+    pub fn new(api_key: &str) -> Result<Self, String> {
+        Self::from_url(api_key, PERPLEXITY_API_BASE_URL)
+    }
+
+    pub fn from_url(api_key: &str, base_url: &str) -> Result<Self, String> {
+        let mut headers = reqwest::header::HeaderMap::new();
+        let authorization = reqwest::header::HeaderValue::from_str(&format!("Bearer {}", api_key))
+            .map_err(|_| "The Perplexity API key contains invalid characters".to_string())?;
+        headers.insert(reqwest::header::AUTHORIZATION, authorization);
+
+        let http_client = reqwest::Client::builder()
+            .default_headers(headers)
+            .build()
+            .map_err(|e| e.to_string())?;
+
+        Ok(Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            http_client,
+        })
+    }
+
+    /// Create a client from the `PERPLEXITY_API_KEY` environment variable
+    pub fn from_env() -> Result<Self, String> {
+        let api_key = std::env::var("PERPLEXITY_API_KEY")
+            .map_err(|_| "PERPLEXITY_API_KEY not set".to_string())?;
+        Self::new(&api_key)
+    }
+
+    pub fn completion_model(&self, model: &str) -> CompletionModel {
+        CompletionModel {
+            client: self.clone(),
+            model: model.to_string(),
+        }
+    }
+
+    /// Start building an agent for `model`
+    pub fn agent(&self, model: &str) -> AgentBuilder<CompletionModel> {
+        AgentBuilder::new(self.completion_model(model))
+    }
+}
+
+/// A web page the answer was based on
+#[derive(Debug, PartialEq)]
+pub struct Citation {
+    pub url: String,
+    /// Only known when the response includes `search_results`
+    pub title: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SearchResult {
+    title: Option<String>,
+    url: String,
+}
+
+/// The Perplexity-specific fields of a response
+#[derive(Deserialize)]
+struct Sources {
+    #[serde(default)]
+    citations: Vec<String>,
+    #[serde(default)]
+    search_results: Vec<SearchResult>,
+}
+
+impl Sources {
+    /// `citations` is the list the answer's `[1]`, `[2]`... markers refer to.
+    /// Titles are looked up in `search_results`, which newer responses include.
+    fn into_citations(self) -> Vec<Citation> {
+        let Sources {
+            citations,
+            search_results,
+        } = self;
+
+        if citations.is_empty() {
+            return search_results
+                .into_iter()
+                .map(|result| Citation {
+                    url: result.url,
+                    title: result.title,
+                })
+                .collect();
+        }
+
+        citations
+            .into_iter()
+            .map(|url| Citation {
+                title: search_results
+                    .iter()
+                    .find(|result| result.url == url)
+                    .and_then(|result| result.title.clone()),
+                url,
+            })
+            .collect()
+    }
+}
+
+/// A chat completion together with the sources it cites
+#[derive(Debug)]
+pub struct CompletionResponse {
+    pub completion: openai::CompletionResponse,
+    /// In the order of the answer's `[1]`, `[2]`... markers
+    pub citations: Vec<Citation>,
+}
+
+/// Parse a response body: the OpenAI-compatible part with rig's types, the
+/// citations separately
+fn parse_response(text: &str) -> Result<CompletionResponse, serde_json::Error> {
+    Ok(CompletionResponse {
+        completion: serde_json::from_str(text)?,
+        citations: serde_json::from_str::<Sources>(text)?.into_citations(),
+    })
+}
+
+#[derive(Clone)]
+pub struct CompletionModel {
+    client: Client,
+    /// Name of the model (e.g.: sonar)
+    pub model: String,
+}
+
+impl CompletionModel {
+    /// Build the chat completions body for `request`. Sonar models can't call
+    /// tools, so there are no tool definitions.
+    fn request_body(&self, mut request: CompletionRequest) -> Value {
+        let mut messages = Vec::new();
+        if let Some(preamble) = &request.preamble {
+            messages.push(completion::Message {
+                role: "system".into(),
+                content: preamble.clone(),
+            });
+        }
+        messages.append(&mut request.chat_history);
+        messages.push(completion::Message {
+            role: "user".into(),
+            content: request.prompt_with_context(),
+        });
+
+        // Perplexity rejects `null` values, so optional parameters are only
+        // sent when set
+        let mut body = json!({
+            "model": self.model,
+            "messages": messages,
+        });
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = json!(temperature);
+        }
+        if let Some(max_tokens) = request.max_tokens {
+            body["max_tokens"] = json!(max_tokens);
+        }
+
+        match request.additional_params {
+            Some(params) => json_utils::merge(body, params),
+            None => body,
+        }
+    }
+}
+
+impl completion::CompletionModel for CompletionModel {
+    type Response = CompletionResponse;
+
+    async fn completion(
+        &self,
+        request: CompletionRequest,
+    ) -> Result<completion::CompletionResponse<CompletionResponse>, CompletionError> {
+        if !request.tools.is_empty() {
+            return Err(CompletionError::RequestError(
+                "Perplexity's Sonar models don't support tool calling".into(),
+            ));
+        }
+
+        let response = self
+            .client
+            .http_client
+            .post(format!("{}/chat/completions", self.client.base_url))
+            .json(&self.request_body(request))
+            .send()
+            .await?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+        let text = response.text().await?;
+
+        // Perplexity explains what went wrong (unknown model, invalid key,
+        // messages not alternating...) in the body of the error response
+        if !status.is_success() {
+            return Err(CompletionError::ProviderError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        let CompletionResponse {
+            completion,
+            citations,
+        } = parse_response(&text)?;
+        let response: completion::CompletionResponse<openai::CompletionResponse> = completion.try_into()?;
+
+        Ok(completion::CompletionResponse {
+            choice: response.choice,
+            raw_response: CompletionResponse {
+                completion: response.raw_response,
+                citations,
+            },
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_request_body() {
+        let model = Client::new("key").unwrap().completion_model(SONAR);
+        let request = CompletionRequest {
+            prompt: "What is Rig?".into(),
+            preamble: Some("Be precise and concise.".into()),
+            chat_history: vec![],
+            documents: vec![],
+            tools: vec![],
+            temperature: None,
+            max_tokens: Some(512),
+            additional_params: Some(json!({ "search_recency_filter": "week" })),
+        };
+
+        assert_eq!(
+            model.request_body(request),
+            json!({
+                "model": "sonar",
+                "messages": [
+                    { "role": "system", "content": "Be precise and concise." },
+                    { "role": "user", "content": "What is Rig?" },
+                ],
+                "max_tokens": 512,
+                "search_recency_filter": "week",
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_response_with_citations() {
+        let text = r#"{
+            "id": "3c90c3cc-0d44-4b50-8888-8dd25736052a",
+            "object": "chat.completion",
+            "created": 1728000000,
+            "model": "sonar",
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": "Rig is a Rust library for LLM applications [1][2]." },
+                "finish_reason": "stop"
+            }],
+            "usage": { "prompt_tokens": 8, "completion_tokens": 14, "total_tokens": 22 },
+            "citations": ["https://github.com/0xPlaygrounds/rig", "https://docs.rig.rs"],
+            "search_results": [{ "title": "Rig on GitHub", "url": "https://github.com/0xPlaygrounds/rig" }]
+        }"#;
+
+        let response = parse_response(text).unwrap();
+        assert_eq!(
+            response.completion.choices[0].message.content.as_deref(),
+            Some("Rig is a Rust library for LLM applications [1][2].")
+        );
+        assert_eq!(
+            response.citations,
+            vec![
+                Citation {
+                    url: "https://github.com/0xPlaygrounds/rig".into(),
+                    title: Some("Rig on GitHub".into()),
+                },
+                Citation {
+                    url: "https://docs.rig.rs".into(),
+                    title: None,
+                },
+            ]
+        );
+
+        // A response without citations, e.g. from a question needing no search
+        let text = text.replace(r#""citations""#, r#""unused""#).replace("search_results", "unused2");
+        assert!(parse_response(&text).unwrap().citations.is_empty());
+    }
+}
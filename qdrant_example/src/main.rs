@@ -0,0 +1,81 @@
+// main.rs
+//
+// Start Qdrant before running this example, either with
+//
+//     docker run -p 6333:6333 -v qdrant_storage:/qdrant/storage qdrant/qdrant
+//
+// or with this docker-compose.yml:
+//
+//     services:
+//       qdrant:
+//         image: qdrant/qdrant
+//         ports:
+//           - "6333:6333"
+//         volumes:
+//           - qdrant_storage:/qdrant/storage
+//     volumes:
+//       qdrant_storage:
+
+mod qdrant_store;
+
+use anyhow::{Context, Result};
+use dotenv::dotenv;
+use qdrant_store::QdrantStore;
+use rig::completion::Prompt;
+use rig::embeddings::EmbeddingsBuilder;
+use rig::providers::openai;
+
+const COLLECTION: &str = "rig_definitions";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    // Initialize OpenAI client
+    let api_key = std::env::var("OPENAI_API_KEY").context("OPENAI_API_KEY not set")?;
+    let openai_client = openai::Client::new(&api_key);
+    let embedding_model = openai_client.embedding_model(openai::TEXT_EMBEDDING_3_SMALL);
+
+    let qdrant_url = std::env::var("QDRANT_URL").unwrap_or_else(|_| "http://localhost:6333".to_string());
+    let store = QdrantStore::connect(&qdrant_url, COLLECTION).await?;
+
+    // Qdrant keeps the embeddings across restarts, so only embed the
+    // documents the first time
+    match store.point_count().await? {
+        Some(count) if count > 0 => {
+            println!("Using {} points already stored in '{}'", count, COLLECTION);
+        }
+        existing => {
+            let embeddings = EmbeddingsBuilder::new(embedding_model.clone())
+                .simple_document("doc0", "Definition of a *flurbo*: A flurbo is a green alien that lives on cold planets")
+                .simple_document("doc1", "Definition of a *glarb-glarb*: A glarb-glarb is an ancient tool used by the ancestors of the inhabitants of planet Jiro to farm the land.")
+                .simple_document("doc2", "Definition of a *linglingdong*: A term used by inhabitants of the far side of the moon to describe humans.")
+                .build()
+                .await?;
+
+            if existing.is_none() {
+                let dimensions = embeddings
+                    .first()
+                    .and_then(|doc| doc.embeddings.first())
+                    .map(|embedding| embedding.vec.len())
+                    .context("No embeddings were generated")?;
+                store.create_collection(dimensions).await?;
+            }
+            store.upsert(&embeddings).await?;
+            println!("Stored {} documents in '{}'", embeddings.len(), COLLECTION);
+        }
+    }
+
+    // The Qdrant index is used exactly like the in-memory one
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a dictionary assistant here to assist the user in understanding the meaning of words. Use the definitions provided as context.")
+        .dynamic_context(1, store.index(embedding_model))
+        .build();
+
+    let response = agent.prompt("What does \"glarb-glarb\" mean?").await?;
+
+    println!("{}", response);
+
+    Ok(())
+}
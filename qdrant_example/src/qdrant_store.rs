@@ -0,0 +1,231 @@
+// qdrant_store.rs
+
+// A minimal Qdrant backend for rig, talking to Qdrant's REST API. Documents
+// are stored as points whose payload holds the rig document id and content,
+// and `QdrantIndex` implements `VectorStoreIndex` so it can be passed to
+// `dynamic_context` just like the in-memory index.
+
+use reqwest::StatusCode;
+use rig::embeddings::{DocumentEmbeddings, Embedding, EmbeddingModel};
+use rig::vector_store::{VectorStoreError, VectorStoreIndex};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[derive(Debug, thiserror::Error)]
+pub enum QdrantError {
+    #[error("Could not connect to Qdrant at {0}. Is it running? Start it with: docker run -p 6333:6333 qdrant/qdrant")]
+    ConnectionRefused(String),
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(#[from] reqwest::Error),
+    #[error("Qdrant returned {status}: {body}")]
+    ApiError { status: StatusCode, body: String },
+}
+
+impl From<QdrantError> for VectorStoreError {
+    fn from(e: QdrantError) -> Self {
+        VectorStoreError::DatastoreError(Box::new(e))
+    }
+}
+
+/// What is stored alongside each vector
+#[derive(Debug, Serialize, Deserialize)]
+struct Payload {
+    doc_id: String,
+    document: Value,
+}
+
+#[derive(Deserialize)]
+struct QdrantResponse<T> {
+    result: T,
+}
+
+#[derive(Deserialize)]
+struct CountResult {
+    count: u64,
+}
+
+#[derive(Deserialize)]
+struct ScoredPoint {
+    score: f64,
+    payload: Payload,
+}
+
+/// A handle on one Qdrant collection
+#[derive(Clone)]
+pub struct QdrantStore {
+    http_client: reqwest::Client,
+    url: String,
+    collection: String,
+}
+
+impl QdrantStore {
+    /// Connect to the Qdrant server at `url` (e.g. `http://localhost:6333`),
+    /// failing early with a clear error if nothing is listening there
+    pub async fn connect(url: &str, collection: &str) -> Result<Self, QdrantError> {
+        let store = Self {
+            http_client: reqwest::Client::new(),
+            url: url.trim_end_matches('/').to_string(),
+            collection: collection.to_string(),
+        };
+        store.send(store.http_client.get(format!("{}/collections", store.url))).await?;
+        Ok(store)
+    }
+
+    /// Number of points in the collection, or `None` if it doesn't exist yet
+    pub async fn point_count(&self) -> Result<Option<u64>, QdrantError> {
+        let response = self
+            .http_client
+            .post(self.collection_url("points/count"))
+            .json(&json!({ "exact": true }))
+            .send()
+            .await
+            .map_err(|e| self.request_error(e))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let count: QdrantResponse<CountResult> = Self::check(response).await?.json().await?;
+        Ok(Some(count.result.count))
+    }
+
+    /// Create the collection for vectors of `dimensions` using cosine distance
+    pub async fn create_collection(&self, dimensions: usize) -> Result<(), QdrantError> {
+        let body = json!({ "vectors": { "size": dimensions, "distance": "Cosine" } });
+        let url = format!("{}/collections/{}", self.url, self.collection);
+        self.send(self.http_client.put(url).json(&body)).await?;
+        Ok(())
+    }
+
+    /// Insert the documents, replacing any points previously stored for them
+    pub async fn upsert(&self, documents: &[DocumentEmbeddings]) -> Result<(), QdrantError> {
+        let points: Vec<Value> = documents
+            .iter()
+            .flat_map(|doc| {
+                doc.embeddings.iter().enumerate().map(|(i, embedding)| {
+                    json!({
+                        "id": point_id(&doc.id, i),
+                        "vector": embedding.vec,
+                        "payload": Payload { doc_id: doc.id.clone(), document: doc.document.clone() },
+                    })
+                })
+            })
+            .collect();
+
+        let request = self
+            .http_client
+            .put(self.collection_url("points?wait=true"))
+            .json(&json!({ "points": points }));
+        self.send(request).await?;
+        Ok(())
+    }
+
+    /// An index over the collection that embeds queries with `model`
+    pub fn index<M: EmbeddingModel>(&self, model: M) -> QdrantIndex<M> {
+        QdrantIndex {
+            store: self.clone(),
+            model,
+        }
+    }
+
+    async fn search(&self, vector: &[f64], limit: usize) -> Result<Vec<ScoredPoint>, QdrantError> {
+        let body = json!({ "vector": vector, "limit": limit, "with_payload": true });
+        let request = self.http_client.post(self.collection_url("points/search")).json(&body);
+        let response: QdrantResponse<Vec<ScoredPoint>> = self.send(request).await?.json().await?;
+        Ok(response.result)
+    }
+
+    fn collection_url(&self, path: &str) -> String {
+        format!("{}/collections/{}/{}", self.url, self.collection, path)
+    }
+
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response, QdrantError> {
+        let response = request.send().await.map_err(|e| self.request_error(e))?;
+        Self::check(response).await
+    }
+
+    async fn check(response: reqwest::Response) -> Result<reqwest::Response, QdrantError> {
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+        let body = response.text().await?;
+        Err(QdrantError::ApiError { status, body })
+    }
+
+    fn request_error(&self, e: reqwest::Error) -> QdrantError {
+        if e.is_connect() {
+            QdrantError::ConnectionRefused(self.url.clone())
+        } else {
+            QdrantError::HttpRequestFailed(e)
+        }
+    }
+}
+
+/// Qdrant point ids must be integers or UUIDs. Derive one from the document id
+/// (FNV-1a) so re-running the indexing overwrites points instead of
+/// duplicating them.
+fn point_id(doc_id: &str, embedding_index: usize) -> u64 {
+    format!("{}#{}", doc_id, embedding_index)
+        .bytes()
+        .fold(0xcbf29ce484222325, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+        })
+}
+
+pub struct QdrantIndex<M: EmbeddingModel> {
+    store: QdrantStore,
+    model: M,
+}
+
+impl<M: EmbeddingModel> VectorStoreIndex for QdrantIndex<M> {
+    async fn top_n_from_query(
+        &self,
+        query: &str,
+        n: usize,
+    ) -> Result<Vec<(f64, DocumentEmbeddings)>, VectorStoreError> {
+        let prompt_embedding = self.model.embed_document(query).await?;
+        self.top_n_from_embedding(&prompt_embedding, n).await
+    }
+
+    /// Returns Qdrant's cosine similarity score with each document. The
+    /// embeddings themselves are not fetched back.
+    async fn top_n_from_embedding(
+        &self,
+        prompt_embedding: &Embedding,
+        n: usize,
+    ) -> Result<Vec<(f64, DocumentEmbeddings)>, VectorStoreError> {
+        let points = self.store.search(&prompt_embedding.vec, n).await?;
+        Ok(points
+            .into_iter()
+            .map(|point| {
+                (
+                    point.score,
+                    DocumentEmbeddings {
+                        id: point.payload.doc_id,
+                        document: point.payload.document,
+                        embeddings: vec![],
+                    },
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_point_ids_are_stable_and_distinct() {
+        assert_eq!(point_id("doc0", 0), point_id("doc0", 0));
+        assert_ne!(point_id("doc0", 0), point_id("doc0", 1));
+        assert_ne!(point_id("doc0", 0), point_id("doc1", 0));
+    }
+
+    #[tokio::test]
+    async fn test_connection_refused_is_reported_clearly() {
+        // Nothing listens on port 1
+        let result = QdrantStore::connect("http://127.0.0.1:1", "test").await;
+        assert!(matches!(result, Err(QdrantError::ConnectionRefused(url)) if url == "http://127.0.0.1:1"));
+    }
+}
@@ -0,0 +1,51 @@
+mod read_file_tool;
+
+use crate::read_file_tool::ReadFileTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // The agent can only read files below this directory
+    let sandbox = std::env::var("READ_FILE_SANDBOX").unwrap_or_else(|_| "sandbox".to_string());
+
+    // Rig answers a prompt with the output of the first tool call the model
+    // makes, so the reader only reads the file with the ReadFileTool and a
+    // second agent answers from its contents
+    let reader = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("Call the read_file tool on the file the user asks about.")
+        .tool(ReadFileTool::new(sandbox))
+        .build();
+
+    let assistant = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a helpful assistant. Answer the user's request from the file contents you are given, as returned by a read_file tool.")
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "Summarize release_notes.md in three bullet points.".to_string()
+    } else {
+        query
+    };
+
+    // Step 1: read the file
+    let file = reader.prompt(&query).await?;
+
+    // Step 2: the file becomes part of the assistant's prompt
+    let response = assistant
+        .prompt(&format!("Request: {}\n\nFile:\n{}", query, file))
+        .await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
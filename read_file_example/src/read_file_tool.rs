@@ -0,0 +1,153 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::Deserialize;
+use serde_json::json;
+use std::path::{Component, Path, PathBuf};
+
+/// Largest file the tool will return, in bytes. Everything it returns ends up
+/// in the model's context window.
+pub const MAX_FILE_SIZE: u64 = 100 * 1024;
+
+#[derive(Deserialize)]
+pub struct ReadFileArgs {
+    path: String,
+}
+
+// Anything returned here is sent back to the model as the tool's output, so
+// the messages say what went wrong in terms it can act on
+#[derive(Debug, thiserror::Error)]
+pub enum ReadFileError {
+    #[error("Path must be relative to the sandbox and may not contain '..': {0}")]
+    OutsideSandbox(String),
+    #[error("File not found: {0}")]
+    NotFound(String),
+    #[error("{path} is {size} bytes; files larger than {limit} bytes can't be read")]
+    TooLarge { path: String, size: u64, limit: u64 },
+    #[error("{0} is not a UTF-8 text file")]
+    NotText(String),
+    #[error("Failed to read file: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// A tool that reads text files from a sandbox directory. The agent can only
+/// see files below `root`.
+pub struct ReadFileTool {
+    root: PathBuf,
+}
+
+impl ReadFileTool {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Resolve `path` inside the sandbox. Absolute paths and `..` are rejected
+    /// outright; the canonical path is then checked again so symlinks can't
+    /// point out of the sandbox either.
+    async fn resolve(&self, path: &str) -> Result<PathBuf, ReadFileError> {
+        let relative = Path::new(path);
+        if !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(ReadFileError::OutsideSandbox(path.to_string()));
+        }
+
+        let root = tokio::fs::canonicalize(&self.root).await?;
+        let resolved = tokio::fs::canonicalize(root.join(relative))
+            .await
+            .map_err(|_| ReadFileError::NotFound(path.to_string()))?;
+        if !resolved.starts_with(&root) {
+            return Err(ReadFileError::OutsideSandbox(path.to_string()));
+        }
+
+        Ok(resolved)
+    }
+}
+
+impl Tool for ReadFileTool {
+    const NAME: &'static str = "read_file";
+
+    type Args = ReadFileArgs;
+    type Output = String;
+    type Error = ReadFileError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "read_file".to_string(),
+            description: "Read a text file from the sandbox directory and return its contents.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string", "description": "Path of the file relative to the sandbox (e.g., 'notes.md')" },
+                },
+                "required": ["path"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let path = self.resolve(&args.path).await?;
+
+        let metadata = tokio::fs::metadata(&path).await?;
+        if !metadata.is_file() {
+            return Err(ReadFileError::NotFound(args.path));
+        }
+        // Check the size before reading so a huge file is never loaded
+        if metadata.len() > MAX_FILE_SIZE {
+            return Err(ReadFileError::TooLarge {
+                path: args.path,
+                size: metadata.len(),
+                limit: MAX_FILE_SIZE,
+            });
+        }
+
+        let bytes = tokio::fs::read(&path).await?;
+        String::from_utf8(bytes).map_err(|_| ReadFileError::NotText(args.path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fresh sandbox with one small and one oversized file
+    async fn sandbox(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("read_file_tool_{}_{}", name, std::process::id()));
+        tokio::fs::create_dir_all(root.join("docs")).await.unwrap();
+        tokio::fs::write(root.join("docs/notes.md"), "Rig is a Rust library.").await.unwrap();
+        tokio::fs::write(root.join("big.txt"), vec![b'a'; MAX_FILE_SIZE as usize + 1]).await.unwrap();
+        root
+    }
+
+    async fn read(tool: &ReadFileTool, path: &str) -> Result<String, ReadFileError> {
+        tool.call(ReadFileArgs { path: path.to_string() }).await
+    }
+
+    #[tokio::test]
+    async fn test_reads_files_in_sandbox() {
+        let root = sandbox("reads").await;
+        let tool = ReadFileTool::new(&root);
+
+        assert_eq!(read(&tool, "docs/notes.md").await.unwrap(), "Rig is a Rust library.");
+        assert_eq!(read(&tool, "./docs/notes.md").await.unwrap(), "Rig is a Rust library.");
+        assert!(matches!(read(&tool, "missing.md").await, Err(ReadFileError::NotFound(_))));
+        assert!(matches!(read(&tool, "big.txt").await, Err(ReadFileError::TooLarge { .. })));
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rejects_paths_outside_sandbox() {
+        let root = sandbox("rejects").await;
+        let tool = ReadFileTool::new(root.join("docs"));
+
+        for path in ["../big.txt", "docs/../../etc/passwd", "/etc/passwd"] {
+            assert!(
+                matches!(read(&tool, path).await, Err(ReadFileError::OutsideSandbox(_))),
+                "{path} was not rejected"
+            );
+        }
+
+        tokio::fs::remove_dir_all(root).await.unwrap();
+    }
+}
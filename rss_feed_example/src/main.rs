@@ -0,0 +1,48 @@
+mod rss_feed_tool;
+
+use crate::rss_feed_tool::RssFeedTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Rig answers a prompt with the output of the first tool call the model
+    // makes, so the reader only fetches the feed with the RssFeedTool and a
+    // second agent answers from its entries
+    let reader = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("Call the read_feed tool on the RSS or Atom feed in the user's request.")
+        .tool(RssFeedTool)
+        .build();
+
+    let assistant = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a news monitoring assistant. Answer the user's request from the feed entries you are given, as returned by a read_feed tool, and link to the entries you mention.")
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What are the latest posts on the Rust blog (https://blog.rust-lang.org/feed.xml)? Summarize the three most recent ones.".to_string()
+    } else {
+        query
+    };
+
+    // Step 1: read the feed
+    let feed = reader.prompt(&query).await?;
+
+    // Step 2: the feed becomes part of the assistant's prompt
+    let response = assistant
+        .prompt(&format!("Request: {}\n\nFeed:\n{}", query, feed))
+        .await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
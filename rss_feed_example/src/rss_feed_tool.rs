@@ -0,0 +1,290 @@
+use feed_rs::model::Entry;
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::time::Duration;
+
+// Maximum time to wait for the feed before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Largest feed downloaded, in bytes
+const MAX_FEED_SIZE: usize = 5 * 1024 * 1024;
+
+// Entries returned when the model doesn't ask for a number, and the most it may ask for
+const DEFAULT_ENTRIES: usize = 10;
+const MAX_ENTRIES: usize = 25;
+
+// Most characters of each entry's summary returned to the model
+const MAX_SUMMARY_LENGTH: usize = 500;
+
+// Tags that separate words, so they are replaced with a space rather than removed
+const BLOCK_TAGS: &[&str] = &["p", "br", "div", "li", "ul", "ol", "h1", "h2", "h3", "h4", "blockquote", "tr", "td"];
+
+#[derive(Deserialize)]
+pub struct RssFeedArgs {
+    url: String,
+    limit: Option<usize>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RssFeedError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("Invalid URL '{0}': only http and https feed URLs are supported")]
+    InvalidUrl(String),
+    #[error("The feed is larger than the {} MiB limit", MAX_FEED_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Not a valid RSS or Atom feed: {0}. Check that the URL points to the feed itself rather than a web page")]
+    InvalidFeed(String),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FeedEntry {
+    title: String,
+    link: Option<String>,
+    /// RFC 3339 date the entry was published, or last updated if the feed
+    /// doesn't say when it was published
+    published: Option<String>,
+    summary: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct FeedOutput {
+    title: Option<String>,
+    entries: Vec<FeedEntry>,
+}
+
+pub struct RssFeedTool;
+
+// Feed summaries are often HTML: drop the tags, decode the common entities and
+// collapse whitespace
+fn strip_html(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut tag: Option<String> = None;
+    for c in html.chars() {
+        match (&mut tag, c) {
+            (None, '<') => tag = Some(String::new()),
+            (Some(name), '>') => {
+                let name = name.trim_start_matches('/');
+                let name = name.split([' ', '/']).next().unwrap_or_default();
+                if BLOCK_TAGS.contains(&name.to_ascii_lowercase().as_str()) {
+                    text.push(' ');
+                }
+                tag = None;
+            }
+            (Some(name), c) => name.push(c),
+            (None, c) => text.push(c),
+        }
+    }
+
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// Cut `text` to at most `MAX_SUMMARY_LENGTH` characters
+fn truncate_summary(text: String) -> String {
+    match text.char_indices().nth(MAX_SUMMARY_LENGTH) {
+        Some((end, _)) => format!("{}…", &text[..end]),
+        None => text,
+    }
+}
+
+fn feed_entry(entry: Entry) -> FeedEntry {
+    let summary = entry
+        .summary
+        .map(|summary| summary.content)
+        .or_else(|| entry.content.and_then(|content| content.body))
+        .map(|summary| truncate_summary(strip_html(&summary)))
+        .filter(|summary| !summary.is_empty());
+
+    FeedEntry {
+        title: entry
+            .title
+            .map(|title| strip_html(&title.content))
+            .unwrap_or_else(|| "(untitled)".to_string()),
+        link: entry.links.into_iter().next().map(|link| link.href),
+        published: entry.published.or(entry.updated).map(|date| date.to_rfc3339()),
+        summary,
+    }
+}
+
+// Parse an RSS, Atom or JSON Feed document and keep the `limit` most recent
+// entries. Entries without a date are kept in feed order after dated ones.
+fn parse_feed(bytes: &[u8], limit: usize) -> Result<FeedOutput, RssFeedError> {
+    let feed = feed_rs::parser::parse(bytes).map_err(|e| RssFeedError::InvalidFeed(e.to_string()))?;
+
+    let mut entries = feed.entries;
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.published.or(entry.updated)));
+    entries.truncate(limit);
+
+    Ok(FeedOutput {
+        title: feed.title.map(|title| strip_html(&title.content)),
+        entries: entries.into_iter().map(feed_entry).collect(),
+    })
+}
+
+impl Tool for RssFeedTool {
+    const NAME: &'static str = "read_feed";
+
+    type Args = RssFeedArgs;
+    type Output = FeedOutput;
+    type Error = RssFeedError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "read_feed".to_string(),
+            description: "Fetch an RSS or Atom feed and return its most recent entries, newest first, with their titles, links, publication dates and summaries".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "url": { "type": "string", "description": "The URL of the feed itself (e.g., 'https://blog.rust-lang.org/feed.xml')" },
+                    "limit": { "type": "integer", "description": format!("How many entries to return (default {}, at most {})", DEFAULT_ENTRIES, MAX_ENTRIES) },
+                },
+                "required": ["url"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let url = reqwest::Url::parse(args.url.trim())
+            .ok()
+            .filter(|url| matches!(url.scheme(), "http" | "https"))
+            .ok_or_else(|| RssFeedError::InvalidUrl(args.url.clone()))?;
+        let limit = args.limit.unwrap_or(DEFAULT_ENTRIES).clamp(1, MAX_ENTRIES);
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("rig-examples/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .map_err(|e| RssFeedError::HttpRequestFailed(e.to_string()))?;
+
+        let mut response = client
+            .get(url)
+            .send()
+            .await
+            .map_err(|e| RssFeedError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        if response
+            .content_length()
+            .is_some_and(|length| length > MAX_FEED_SIZE as u64)
+        {
+            return Err(RssFeedError::TooLarge);
+        }
+
+        // The length header is optional, so the cap is also enforced while reading
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| RssFeedError::HttpRequestFailed(e.to_string()))?
+        {
+            if bytes.len() + chunk.len() > MAX_FEED_SIZE {
+                return Err(RssFeedError::TooLarge);
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        if !status.is_success() {
+            return Err(RssFeedError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status,
+                String::from_utf8_lossy(&bytes)
+            )));
+        }
+        if bytes.is_empty() {
+            return Err(RssFeedError::InvalidResponse("the feed is empty".to_string()));
+        }
+
+        parse_feed(&bytes, limit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rss_and_atom() {
+        let rss = br#"<?xml version="1.0"?>
+            <rss version="2.0"><channel>
+              <title>Rust Blog</title>
+              <item>
+                <title>Announcing Rust 1.80</title>
+                <link>https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html</link>
+                <pubDate>Thu, 25 Jul 2024 00:00:00 +0000</pubDate>
+                <description>&lt;p&gt;LazyCell &amp;amp; LazyLock are &lt;b&gt;stable&lt;/b&gt;.&lt;/p&gt;</description>
+              </item>
+              <item>
+                <title>Announcing Rust 1.81</title>
+                <link>https://blog.rust-lang.org/2024/09/05/Rust-1.81.0.html</link>
+                <pubDate>Thu, 05 Sep 2024 00:00:00 +0000</pubDate>
+              </item>
+            </channel></rss>"#;
+
+        let feed = parse_feed(rss, 10).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Rust Blog"));
+        // Newest first, whatever the order in the feed
+        assert_eq!(feed.entries[0].title, "Announcing Rust 1.81");
+        assert_eq!(
+            feed.entries[1],
+            FeedEntry {
+                title: "Announcing Rust 1.80".into(),
+                link: Some("https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html".into()),
+                published: Some("2024-07-25T00:00:00+00:00".into()),
+                summary: Some("LazyCell & LazyLock are stable.".into()),
+            }
+        );
+        assert_eq!(parse_feed(rss, 1).unwrap().entries.len(), 1);
+
+        let atom = br#"<?xml version="1.0" encoding="utf-8"?>
+            <feed xmlns="http://www.w3.org/2005/Atom">
+              <title>Example Atom Feed</title>
+              <id>urn:example</id>
+              <updated>2024-10-08T12:00:00Z</updated>
+              <entry>
+                <title>First post</title>
+                <id>urn:example:1</id>
+                <link href="https://example.com/first"/>
+                <updated>2024-10-08T12:00:00Z</updated>
+                <summary>Hello from Atom.</summary>
+              </entry>
+            </feed>"#;
+
+        let feed = parse_feed(atom, 10).unwrap();
+        assert_eq!(
+            feed.entries,
+            vec![FeedEntry {
+                title: "First post".into(),
+                link: Some("https://example.com/first".into()),
+                published: Some("2024-10-08T12:00:00+00:00".into()),
+                summary: Some("Hello from Atom.".into()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_malformed_feed() {
+        assert!(matches!(
+            parse_feed(b"<rss><channel><title>Broken", 10),
+            Err(RssFeedError::InvalidFeed(_))
+        ));
+        assert!(matches!(
+            parse_feed(b"<!DOCTYPE html><html><body>Not a feed</body></html>", 10),
+            Err(RssFeedError::InvalidFeed(_))
+        ));
+    }
+}
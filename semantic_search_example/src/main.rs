@@ -0,0 +1,104 @@
+// main.rs
+
+use anyhow::{Context, Result};
+use dotenv::dotenv;
+use rig::embeddings::{EmbeddingModel, EmbeddingsBuilder};
+use rig::providers::openai;
+use rig::vector_store::in_memory_store::InMemoryVectorStore;
+use rig::vector_store::{VectorStore, VectorStoreIndex};
+
+/// Number of nearest neighbors printed for the query
+const TOP_K: usize = 3;
+
+/// The corpus to search
+const SENTENCES: &[&str] = &[
+    "Rust guarantees memory safety without a garbage collector.",
+    "The borrow checker enforces rules about references at compile time.",
+    "Tokio is an asynchronous runtime for writing network applications.",
+    "Async functions return futures that do nothing until they are awaited.",
+    "Cargo is Rust's build system and package manager.",
+    "Crates are published to crates.io for others to depend on.",
+    "Paris is the capital of France.",
+    "The Great Barrier Reef is the largest coral reef system in the world.",
+    "Photosynthesis turns sunlight, water and carbon dioxide into glucose.",
+    "A sourdough starter is a fermented mix of flour and water.",
+];
+
+/// Cosine similarity of two vectors: 1.0 for the same direction, 0.0 for
+/// unrelated ones
+fn cosine_similarity(a: &[f64], b: &[f64]) -> f64 {
+    let dot: f64 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f64>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+    dot / (norm_a * norm_b)
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    // Take the query from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "How does Rust handle concurrency?".to_string()
+    } else {
+        query
+    };
+
+    // Initialize OpenAI client
+    let api_key = std::env::var("OPENAI_API_KEY").context("OPENAI_API_KEY not set")?;
+    let openai_client = openai::Client::new(&api_key);
+    let embedding_model = openai_client.embedding_model(openai::TEXT_EMBEDDING_3_SMALL);
+
+    // Embed every sentence, using its position as the document id
+    let mut builder = EmbeddingsBuilder::new(embedding_model.clone());
+    for (i, sentence) in SENTENCES.iter().enumerate() {
+        builder = builder.simple_document(&i.to_string(), sentence);
+    }
+    let embeddings = builder.build().await?;
+
+    let mut vector_store = InMemoryVectorStore::default();
+    vector_store.add_documents(embeddings).await?;
+    let index = vector_store.index(embedding_model.clone());
+
+    // Embed the query once, then search the index with it directly
+    let query_embedding = embedding_model.embed_document(&query).await?;
+    let mut results: Vec<(f64, String)> = index
+        .top_n_from_embedding(&query_embedding, TOP_K)
+        .await?
+        .into_iter()
+        .filter_map(|(_, doc)| {
+            let embedding = doc.embeddings.first()?;
+            Some((
+                cosine_similarity(&query_embedding.vec, &embedding.vec),
+                embedding.document.clone(),
+            ))
+        })
+        .collect();
+
+    // The index returns its matches in no particular order
+    results.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    println!("Top {} matches for {:?}:", TOP_K, query);
+    for (score, sentence) in results {
+        println!("  {:.3}  {}", score, sentence);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cosine_similarity() {
+        assert!((cosine_similarity(&[1.0, 2.0], &[2.0, 4.0]) - 1.0).abs() < 1e-9);
+        assert!(cosine_similarity(&[1.0, 0.0], &[0.0, 3.0]).abs() < 1e-9);
+        assert!((cosine_similarity(&[1.0, 0.0], &[-1.0, 0.0]) + 1.0).abs() < 1e-9);
+        assert_eq!(cosine_similarity(&[0.0, 0.0], &[1.0, 1.0]), 0.0);
+    }
+}
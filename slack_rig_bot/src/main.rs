@@ -0,0 +1,233 @@
+// main.rs
+
+mod mrkdwn;
+mod rig_agent;
+mod slack_api;
+
+use anyhow::{Context, Result};
+use dotenv::dotenv;
+use futures::{SinkExt, StreamExt};
+use mrkdwn::to_mrkdwn;
+use rig_agent::RigAgent;
+use serde::Deserialize;
+use serde_json::json;
+use slack_api::SlackClient;
+use std::env;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, error, info, warn};
+
+/// Wait before reconnecting after the connection drops unexpectedly
+const RECONNECT_DELAY: Duration = Duration::from_secs(5);
+
+/// A Socket Mode message. Only `events_api` envelopes carrying an
+/// `app_mention` event are acted on.
+#[derive(Deserialize)]
+struct Envelope {
+    #[serde(rename = "type")]
+    kind: String,
+    envelope_id: Option<String>,
+    payload: Option<EventPayload>,
+}
+
+#[derive(Deserialize)]
+struct EventPayload {
+    event: Option<Event>,
+}
+
+#[derive(Deserialize)]
+struct Event {
+    #[serde(rename = "type")]
+    kind: String,
+    text: Option<String>,
+    channel: Option<String>,
+    ts: Option<String>,
+    thread_ts: Option<String>,
+}
+
+/// An @-mention of the bot that should be answered
+struct Mention {
+    text: String,
+    channel: String,
+    thread_ts: String,
+}
+
+impl Event {
+    fn into_mention(self) -> Option<Mention> {
+        if self.kind != "app_mention" {
+            return None;
+        }
+        let channel = self.channel?;
+        // Answer in the thread the mention was in, or start one under it
+        let thread_ts = self.thread_ts.or(self.ts)?;
+        Some(Mention {
+            text: strip_mentions(&self.text.unwrap_or_default()),
+            channel,
+            thread_ts,
+        })
+    }
+}
+
+/// Remove `<@U123ABC>` user mentions from a message
+fn strip_mentions(text: &str) -> String {
+    let mut output = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("<@") {
+        output.push_str(&rest[..start]);
+        match rest[start..].find('>') {
+            Some(end) => rest = &rest[start + end + 1..],
+            None => {
+                rest = &rest[start..];
+                break;
+            }
+        }
+    }
+    output.push_str(rest);
+    output.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Post a placeholder in the thread, then replace it with the answer
+async fn answer(slack: &SlackClient, rig_agent: &RigAgent, mention: Mention) -> Result<()> {
+    let placeholder = slack
+        .post_message(&mention.channel, "Thinking...", Some(&mention.thread_ts))
+        .await?;
+
+    let response = match rig_agent.process_message(&mention.text).await {
+        Ok(response) => to_mrkdwn(&response),
+        Err(e) => {
+            error!("Error processing message: {:?}", e);
+            format!("Error processing request: {}", e)
+        }
+    };
+
+    slack.update_message(&placeholder, &response).await
+}
+
+/// Handle one Socket Mode connection until Slack closes it
+async fn run_connection(slack: &Arc<SlackClient>, rig_agent: &Arc<RigAgent>) -> Result<()> {
+    let url = slack.open_connection().await?;
+    let (mut socket, _) = tokio_tungstenite::connect_async(url.as_str())
+        .await
+        .context("Failed to connect to Slack")?;
+    info!("Connected to Slack");
+
+    while let Some(message) = socket.next().await {
+        let text = match message? {
+            Message::Text(text) => text,
+            Message::Ping(data) => {
+                socket.send(Message::Pong(data)).await?;
+                continue;
+            }
+            Message::Close(_) => break,
+            _ => continue,
+        };
+
+        let envelope: Envelope = match serde_json::from_str(&text) {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                warn!("Ignoring unrecognized message: {}", e);
+                continue;
+            }
+        };
+
+        // Slack redelivers any envelope not acknowledged within 3 seconds, so
+        // acknowledge first and answer in the background, the same way the
+        // Discord bot replies "Thinking..." before calling the model
+        if let Some(envelope_id) = &envelope.envelope_id {
+            socket
+                .send(Message::Text(json!({ "envelope_id": envelope_id }).to_string()))
+                .await?;
+        }
+
+        match envelope.kind.as_str() {
+            "events_api" => {
+                let Some(mention) = envelope
+                    .payload
+                    .and_then(|payload| payload.event)
+                    .and_then(Event::into_mention)
+                else {
+                    continue;
+                };
+                debug!("Mentioned in {}: {}", mention.channel, mention.text);
+
+                let slack = Arc::clone(slack);
+                let rig_agent = Arc::clone(rig_agent);
+                tokio::spawn(async move {
+                    if let Err(e) = answer(&slack, &rig_agent, mention).await {
+                        error!("Error answering mention: {:?}", e);
+                    }
+                });
+            }
+            // Slack asks clients to reconnect every few hours
+            "disconnect" => {
+                info!("Slack requested a reconnect");
+                break;
+            }
+            kind => debug!("Ignoring {} message", kind),
+        }
+    }
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .init();
+
+    let app_token = env::var("SLACK_APP_TOKEN").context("SLACK_APP_TOKEN not set")?;
+    let bot_token = env::var("SLACK_BOT_TOKEN").context("SLACK_BOT_TOKEN not set")?;
+    let slack = Arc::new(SlackClient::new(app_token, bot_token));
+
+    let rig_agent = Arc::new(RigAgent::new().await?);
+
+    loop {
+        match run_connection(&slack, &rig_agent).await {
+            Ok(()) => info!("Connection closed, reconnecting"),
+            Err(e) => {
+                error!("Connection error: {:?}; reconnecting in {:?}", e, RECONNECT_DELAY);
+                tokio::time::sleep(RECONNECT_DELAY).await;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_mentions() {
+        assert_eq!(strip_mentions("<@U0123ABC> what is   Rig?"), "what is Rig?");
+        assert_eq!(strip_mentions("hey <@U1> and <@U2>"), "hey and");
+        assert_eq!(strip_mentions("broken <@U1"), "broken <@U1");
+    }
+
+    #[test]
+    fn test_app_mention_envelope() {
+        let text = r#"{
+            "type": "events_api",
+            "envelope_id": "57d6a792-4d35-4d0b-b6aa-3361493e1caf",
+            "payload": {
+                "event": {
+                    "type": "app_mention",
+                    "user": "U061F7AUR",
+                    "text": "<@U0LAN0Z89> is Rig async?",
+                    "ts": "1515449522.000016",
+                    "channel": "C0LAN2Q65"
+                }
+            }
+        }"#;
+
+        let envelope: Envelope = serde_json::from_str(text).unwrap();
+        assert_eq!(envelope.kind, "events_api");
+        let mention = envelope.payload.unwrap().event.unwrap().into_mention().unwrap();
+        assert_eq!(mention.text, "is Rig async?");
+        assert_eq!(mention.channel, "C0LAN2Q65");
+        assert_eq!(mention.thread_ts, "1515449522.000016");
+    }
+}
@@ -0,0 +1,117 @@
+// mrkdwn.rs
+
+/// Convert the Markdown the model writes into Slack's mrkdwn dialect:
+///
+/// - `**bold**` and `__bold__` become `*bold*`
+/// - `# Heading` lines become bold lines
+/// - `[text](url)` becomes `<url|text>`
+/// - code fences lose their language tag, which Slack would print as code
+///
+/// Code blocks and inline code are copied unchanged.
+pub fn to_mrkdwn(markdown: &str) -> String {
+    let mut output = Vec::new();
+    let mut in_fence = false;
+
+    for line in markdown.lines() {
+        if line.trim_start().starts_with("```") {
+            in_fence = !in_fence;
+            output.push("```".to_string());
+            continue;
+        }
+        if in_fence {
+            output.push(line.to_string());
+            continue;
+        }
+
+        let trimmed = line.trim_start();
+        let heading = trimmed.trim_start_matches('#');
+        if heading.len() < trimmed.len() && heading.starts_with(' ') {
+            output.push(format!("*{}*", convert_inline(heading.trim())));
+        } else {
+            output.push(convert_inline(line));
+        }
+    }
+
+    output.join("\n")
+}
+
+/// Convert bold markers and links outside of inline code spans
+fn convert_inline(line: &str) -> String {
+    line.split('`')
+        .enumerate()
+        .map(|(i, part)| {
+            // Odd parts are inside backticks
+            if i % 2 == 1 {
+                part.to_string()
+            } else {
+                convert_links(&part.replace("**", "*").replace("__", "*"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("`")
+}
+
+fn convert_links(text: &str) -> String {
+    let mut output = String::new();
+    let mut rest = text;
+
+    while let Some(start) = rest.find('[') {
+        let Some(link) = parse_link(&rest[start..]) else {
+            output.push_str(&rest[..=start]);
+            rest = &rest[start + 1..];
+            continue;
+        };
+        output.push_str(&rest[..start]);
+        output.push_str(&format!("<{}|{}>", link.url, link.text));
+        rest = &rest[start + link.len..];
+    }
+    output.push_str(rest);
+
+    output
+}
+
+struct Link<'a> {
+    text: &'a str,
+    url: &'a str,
+    /// Length of the whole `[text](url)` in bytes
+    len: usize,
+}
+
+/// Parse a `[text](url)` at the start of `text`
+fn parse_link(text: &str) -> Option<Link<'_>> {
+    let text_end = text.find("](")?;
+    let url_end = text[text_end..].find(')')? + text_end;
+    let link_text = &text[1..text_end];
+    if link_text.contains('[') {
+        return None;
+    }
+    Some(Link {
+        text: link_text,
+        url: &text[text_end + 2..url_end],
+        len: url_end + 1,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_formatting() {
+        assert_eq!(
+            to_mrkdwn("**Rig** is on [GitHub](https://github.com/0xPlaygrounds/rig)."),
+            "*Rig* is on <https://github.com/0xPlaygrounds/rig|GitHub>."
+        );
+        assert_eq!(to_mrkdwn("## Getting started"), "*Getting started*");
+        assert_eq!(to_mrkdwn("Use `**kwargs` [here"), "Use `**kwargs` [here");
+    }
+
+    #[test]
+    fn test_code_blocks_are_untouched() {
+        let markdown = "Example:\n```rust\nlet x = **y;\n# not a heading\n```";
+        assert_eq!(
+            to_mrkdwn(markdown),
+            "Example:\n```\nlet x = **y;\n# not a heading\n```"
+        );
+    }
+}
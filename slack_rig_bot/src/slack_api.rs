@@ -0,0 +1,103 @@
+// slack_api.rs
+
+// The few Slack Web API calls the bot needs, made directly with reqwest.
+// Slack answers every call with HTTP 200 and reports failures through the
+// `ok` and `error` fields of the body.
+
+use anyhow::{bail, Result};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+const SLACK_API_URL: &str = "https://slack.com/api";
+
+#[derive(Deserialize)]
+struct SlackResponse<T> {
+    ok: bool,
+    error: Option<String>,
+    #[serde(flatten)]
+    data: Option<T>,
+}
+
+#[derive(Deserialize)]
+struct ConnectionInfo {
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct PostedMessage {
+    ts: String,
+}
+
+/// A message posted by the bot, which can be updated later
+#[derive(Debug, Clone, Serialize)]
+pub struct MessageRef {
+    pub channel: String,
+    pub ts: String,
+}
+
+pub struct SlackClient {
+    http_client: reqwest::Client,
+    /// App-level token (`xapp-...`), used to open Socket Mode connections
+    app_token: String,
+    /// Bot token (`xoxb-...`), used to post messages
+    bot_token: String,
+}
+
+impl SlackClient {
+    pub fn new(app_token: String, bot_token: String) -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            app_token,
+            bot_token,
+        }
+    }
+
+    /// Get a fresh WebSocket URL for Socket Mode. Each URL can only be used
+    /// once, so this is called again on every reconnect.
+    pub async fn open_connection(&self) -> Result<String> {
+        let info: ConnectionInfo = self
+            .call(&self.app_token, "apps.connections.open", json!({}))
+            .await?;
+        Ok(info.url)
+    }
+
+    /// Post `text` in `channel`, in the thread of `thread_ts` if given
+    pub async fn post_message(&self, channel: &str, text: &str, thread_ts: Option<&str>) -> Result<MessageRef> {
+        let body = json!({ "channel": channel, "text": text, "thread_ts": thread_ts });
+        let posted: PostedMessage = self.call(&self.bot_token, "chat.postMessage", body).await?;
+        Ok(MessageRef {
+            channel: channel.to_string(),
+            ts: posted.ts,
+        })
+    }
+
+    /// Replace the text of a message the bot posted earlier
+    pub async fn update_message(&self, message: &MessageRef, text: &str) -> Result<()> {
+        let body = json!({ "channel": message.channel, "ts": message.ts, "text": text });
+        let _: serde_json::Value = self.call(&self.bot_token, "chat.update", body).await?;
+        Ok(())
+    }
+
+    async fn call<T: DeserializeOwned>(&self, token: &str, method: &str, body: serde_json::Value) -> Result<T> {
+        let response: SlackResponse<T> = self
+            .http_client
+            .post(format!("{}/{}", SLACK_API_URL, method))
+            .bearer_auth(token)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        match response {
+            SlackResponse { ok: true, data: Some(data), .. } => Ok(data),
+            SlackResponse { error, .. } => bail!(
+                "Slack API call {} failed: {}",
+                method,
+                error.unwrap_or_else(|| "unknown error".to_string())
+            ),
+        }
+    }
+}
@@ -0,0 +1,79 @@
+mod sql_query_tool;
+
+use crate::sql_query_tool::SqlQueryTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+use rusqlite::Connection;
+use std::path::Path;
+
+/// Create a small example shop database so the example runs out of the box
+fn create_example_database(path: &Path) -> rusqlite::Result<()> {
+    let connection = Connection::open(path)?;
+    connection.execute_batch(
+        "CREATE TABLE customers (
+             id INTEGER PRIMARY KEY,
+             name TEXT NOT NULL,
+             country TEXT NOT NULL
+         );
+         CREATE TABLE orders (
+             id INTEGER PRIMARY KEY,
+             customer_id INTEGER NOT NULL REFERENCES customers(id),
+             product TEXT NOT NULL,
+             quantity INTEGER NOT NULL,
+             unit_price REAL NOT NULL,
+             ordered_at TEXT NOT NULL -- ISO 8601 date
+         );
+         INSERT INTO customers (id, name, country) VALUES
+             (1, 'Ada Lovelace', 'United Kingdom'),
+             (2, 'Grace Hopper', 'United States'),
+             (3, 'Alan Turing', 'United Kingdom'),
+             (4, 'Katherine Johnson', 'United States'),
+             (5, 'Hedy Lamarr', 'Austria');
+         INSERT INTO orders (customer_id, product, quantity, unit_price, ordered_at) VALUES
+             (1, 'Mechanical keyboard', 1, 129.00, '2024-07-02'),
+             (2, 'USB-C hub', 2, 39.50, '2024-07-15'),
+             (3, 'Mechanical keyboard', 1, 129.00, '2024-08-01'),
+             (1, 'Monitor arm', 1, 89.99, '2024-08-11'),
+             (4, 'USB-C hub', 3, 39.50, '2024-08-20'),
+             (5, 'Noise-cancelling headphones', 1, 249.00, '2024-09-03'),
+             (2, 'Monitor arm', 2, 89.99, '2024-09-18'),
+             (3, 'USB-C hub', 1, 39.50, '2024-09-30');",
+    )
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Use the database named by `SQLITE_DATABASE`, or create the example one
+    let database = std::env::var("SQLITE_DATABASE").unwrap_or_else(|_| "example.db".to_string());
+    if !Path::new(&database).exists() {
+        println!("Creating example database {}", database);
+        create_example_database(Path::new(&database))?;
+    }
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the SqlQueryTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a data analyst. Answer questions about the database by querying it with the sql_query tool. Never guess numbers you haven't queried.")
+        .tool(SqlQueryTool::open(&database)?)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "Which customer has spent the most, and on what?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
@@ -0,0 +1,240 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use rusqlite::types::ValueRef;
+use rusqlite::{Connection, OpenFlags};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+/// Most rows returned for a query; the rest are dropped and `truncated` is set
+const MAX_ROWS: usize = 50;
+
+#[derive(Deserialize)]
+pub struct SqlQueryArgs {
+    query: String,
+}
+
+// Anything returned here is sent back to the model as the tool's output, so
+// the messages say what went wrong in terms it can act on
+#[derive(Debug, thiserror::Error)]
+pub enum SqlQueryError {
+    #[error("Only a single SELECT statement is allowed")]
+    NotReadOnly,
+    #[error("SQL error: {0}")]
+    Sql(#[from] rusqlite::Error),
+    #[error("Query task failed: {0}")]
+    TaskFailed(String),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct QueryResult {
+    columns: Vec<String>,
+    rows: Vec<Vec<Value>>,
+    /// Whether rows beyond `MAX_ROWS` were left out
+    truncated: bool,
+}
+
+/// A tool that runs read-only SQL against a SQLite database. The schema is
+/// read once when the tool is created and included in its description so the
+/// model knows which tables and columns exist.
+pub struct SqlQueryTool {
+    path: PathBuf,
+    schema: String,
+}
+
+impl SqlQueryTool {
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self, SqlQueryError> {
+        let path = path.into();
+        let connection = open_read_only(&path)?;
+        let mut statement = connection.prepare(
+            "SELECT sql FROM sqlite_master
+             WHERE type IN ('table', 'view') AND sql IS NOT NULL AND name NOT LIKE 'sqlite_%'
+             ORDER BY name",
+        )?;
+        let schema = statement
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<Result<Vec<_>, _>>()?
+            .join(";\n");
+
+        Ok(Self { path, schema })
+    }
+}
+
+fn open_read_only(path: &PathBuf) -> Result<Connection, SqlQueryError> {
+    Ok(Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )?)
+}
+
+// Whether `query` has a `;` outside quoted strings and identifiers, i.e. more
+// than one statement. `prepare` would silently ignore everything after the
+// first one.
+fn has_separator(query: &str) -> bool {
+    let mut quote = None;
+    for c in query.chars() {
+        match (quote, c) {
+            (None, '\'' | '"' | '`') => quote = Some(c),
+            (None, '[') => quote = Some(']'),
+            (None, ';') => return true,
+            // A doubled quote inside a string closes and reopens it, which
+            // leaves it open as it should
+            (Some(close), _) if c == close => quote = None,
+            _ => {}
+        }
+    }
+    false
+}
+
+// A cheap first check: one statement, starting with SELECT or WITH. SQLite's
+// own read-only check on the prepared statement and the read-only connection
+// catch anything that slips through, such as `WITH ... DELETE`.
+fn is_select(query: &str) -> bool {
+    let query = query.trim().trim_end_matches(';');
+    let keyword = query
+        .split_whitespace()
+        .next()
+        .unwrap_or_default()
+        .to_uppercase();
+    !has_separator(query) && (keyword == "SELECT" || keyword == "WITH")
+}
+
+fn to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => json!(i),
+        ValueRef::Real(f) => json!(f),
+        ValueRef::Text(text) => json!(String::from_utf8_lossy(text)),
+        ValueRef::Blob(blob) => json!(format!("<{} bytes>", blob.len())),
+    }
+}
+
+fn run_query(connection: &Connection, query: &str) -> Result<QueryResult, SqlQueryError> {
+    if !is_select(query) {
+        return Err(SqlQueryError::NotReadOnly);
+    }
+
+    let mut statement = connection.prepare(query)?;
+    if !statement.readonly() {
+        return Err(SqlQueryError::NotReadOnly);
+    }
+
+    let columns: Vec<String> = statement.column_names().iter().map(|name| name.to_string()).collect();
+    let mut rows = Vec::new();
+    let mut truncated = false;
+
+    let mut results = statement.query([])?;
+    while let Some(row) = results.next()? {
+        if rows.len() == MAX_ROWS {
+            truncated = true;
+            break;
+        }
+        rows.push(
+            (0..columns.len())
+                .map(|i| row.get_ref(i).map(to_json))
+                .collect::<Result<_, _>>()?,
+        );
+    }
+
+    Ok(QueryResult {
+        columns,
+        rows,
+        truncated,
+    })
+}
+
+impl Tool for SqlQueryTool {
+    const NAME: &'static str = "sql_query";
+
+    type Args = SqlQueryArgs;
+    type Output = QueryResult;
+    type Error = SqlQueryError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "sql_query".to_string(),
+            description: format!(
+                "Run a single read-only SQLite SELECT query and return the column names and rows (at most {} rows). The database schema is:\n{}",
+                MAX_ROWS, self.schema
+            ),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "The SELECT statement to run (e.g., 'SELECT name FROM customers LIMIT 5')" },
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // rusqlite is synchronous, so keep it off the async runtime's threads
+        let path = self.path.clone();
+        tokio::task::spawn_blocking(move || run_query(&open_read_only(&path)?, &args.query))
+            .await
+            .map_err(|e| SqlQueryError::TaskFailed(e.to_string()))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_database() -> Connection {
+        let connection = Connection::open_in_memory().unwrap();
+        connection
+            .execute_batch(
+                "CREATE TABLE items (id INTEGER PRIMARY KEY, name TEXT, price REAL);
+                 WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n WHERE i < 60)
+                 INSERT INTO items (id, name, price) SELECT i, 'item ' || i, i * 1.5 FROM n;",
+            )
+            .unwrap();
+        connection
+    }
+
+    #[test]
+    fn test_select_caps_rows() {
+        let connection = test_database();
+
+        let result = run_query(&connection, "SELECT id, name, price FROM items WHERE id <= 2;").unwrap();
+        assert_eq!(
+            result,
+            QueryResult {
+                columns: vec!["id".into(), "name".into(), "price".into()],
+                rows: vec![
+                    vec![json!(1), json!("item 1"), json!(1.5)],
+                    vec![json!(2), json!("item 2"), json!(3.0)],
+                ],
+                truncated: false,
+            }
+        );
+
+        let result = run_query(&connection, "SELECT * FROM items").unwrap();
+        assert_eq!(result.rows.len(), MAX_ROWS);
+        assert!(result.truncated);
+    }
+
+    #[test]
+    fn test_rejects_writes() {
+        let connection = test_database();
+
+        for query in [
+            "DELETE FROM items",
+            "select 1; DROP TABLE items",
+            "SELECT 'a;b'; DROP TABLE items",
+            "WITH doomed AS (SELECT id FROM items) DELETE FROM items WHERE id IN doomed",
+        ] {
+            assert!(
+                matches!(run_query(&connection, query), Err(SqlQueryError::NotReadOnly)),
+                "{query} was not rejected"
+            );
+        }
+        assert_eq!(run_query(&connection, "SELECT COUNT(*) FROM items").unwrap().rows, vec![vec![json!(60)]]);
+
+        // Semicolons inside strings don't separate statements
+        assert_eq!(
+            run_query(&connection, "SELECT 'a;b', 'it''s; fine' FROM items WHERE id = 1;").unwrap().rows,
+            vec![vec![json!("a;b"), json!("it's; fine")]]
+        );
+    }
+}
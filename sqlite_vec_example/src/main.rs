@@ -0,0 +1,77 @@
+// main.rs
+//
+// The vector store lives in `rig_store.db` next to this file, so there is no
+// server to start. Run with `--reindex` to embed the documents again.
+
+mod sqlite_store;
+
+use anyhow::{Context, Result};
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::embeddings::EmbeddingsBuilder;
+use rig::providers::openai;
+use sqlite_store::SqliteStore;
+use std::time::Instant;
+
+const DB_PATH: &str = "rig_store.db";
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    // Initialize OpenAI client
+    let api_key = std::env::var("OPENAI_API_KEY").context("OPENAI_API_KEY not set")?;
+    let openai_client = openai::Client::new(&api_key);
+    let embedding_model = openai_client.embedding_model(openai::TEXT_EMBEDDING_3_SMALL);
+
+    let reindex = std::env::args().any(|arg| arg == "--reindex");
+
+    let started = Instant::now();
+    let store = SqliteStore::open(DB_PATH)?;
+
+    // The store keeps the embeddings across runs, so only embed the documents
+    // the first time, or when asked to or the embedding model changed
+    match store.index_info()? {
+        Some(info) if !reindex && info.model == openai::TEXT_EMBEDDING_3_SMALL => {
+            println!(
+                "Loaded {} documents from {} in {:?} (embedding them took {:?})",
+                info.documents,
+                DB_PATH,
+                started.elapsed(),
+                info.embedding_time
+            );
+        }
+        _ => {
+            let embedding_started = Instant::now();
+            let embeddings = EmbeddingsBuilder::new(embedding_model.clone())
+                .simple_document("doc0", "Definition of a *flurbo*: A flurbo is a green alien that lives on cold planets")
+                .simple_document("doc1", "Definition of a *glarb-glarb*: A glarb-glarb is an ancient tool used by the ancestors of the inhabitants of planet Jiro to farm the land.")
+                .simple_document("doc2", "Definition of a *linglingdong*: A term used by inhabitants of the far side of the moon to describe humans.")
+                .build()
+                .await?;
+            let embedding_time = embedding_started.elapsed();
+
+            store.rebuild(openai::TEXT_EMBEDDING_3_SMALL, &embeddings, embedding_time)?;
+            println!(
+                "Embedded {} documents in {:?} and stored them in {} ({:?} in total)",
+                embeddings.len(),
+                embedding_time,
+                DB_PATH,
+                started.elapsed()
+            );
+        }
+    }
+
+    // The SQLite index is used exactly like the in-memory one
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a dictionary assistant here to assist the user in understanding the meaning of words. Use the definitions provided as context.")
+        .dynamic_context(1, store.index(embedding_model))
+        .build();
+
+    let response = agent.prompt("What does \"glarb-glarb\" mean?").await?;
+
+    println!("{}", response);
+
+    Ok(())
+}
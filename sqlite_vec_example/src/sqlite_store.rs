@@ -0,0 +1,291 @@
+// sqlite_store.rs
+
+// An embedded vector store for rig, kept in a single SQLite file. The
+// sqlite-vec extension is compiled into the binary and provides a `vec0`
+// virtual table that runs the nearest-neighbour search inside SQLite, so
+// there is no server to run. `SqliteIndex` implements `VectorStoreIndex` so it
+// can be passed to `dynamic_context` just like the in-memory index.
+
+use rig::embeddings::{DocumentEmbeddings, Embedding, EmbeddingModel};
+use rig::vector_store::{VectorStoreError, VectorStoreIndex};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+use std::path::Path;
+use std::sync::{Arc, Mutex, Once};
+use std::time::Duration;
+
+#[derive(Debug, thiserror::Error)]
+pub enum SqliteStoreError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("Invalid stored document: {0}")]
+    InvalidDocument(#[from] serde_json::Error),
+    #[error("No embeddings to store")]
+    NoEmbeddings,
+}
+
+impl From<SqliteStoreError> for VectorStoreError {
+    fn from(e: SqliteStoreError) -> Self {
+        VectorStoreError::DatastoreError(Box::new(e))
+    }
+}
+
+/// What a previous run stored, read back at startup
+#[derive(Debug, PartialEq)]
+pub struct IndexInfo {
+    /// The embedding model the documents were embedded with. Queries must use
+    /// the same one, so a different model means re-indexing.
+    pub model: String,
+    pub documents: usize,
+    /// How long embedding the documents took when they were indexed
+    pub embedding_time: Duration,
+}
+
+/// Register sqlite-vec with every connection opened from now on
+fn load_sqlite_vec() {
+    static LOAD: Once = Once::new();
+    LOAD.call_once(|| unsafe {
+        // The documented way to load the statically linked extension: the init
+        // function has the signature SQLite expects but is declared without
+        // arguments by the sqlite-vec crate
+        rusqlite::ffi::sqlite3_auto_extension(Some(std::mem::transmute::<
+            *const (),
+            unsafe extern "C" fn(
+                *mut rusqlite::ffi::sqlite3,
+                *mut *mut std::os::raw::c_char,
+                *const rusqlite::ffi::sqlite3_api_routines,
+            ) -> std::os::raw::c_int,
+        >(sqlite_vec::sqlite3_vec_init as *const ())));
+    });
+}
+
+/// sqlite-vec takes vectors as little-endian `f32` blobs
+fn vector_blob(vector: &[f64]) -> Vec<u8> {
+    vector.iter().flat_map(|x| (*x as f32).to_le_bytes()).collect()
+}
+
+/// A handle on the store's SQLite database
+#[derive(Clone)]
+pub struct SqliteStore {
+    conn: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    /// Open the store at `path`, creating the file if it doesn't exist
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SqliteStoreError> {
+        load_sqlite_vec();
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS meta (key TEXT PRIMARY KEY, value TEXT NOT NULL);",
+        )?;
+        Ok(Self {
+            conn: Arc::new(Mutex::new(conn)),
+        })
+    }
+
+    /// What is currently stored, or `None` if nothing has been indexed yet
+    pub fn index_info(&self) -> Result<Option<IndexInfo>, SqliteStoreError> {
+        let conn = self.conn.lock().expect("store lock poisoned");
+        let meta = |key: &str| -> Result<Option<String>, rusqlite::Error> {
+            conn.query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| row.get(0))
+                .optional()
+        };
+
+        let (Some(model), Some(embedding_ms)) = (meta("model")?, meta("embedding_ms")?) else {
+            return Ok(None);
+        };
+        let documents: usize = conn.query_row("SELECT count(*) FROM documents", [], |row| row.get(0))?;
+
+        Ok(Some(IndexInfo {
+            model,
+            documents,
+            embedding_time: Duration::from_millis(embedding_ms.parse().unwrap_or_default()),
+        }))
+    }
+
+    /// Replace everything in the store with `documents`, embedded with `model`
+    /// in `embedding_time`. Runs in one transaction, so an interrupted run
+    /// leaves the previous index in place.
+    pub fn rebuild(
+        &self,
+        model: &str,
+        documents: &[DocumentEmbeddings],
+        embedding_time: Duration,
+    ) -> Result<(), SqliteStoreError> {
+        let dimensions = documents
+            .iter()
+            .flat_map(|doc| doc.embeddings.first())
+            .map(|embedding| embedding.vec.len())
+            .next()
+            .ok_or(SqliteStoreError::NoEmbeddings)?;
+
+        let mut conn = self.conn.lock().expect("store lock poisoned");
+        let tx = conn.transaction()?;
+
+        // The vector table is sized for one model's embeddings, so it is
+        // recreated rather than emptied
+        tx.execute_batch(&format!(
+            "DROP TABLE IF EXISTS embeddings;
+             DROP TABLE IF EXISTS chunks;
+             DROP TABLE IF EXISTS documents;
+             CREATE TABLE documents (id TEXT PRIMARY KEY, document TEXT NOT NULL);
+             CREATE TABLE chunks (id INTEGER PRIMARY KEY, doc_id TEXT NOT NULL REFERENCES documents(id));
+             CREATE VIRTUAL TABLE embeddings USING vec0(embedding float[{}] distance_metric=cosine);",
+            dimensions
+        ))?;
+
+        for doc in documents {
+            tx.execute(
+                "INSERT INTO documents (id, document) VALUES (?1, ?2)",
+                params![doc.id, serde_json::to_string(&doc.document)?],
+            )?;
+            // A document may have several embeddings, one per chunk
+            for embedding in &doc.embeddings {
+                tx.execute("INSERT INTO chunks (doc_id) VALUES (?1)", [&doc.id])?;
+                tx.execute(
+                    "INSERT INTO embeddings (rowid, embedding) VALUES (?1, ?2)",
+                    params![tx.last_insert_rowid(), vector_blob(&embedding.vec)],
+                )?;
+            }
+        }
+
+        tx.execute(
+            "INSERT OR REPLACE INTO meta (key, value) VALUES ('model', ?1), ('embedding_ms', ?2)",
+            params![model, embedding_time.as_millis().to_string()],
+        )?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    /// An index over the store that embeds queries with `model`
+    pub fn index<M: EmbeddingModel>(&self, model: M) -> SqliteIndex<M> {
+        SqliteIndex {
+            store: self.clone(),
+            model,
+        }
+    }
+
+    /// The `n` documents closest to `vector` with their cosine similarity,
+    /// most similar first
+    fn search(&self, vector: &[f64], n: usize) -> Result<Vec<(f64, String, Value)>, SqliteStoreError> {
+        let conn = self.conn.lock().expect("store lock poisoned");
+        let mut statement = conn.prepare(
+            "SELECT chunks.doc_id, documents.document, knn.distance
+             FROM (SELECT rowid, distance FROM embeddings WHERE embedding MATCH ?1 AND k = ?2) AS knn
+             JOIN chunks ON chunks.id = knn.rowid
+             JOIN documents ON documents.id = chunks.doc_id
+             ORDER BY knn.distance",
+        )?;
+        let rows = statement.query_map(params![vector_blob(vector), n as i64], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, f64>(2)?,
+            ))
+        })?;
+
+        // Several chunks of the same document may match; keep the closest
+        let mut results: Vec<(f64, String, Value)> = Vec::new();
+        for row in rows {
+            let (id, document, distance) = row?;
+            if results.iter().any(|(_, existing, _)| *existing == id) {
+                continue;
+            }
+            results.push((1.0 - distance, id, serde_json::from_str(&document)?));
+        }
+        Ok(results)
+    }
+}
+
+pub struct SqliteIndex<M: EmbeddingModel> {
+    store: SqliteStore,
+    model: M,
+}
+
+impl<M: EmbeddingModel> VectorStoreIndex for SqliteIndex<M> {
+    async fn top_n_from_query(
+        &self,
+        query: &str,
+        n: usize,
+    ) -> Result<Vec<(f64, DocumentEmbeddings)>, VectorStoreError> {
+        let prompt_embedding = self.model.embed_document(query).await?;
+        self.top_n_from_embedding(&prompt_embedding, n).await
+    }
+
+    /// Returns the cosine similarity with each document. The embeddings
+    /// themselves are not read back.
+    async fn top_n_from_embedding(
+        &self,
+        prompt_embedding: &Embedding,
+        n: usize,
+    ) -> Result<Vec<(f64, DocumentEmbeddings)>, VectorStoreError> {
+        let results = self.store.search(&prompt_embedding.vec, n)?;
+        Ok(results
+            .into_iter()
+            .map(|(score, id, document)| {
+                (
+                    score,
+                    DocumentEmbeddings {
+                        id,
+                        document,
+                        embeddings: vec![],
+                    },
+                )
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(id: &str, vec: Vec<f64>) -> DocumentEmbeddings {
+        DocumentEmbeddings {
+            id: id.to_string(),
+            document: Value::String(format!("Definition of {}", id)),
+            embeddings: vec![Embedding {
+                document: id.to_string(),
+                vec,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_rebuild_and_index_info() {
+        let store = SqliteStore::open(":memory:").unwrap();
+        assert_eq!(store.index_info().unwrap(), None);
+
+        let documents = [document("doc0", vec![1.0, 0.0]), document("doc1", vec![0.0, 1.0])];
+        store.rebuild("test-model", &documents, Duration::from_millis(1500)).unwrap();
+        // Rebuilding replaces the previous index instead of adding to it
+        store.rebuild("test-model", &documents, Duration::from_millis(1500)).unwrap();
+
+        assert_eq!(
+            store.index_info().unwrap(),
+            Some(IndexInfo {
+                model: "test-model".into(),
+                documents: 2,
+                embedding_time: Duration::from_millis(1500),
+            })
+        );
+    }
+
+    #[test]
+    fn test_search_returns_closest_documents_first() {
+        let store = SqliteStore::open(":memory:").unwrap();
+        let documents = [
+            document("doc0", vec![1.0, 0.0, 0.0]),
+            document("doc1", vec![0.0, 1.0, 0.0]),
+            document("doc2", vec![0.6, 0.8, 0.0]),
+        ];
+        store.rebuild("test-model", &documents, Duration::ZERO).unwrap();
+
+        let results = store.search(&[0.0, 1.0, 0.0], 2).unwrap();
+        let ids: Vec<&str> = results.iter().map(|(_, id, _)| id.as_str()).collect();
+        assert_eq!(ids, ["doc1", "doc2"]);
+        assert!((results[0].0 - 1.0).abs() < 1e-6);
+        assert!((results[1].0 - 0.8).abs() < 1e-6);
+        assert_eq!(results[0].2, Value::String("Definition of doc1".into()));
+    }
+}
@@ -0,0 +1,35 @@
+mod stock_quote_tool;
+
+use crate::stock_quote_tool::StockQuoteTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the StockQuoteTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a stock market assistant. Use the get_stock_quote tool to look up current stock prices.")
+        .tool(StockQuoteTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "How is Apple stock doing today?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
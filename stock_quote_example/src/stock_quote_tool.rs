@@ -0,0 +1,183 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::env;
+use std::time::Duration;
+
+const FINNHUB_QUOTE_URL: &str = "https://finnhub.io/api/v1/quote";
+
+// Maximum time to wait for Finnhub before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+pub struct StockQuoteArgs {
+    symbol: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StockQuoteError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Missing API key: set the FINNHUB_API_KEY environment variable")]
+    MissingApiKey,
+    #[error("Unknown ticker '{0}': use the symbol the stock trades under on a US exchange, e.g. 'AAPL'")]
+    UnknownTicker(String),
+    #[error("Finnhub rate limit reached; wait a minute before asking for more quotes")]
+    RateLimited,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct StockQuote {
+    symbol: String,
+    price: f64,
+    change: f64,
+    percent_change: f64,
+    day_low: f64,
+    day_high: f64,
+    previous_close: f64,
+}
+
+// Finnhub's quote response, with its one-letter field names
+#[derive(Deserialize)]
+struct FinnhubQuote {
+    c: f64,
+    d: Option<f64>,
+    dp: Option<f64>,
+    h: f64,
+    l: f64,
+    pc: f64,
+}
+
+pub struct StockQuoteTool;
+
+// Turn the raw response body into a quote for `symbol`
+fn parse_quote(text: &str, symbol: &str) -> Result<StockQuote, StockQuoteError> {
+    let quote: FinnhubQuote =
+        serde_json::from_str(text).map_err(|e| StockQuoteError::InvalidResponse(e.to_string()))?;
+
+    // Finnhub answers unknown symbols with zeros and no change figures
+    let (Some(change), Some(percent_change)) = (quote.d, quote.dp) else {
+        return Err(StockQuoteError::UnknownTicker(symbol.to_string()));
+    };
+
+    Ok(StockQuote {
+        symbol: symbol.to_string(),
+        price: quote.c,
+        change,
+        percent_change,
+        day_low: quote.l,
+        day_high: quote.h,
+        previous_close: quote.pc,
+    })
+}
+
+impl From<BodyError> for StockQuoteError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => StockQuoteError::TooLarge,
+            BodyError::Read(e) => StockQuoteError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for StockQuoteTool {
+    const NAME: &'static str = "get_stock_quote";
+
+    type Args = StockQuoteArgs;
+    type Output = StockQuote;
+    type Error = StockQuoteError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "get_stock_quote".to_string(),
+            description: "Get the latest price of a stock, its change since the previous close and today's trading range, in USD".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "symbol": { "type": "string", "description": "The stock's ticker symbol (e.g., 'AAPL', 'MSFT')" },
+                },
+                "required": ["symbol"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // Use the Finnhub API key from an environment variable
+        let api_key = env::var("FINNHUB_API_KEY").map_err(|_| StockQuoteError::MissingApiKey)?;
+
+        let symbol = args.symbol.trim().to_uppercase();
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| StockQuoteError::HttpRequestFailed(e.to_string()))?;
+
+        let response = client
+            .get(FINNHUB_QUOTE_URL)
+            .header("X-Finnhub-Token", api_key)
+            .query(&[("symbol", symbol.as_str())])
+            .send()
+            .await
+            .map_err(|e| StockQuoteError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(StockQuoteError::RateLimited);
+        }
+
+        let text = read_body(response).await?;
+
+        if !status.is_success() {
+            return Err(StockQuoteError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        parse_quote(&text, &symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_quote() {
+        let text = r#"{"c":227.52,"d":-1.33,"dp":-0.5811,"h":229.74,"l":226.41,"o":228.6,"pc":228.85,"t":1728331200}"#;
+        assert_eq!(
+            parse_quote(text, "AAPL").unwrap(),
+            StockQuote {
+                symbol: "AAPL".into(),
+                price: 227.52,
+                change: -1.33,
+                percent_change: -0.5811,
+                day_low: 226.41,
+                day_high: 229.74,
+                previous_close: 228.85,
+            }
+        );
+    }
+
+    #[test]
+    fn test_unknown_ticker() {
+        let text = r#"{"c":0,"d":null,"dp":null,"h":0,"l":0,"o":0,"pc":0,"t":0}"#;
+        assert!(matches!(
+            parse_quote(text, "NOTREAL"),
+            Err(StockQuoteError::UnknownTicker(_))
+        ));
+        assert!(matches!(
+            parse_quote("not json", "AAPL"),
+            Err(StockQuoteError::InvalidResponse(_))
+        ));
+    }
+}
@@ -0,0 +1,216 @@
+// inline_query.rs
+
+use crate::message_utils::{chunk_message, TELEGRAM_MESSAGE_LIMIT};
+use crate::rig_agent::RigAgent;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::prelude::*;
+use teloxide::types::{
+    InlineQuery, InlineQueryResult, InlineQueryResultArticle, InlineQueryResultsButton,
+    InlineQueryResultsButtonKind, InputMessageContent, InputMessageContentText,
+};
+use tokio::sync::Mutex;
+use tokio::time::Instant;
+use tracing::{debug, error, warn};
+
+/// Telegram sends a new inline query with every few characters typed. A query
+/// is only answered once no newer one from the same user has arrived for this
+/// long, so a question isn't answered once per keystroke.
+const DEBOUNCE: Duration = Duration::from_millis(800);
+
+/// Telegram rejects answers to an inline query after roughly ten seconds, so
+/// the agent gets this long from when the query arrives. A slower answer is
+/// replaced with a button to ask in the private chat instead.
+const ANSWER_TIMEOUT: Duration = Duration::from_secs(8);
+
+/// Seconds Telegram may serve an answer to anyone sending the same query text
+/// without asking the bot again. Answers don't depend on who asked, so caching
+/// them saves a completion for repeated questions.
+const CACHE_TIME: u32 = 300;
+
+/// Sent with `/start` when the "ask in a private chat" button is pressed
+const START_PARAMETER: &str = "inline";
+
+/// Characters of the answer previewed under the result's title
+const PREVIEW_LENGTH: usize = 200;
+
+/// Appended to an answer shortened to fit in one message
+const SHORTENED_MARKER: &str = "\n\n… (shortened; ask me in a private chat for the full answer)";
+
+/// The latest inline query of each user, so superseded ones can be dropped
+#[derive(Default)]
+pub struct InlineQueries {
+    latest: Mutex<HashMap<UserId, String>>,
+}
+
+impl InlineQueries {
+    /// Record `query_id` as the latest query from `user`
+    async fn start(&self, user: UserId, query_id: &str) {
+        self.latest.lock().await.insert(user, query_id.to_string());
+    }
+
+    /// Whether `query_id` is still the latest query from `user`. Forgets it if
+    /// so, as it is about to be answered.
+    async fn take_if_latest(&self, user: UserId, query_id: &str) -> bool {
+        let mut latest = self.latest.lock().await;
+        if latest.get(&user).is_some_and(|latest| latest == query_id) {
+            latest.remove(&user);
+            true
+        } else {
+            false
+        }
+    }
+}
+
+/// Id of the result answering `question`. Derived from the question so the
+/// same question always gets the same id, matching what Telegram caches.
+fn result_id(question: &str) -> String {
+    let mut hasher = DefaultHasher::new();
+    question.hash(&mut hasher);
+    format!("answer-{:016x}", hasher.finish())
+}
+
+/// The message sent to the chat when the result is picked: the question, then
+/// the answer, shortened to fit in a single message
+fn inline_message(question: &str, answer: &str) -> String {
+    let text = format!("{}\n\n{}", question, answer.trim());
+    let chunks = chunk_message(&text, TELEGRAM_MESSAGE_LIMIT);
+    if chunks.len() <= 1 {
+        return text;
+    }
+    let mut shortened = chunk_message(&text, TELEGRAM_MESSAGE_LIMIT - SHORTENED_MARKER.len())
+        .into_iter()
+        .next()
+        .unwrap_or_default();
+    shortened.push_str(SHORTENED_MARKER);
+    shortened
+}
+
+/// The start of `answer` on a single line, shown under the result's title
+fn preview(answer: &str) -> String {
+    let line = answer.split_whitespace().collect::<Vec<_>>().join(" ");
+    if line.chars().count() <= PREVIEW_LENGTH {
+        return line;
+    }
+    let mut preview: String = line.chars().take(PREVIEW_LENGTH - 1).collect();
+    preview.push('…');
+    preview
+}
+
+fn private_chat_button(text: &str) -> InlineQueryResultsButton {
+    InlineQueryResultsButton {
+        text: text.to_string(),
+        kind: InlineQueryResultsButtonKind::StartParameter(START_PARAMETER.to_string()),
+    }
+}
+
+/// Answer `@bot question` typed in any chat with a single result holding the
+/// agent's answer, which the user can send to that chat
+pub async fn handle_inline_query(
+    bot: Bot,
+    query: InlineQuery,
+    rig_agent: Arc<RigAgent>,
+    queries: Arc<InlineQueries>,
+) -> ResponseResult<()> {
+    let received = Instant::now();
+    let question = query.query.trim();
+    let user = query.from.id;
+
+    // Nothing typed yet: offer the private chat instead of leaving a spinner
+    if question.is_empty() {
+        bot.answer_inline_query(&query.id, Vec::<InlineQueryResult>::new())
+            .button(private_chat_button("Type a question, or chat with me privately"))
+            .cache_time(CACHE_TIME)
+            .await?;
+        return Ok(());
+    }
+
+    queries.start(user, &query.id).await;
+    tokio::time::sleep(DEBOUNCE).await;
+    if !queries.take_if_latest(user, &query.id).await {
+        debug!("Skipping superseded inline query {:?}", question);
+        return Ok(());
+    }
+    debug!("Answering inline query from {}: {}", user, question);
+
+    let answer =
+        tokio::time::timeout_at(received + ANSWER_TIMEOUT, rig_agent.process_message(question)).await;
+
+    let request = match answer {
+        Ok(Ok(answer)) => {
+            let article = InlineQueryResultArticle::new(
+                result_id(question),
+                question,
+                InputMessageContent::Text(InputMessageContentText::new(inline_message(question, &answer))),
+            )
+            .description(preview(&answer));
+            bot.answer_inline_query(&query.id, vec![InlineQueryResult::Article(article)])
+                .cache_time(CACHE_TIME)
+        }
+        // Not cached, so the same question can be tried again
+        Ok(Err(e)) => {
+            error!("Error processing inline query: {:?}", e);
+            bot.answer_inline_query(&query.id, Vec::<InlineQueryResult>::new())
+                .button(private_chat_button("Something went wrong. Ask me privately"))
+                .cache_time(0)
+        }
+        Err(_) => {
+            warn!("Inline answer took longer than {:?}: {}", ANSWER_TIMEOUT, question);
+            bot.answer_inline_query(&query.id, Vec::<InlineQueryResult>::new())
+                .button(private_chat_button("This needs longer. Ask me privately"))
+                .cache_time(0)
+        }
+    };
+
+    // The query expires if answering took too long despite the timeout; the
+    // user has moved on by then, so there is nobody to tell
+    if let Err(e) = request.await {
+        warn!("Could not answer inline query: {:?}", e);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_inline_message() {
+        assert_eq!(
+            inline_message("What is Rig?", "A Rust library.\n"),
+            "What is Rig?\n\nA Rust library."
+        );
+
+        let long_answer = "Rig makes LLM apps easy.\n\n".repeat(300);
+        let message = inline_message("Tell me about Rig", &long_answer);
+        assert!(message.len() <= TELEGRAM_MESSAGE_LIMIT);
+        assert!(message.starts_with("Tell me about Rig\n\n"));
+        assert!(message.ends_with(SHORTENED_MARKER));
+
+        assert_eq!(preview("Rig is\n\na  Rust library."), "Rig is a Rust library.");
+        assert_eq!(preview(&long_answer).chars().count(), PREVIEW_LENGTH);
+
+        assert_eq!(result_id("What is Rig?"), result_id("What is Rig?"));
+        assert_ne!(result_id("What is Rig?"), result_id("What is Rag?"));
+        assert!(result_id("What is Rig?").len() <= 64);
+    }
+
+    #[tokio::test]
+    async fn test_only_the_latest_query_is_answered() {
+        let queries = InlineQueries::default();
+        let (alice, bob) = (UserId(1), UserId(2));
+
+        queries.start(alice, "q1").await;
+        queries.start(bob, "q2").await;
+        queries.start(alice, "q3").await;
+
+        assert!(!queries.take_if_latest(alice, "q1").await);
+        assert!(queries.take_if_latest(bob, "q2").await);
+        assert!(queries.take_if_latest(alice, "q3").await);
+        // Already answered
+        assert!(!queries.take_if_latest(alice, "q3").await);
+    }
+}
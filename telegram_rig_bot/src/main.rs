@@ -0,0 +1,94 @@
+// main.rs
+
+mod inline_query;
+mod message_utils;
+mod rig_agent;
+
+use anyhow::Result;
+use dotenv::dotenv;
+use inline_query::{handle_inline_query, InlineQueries};
+use message_utils::{chunk_message, TELEGRAM_MESSAGE_LIMIT};
+use rig_agent::RigAgent;
+use std::sync::Arc;
+use std::time::Duration;
+use teloxide::prelude::*;
+use teloxide::types::ChatAction;
+use tracing::{debug, error};
+
+/// Telegram clears the "typing" indicator after about five seconds, so it is
+/// sent again at this interval until the answer is ready
+const TYPING_INTERVAL: Duration = Duration::from_secs(4);
+
+const GREETING: &str = "Hello! I'm your helpful Rust and Rig-powered assistant. Ask me anything about Rig.";
+
+async fn handle_message(bot: Bot, msg: Message, rig_agent: Arc<RigAgent>) -> ResponseResult<()> {
+    let Some(text) = msg.text() else {
+        return Ok(());
+    };
+    debug!("Received message in chat {}: {}", msg.chat.id, text);
+
+    if text.starts_with("/start") {
+        bot.send_message(msg.chat.id, GREETING).await?;
+        return Ok(());
+    }
+
+    // Keep the typing indicator up while the agent works
+    let typing = tokio::spawn({
+        let bot = bot.clone();
+        let chat_id = msg.chat.id;
+        async move {
+            loop {
+                let _ = bot.send_chat_action(chat_id, ChatAction::Typing).await;
+                tokio::time::sleep(TYPING_INTERVAL).await;
+            }
+        }
+    });
+
+    let response = rig_agent.process_message(text).await;
+    typing.abort();
+
+    let response = match response {
+        Ok(response) => response,
+        Err(e) => {
+            error!("Error processing message: {:?}", e);
+            format!("Error processing request: {:?}", e)
+        }
+    };
+
+    for chunk in chunk_message(&response, TELEGRAM_MESSAGE_LIMIT) {
+        bot.send_message(msg.chat.id, chunk).await?;
+    }
+
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .init();
+
+    // Reads TELOXIDE_TOKEN
+    let bot = Bot::from_env();
+
+    let rig_agent = Arc::new(RigAgent::new().await?);
+
+    let inline_queries = Arc::new(InlineQueries::default());
+
+    // Inline queries (`@bot question` typed in any chat) only arrive once
+    // inline mode is enabled with BotFather's /setinline
+    let handler = dptree::entry()
+        .branch(Update::filter_message().endpoint(handle_message))
+        .branch(Update::filter_inline_query().endpoint(handle_inline_query));
+
+    Dispatcher::builder(bot, handler)
+        .dependencies(dptree::deps![rig_agent, inline_queries])
+        .enable_ctrlc_handler()
+        .build()
+        .dispatch()
+        .await;
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+// message_utils.rs
+
+/// Maximum size of a single Telegram message. The hard limit is 4096
+/// characters; chunks are measured in bytes, so this is always within it.
+pub const TELEGRAM_MESSAGE_LIMIT: usize = 4096;
+
+/// Split `text` into pieces of at most `limit` bytes that can be sent as
+/// sequential Telegram messages.
+///
+/// Pieces are cut on paragraph boundaries whenever possible, then on line
+/// boundaries, and only cut inside a line when a single line is too long.
+pub fn chunk_message(text: &str, limit: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n").filter(|p| !p.trim().is_empty()) {
+        for line in paragraph_pieces(paragraph, limit) {
+            if !current.is_empty() && current.len() + 2 + line.len() > limit {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(&line);
+        }
+    }
+
+    if !current.trim().is_empty() {
+        chunks.push(current);
+    }
+
+    chunks
+}
+
+/// Split a paragraph that does not fit in `limit` on line boundaries
+fn paragraph_pieces(paragraph: &str, limit: usize) -> Vec<String> {
+    if paragraph.len() <= limit {
+        return vec![paragraph.to_string()];
+    }
+
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+
+    for segment in paragraph.lines().flat_map(|line| split_line(line, limit)) {
+        if !current.is_empty() && current.len() + 1 + segment.len() > limit {
+            pieces.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push('\n');
+        }
+        current.push_str(segment);
+    }
+
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+
+    pieces
+}
+
+/// Split a single line into segments of at most `limit` bytes without cutting
+/// through a multi-byte character.
+fn split_line(line: &str, limit: usize) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut rest = line;
+
+    while rest.len() > limit {
+        let mut end = limit;
+        while !rest.is_char_boundary(end) {
+            end -= 1;
+        }
+        if end == 0 {
+            // A single character wider than the limit; emit it whole.
+            end = rest.chars().next().map_or(rest.len(), char::len_utf8);
+        }
+        segments.push(&rest[..end]);
+        rest = &rest[end..];
+    }
+    segments.push(rest);
+
+    segments
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_message_is_single_chunk() {
+        let chunks = chunk_message("Hello, Rig!", TELEGRAM_MESSAGE_LIMIT);
+        assert_eq!(chunks, vec!["Hello, Rig!".to_string()]);
+    }
+
+    #[test]
+    fn test_long_message_respects_limit() {
+        let paragraph = "word ".repeat(500);
+        let text = format!("{}\n\n{}\n\n{}", paragraph, "é".repeat(3000), paragraph);
+        let chunks = chunk_message(&text, TELEGRAM_MESSAGE_LIMIT);
+
+        assert!(chunks.len() > 1);
+        for chunk in &chunks {
+            assert!(chunk.len() <= TELEGRAM_MESSAGE_LIMIT);
+        }
+        assert_eq!(chunks.concat().matches('é').count(), 3000);
+    }
+}
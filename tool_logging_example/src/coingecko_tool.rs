@@ -0,0 +1,198 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::time::Duration;
+
+const SIMPLE_PRICE_URL: &str = "https://api.coingecko.com/api/v3/simple/price";
+
+// Maximum time to wait for CoinGecko before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// CoinGecko identifies coins by id rather than ticker; these are the tickers
+// people ask about most. Anything else is passed through as an id.
+const TICKER_IDS: &[(&str, &str)] = &[
+    ("btc", "bitcoin"),
+    ("eth", "ethereum"),
+    ("usdt", "tether"),
+    ("usdc", "usd-coin"),
+    ("bnb", "binancecoin"),
+    ("sol", "solana"),
+    ("xrp", "ripple"),
+    ("doge", "dogecoin"),
+    ("ada", "cardano"),
+    ("trx", "tron"),
+    ("avax", "avalanche-2"),
+    ("dot", "polkadot"),
+    ("link", "chainlink"),
+    ("ltc", "litecoin"),
+    ("matic", "matic-network"),
+    ("atom", "cosmos"),
+    ("hype", "hyperliquid"),
+];
+
+#[derive(Deserialize)]
+pub struct CoinGeckoArgs {
+    coin: String,
+    currency: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CoinGeckoError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Unknown coin '{0}': use a CoinGecko coin id such as 'bitcoin' or a common ticker such as 'btc'")]
+    UnknownCoin(String),
+    #[error("No {currency} price available for '{coin}'")]
+    UnsupportedCurrency { coin: String, currency: String },
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct CoinPrice {
+    coin_id: String,
+    currency: String,
+    price: f64,
+}
+
+pub struct CoinGeckoTool;
+
+// Map a ticker or name to a CoinGecko id, e.g. "BTC" -> "bitcoin"
+fn coin_id(coin: &str) -> String {
+    let coin = coin.trim().to_lowercase();
+    TICKER_IDS
+        .iter()
+        .find(|(ticker, _)| *ticker == coin)
+        .map_or(coin, |(_, id)| id.to_string())
+}
+
+// Pull the price out of a `{ "<id>": { "<currency>": <price> } }` response
+fn parse_price(text: &str, coin_id: &str, currency: &str) -> Result<CoinPrice, CoinGeckoError> {
+    let prices: HashMap<String, HashMap<String, f64>> =
+        serde_json::from_str(text).map_err(|e| CoinGeckoError::InvalidResponse(e.to_string()))?;
+
+    // CoinGecko answers unknown ids with an empty object rather than an error
+    let coin_prices = prices
+        .get(coin_id)
+        .ok_or_else(|| CoinGeckoError::UnknownCoin(coin_id.to_string()))?;
+    let price = coin_prices
+        .get(currency)
+        .ok_or_else(|| CoinGeckoError::UnsupportedCurrency {
+            coin: coin_id.to_string(),
+            currency: currency.to_uppercase(),
+        })?;
+
+    Ok(CoinPrice {
+        coin_id: coin_id.to_string(),
+        currency: currency.to_uppercase(),
+        price: *price,
+    })
+}
+
+impl From<BodyError> for CoinGeckoError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => CoinGeckoError::TooLarge,
+            BodyError::Read(e) => CoinGeckoError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for CoinGeckoTool {
+    const NAME: &'static str = "get_coin_price";
+
+    type Args = CoinGeckoArgs;
+    type Output = CoinPrice;
+    type Error = CoinGeckoError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "get_coin_price".to_string(),
+            description: "Get the current price of a cryptocurrency in a fiat currency from CoinGecko".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "coin": { "type": "string", "description": "A ticker (e.g., 'btc', 'eth') or CoinGecko coin id (e.g., 'bitcoin', 'arbitrum')" },
+                    "currency": { "type": "string", "description": "Fiat currency code to price the coin in (default 'usd')" },
+                },
+                "required": ["coin"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let coin_id = coin_id(&args.coin);
+        let currency = args
+            .currency
+            .unwrap_or_else(|| "usd".to_string())
+            .to_lowercase();
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| CoinGeckoError::HttpRequestFailed(e.to_string()))?;
+
+        // The public API needs no key
+        let response = client
+            .get(SIMPLE_PRICE_URL)
+            .query(&[("ids", coin_id.as_str()), ("vs_currencies", currency.as_str())])
+            .send()
+            .await
+            .map_err(|e| CoinGeckoError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        let text = read_body(response).await?;
+
+        if !status.is_success() {
+            return Err(CoinGeckoError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        parse_price(&text, &coin_id, &currency)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_coin_id_maps_tickers() {
+        assert_eq!(coin_id("BTC"), "bitcoin");
+        assert_eq!(coin_id(" eth "), "ethereum");
+        assert_eq!(coin_id("arbitrum"), "arbitrum");
+    }
+
+    #[test]
+    fn test_parse_price() {
+        let text = r#"{"bitcoin":{"eur":61234.5}}"#;
+        assert_eq!(
+            parse_price(text, "bitcoin", "eur").unwrap(),
+            CoinPrice {
+                coin_id: "bitcoin".into(),
+                currency: "EUR".into(),
+                price: 61234.5,
+            }
+        );
+
+        assert!(matches!(
+            parse_price("{}", "notacoin", "usd"),
+            Err(CoinGeckoError::UnknownCoin(_))
+        ));
+        assert!(matches!(
+            parse_price(r#"{"bitcoin":{}}"#, "bitcoin", "xyz"),
+            Err(CoinGeckoError::UnsupportedCurrency { .. })
+        ));
+    }
+}
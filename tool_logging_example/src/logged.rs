@@ -0,0 +1,122 @@
+// logged.rs
+
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde_json::Value;
+use std::time::Instant;
+use tracing::{info, warn};
+
+#[derive(Debug, thiserror::Error)]
+pub enum LoggedError<E: std::error::Error> {
+    #[error("Invalid arguments: {0}")]
+    InvalidArgs(#[from] serde_json::Error),
+    #[error(transparent)]
+    Tool(E),
+}
+
+/// Wraps a tool and logs every call the model makes to it: the tool's name,
+/// the arguments exactly as the model sent them, how long the call took and
+/// whether it succeeded.
+///
+/// The wrapper has the same name and definition as the tool it wraps, so the
+/// model can't tell the difference:
+///
+/// ```ignore
+/// agent_builder.tool(Logged(CalculatorTool))
+/// ```
+pub struct Logged<T>(pub T);
+
+impl<T: Tool> Tool for Logged<T> {
+    const NAME: &'static str = T::NAME;
+
+    // Taking the raw JSON lets the arguments be logged even when they don't
+    // match what the tool expects
+    type Args = Value;
+    type Output = T::Output;
+    type Error = LoggedError<T::Error>;
+
+    async fn definition(&self, prompt: String) -> ToolDefinition {
+        self.0.definition(prompt).await
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        let started = Instant::now();
+        let raw_args = args.to_string();
+
+        let result = match serde_json::from_value(args) {
+            Ok(args) => self.0.call(args).await.map_err(LoggedError::Tool),
+            Err(e) => Err(LoggedError::InvalidArgs(e)),
+        };
+
+        let elapsed_ms = started.elapsed().as_millis();
+        match &result {
+            Ok(_) => info!(tool = T::NAME, args = %raw_args, elapsed_ms, "Tool call succeeded"),
+            Err(e) => warn!(tool = T::NAME, args = %raw_args, elapsed_ms, error = %e, "Tool call failed"),
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct HalfArgs {
+        value: i64,
+    }
+
+    #[derive(Debug, thiserror::Error)]
+    #[error("{0} is odd")]
+    struct OddError(i64);
+
+    /// Halves even numbers and fails on odd ones
+    struct Half;
+
+    impl Tool for Half {
+        const NAME: &'static str = "half";
+
+        type Args = HalfArgs;
+        type Output = i64;
+        type Error = OddError;
+
+        async fn definition(&self, _prompt: String) -> ToolDefinition {
+            ToolDefinition {
+                name: Self::NAME.to_string(),
+                description: "Halve an even number".to_string(),
+                parameters: json!({}),
+            }
+        }
+
+        async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+            match args.value % 2 {
+                0 => Ok(args.value / 2),
+                _ => Err(OddError(args.value)),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_passes_calls_through() {
+        let tool = Logged(Half);
+        assert_eq!(tool.name(), "half");
+        assert_eq!(tool.definition(String::new()).await.description, "Halve an even number");
+        assert_eq!(tool.call(json!({ "value": 42 })).await.unwrap(), 21);
+    }
+
+    #[tokio::test]
+    async fn test_reports_errors() {
+        let tool = Logged(Half);
+        assert!(matches!(
+            tool.call(json!({ "value": 7 })).await,
+            Err(LoggedError::Tool(OddError(7)))
+        ));
+        assert!(matches!(
+            tool.call(json!({ "number": 8 })).await,
+            Err(LoggedError::InvalidArgs(_))
+        ));
+    }
+}
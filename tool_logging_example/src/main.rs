@@ -0,0 +1,46 @@
+mod calculator_tool;
+mod coingecko_tool;
+mod logged;
+
+use crate::calculator_tool::CalculatorTool;
+use crate::coingecko_tool::CoinGeckoTool;
+use crate::logged::Logged;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Print the tool call logs to stderr
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .with_writer(std::io::stderr)
+        .init();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with both tools wrapped in `Logged`
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a crypto market assistant. Use the get_coin_price tool to look up current prices and the calculator tool for any arithmetic.")
+        .tool(Logged(CoinGeckoTool))
+        .tool(Logged(CalculatorTool))
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What's the price of ETH in euros?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
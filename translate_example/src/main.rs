@@ -0,0 +1,35 @@
+mod translate_tool;
+
+use crate::translate_tool::TranslateTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the TranslateTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a helpful language assistant. Use the translate tool for every translation instead of translating yourself.")
+        .tool(TranslateTool)
+        .build();
+
+    // Take the request from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "How do I say 'Where is the train station?' in Japanese?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
@@ -0,0 +1,211 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::env;
+use std::time::Duration;
+
+// Keys for DeepL's free plan end in ":fx" and only work on the free API host
+const DEEPL_FREE_URL: &str = "https://api-free.deepl.com/v2/translate";
+const DEEPL_PRO_URL: &str = "https://api.deepl.com/v2/translate";
+
+// Maximum time to wait for DeepL before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Deserialize)]
+pub struct TranslateArgs {
+    text: String,
+    source_language: Option<String>,
+    target_language: String,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum TranslateError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Missing API key: set the DEEPL_API_KEY environment variable")]
+    MissingApiKey,
+    #[error("Unsupported language: {0}. Use a DeepL language code such as 'EN', 'DE' or 'JA'")]
+    UnsupportedLanguage(String),
+    #[error("The DeepL character quota for this billing period has been used up")]
+    QuotaExceeded,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Translation {
+    text: String,
+    detected_source_language: String,
+}
+
+#[derive(Deserialize)]
+struct DeeplResponse {
+    translations: Vec<DeeplTranslation>,
+}
+
+#[derive(Deserialize)]
+struct DeeplTranslation {
+    detected_source_language: String,
+    text: String,
+}
+
+#[derive(Deserialize)]
+struct DeeplError {
+    message: String,
+}
+
+pub struct TranslateTool;
+
+fn api_url(api_key: &str) -> &'static str {
+    if api_key.ends_with(":fx") {
+        DEEPL_FREE_URL
+    } else {
+        DEEPL_PRO_URL
+    }
+}
+
+// Turn the raw response body into the translation of the single text sent
+fn parse_translation(text: &str) -> Result<Translation, TranslateError> {
+    let response: DeeplResponse =
+        serde_json::from_str(text).map_err(|e| TranslateError::InvalidResponse(e.to_string()))?;
+
+    let translation = response
+        .translations
+        .into_iter()
+        .next()
+        .ok_or_else(|| TranslateError::InvalidResponse("no translations returned".to_string()))?;
+
+    Ok(Translation {
+        text: translation.text,
+        detected_source_language: translation.detected_source_language,
+    })
+}
+
+// DeepL answers bad requests with a 400 and a message such as
+// "Value for 'target_lang' not supported."; language problems get their own error
+fn bad_request_error(text: &str) -> TranslateError {
+    let message = serde_json::from_str::<DeeplError>(text)
+        .map(|error| error.message)
+        .unwrap_or_else(|_| text.to_string());
+
+    if message.contains("_lang") {
+        TranslateError::UnsupportedLanguage(message)
+    } else {
+        TranslateError::ApiError(message)
+    }
+}
+
+impl From<BodyError> for TranslateError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => TranslateError::TooLarge,
+            BodyError::Read(e) => TranslateError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for TranslateTool {
+    const NAME: &'static str = "translate";
+
+    type Args = TranslateArgs;
+    type Output = Translation;
+    type Error = TranslateError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "translate".to_string(),
+            description: "Translate text into another language with DeepL. Returns the translation and the detected source language.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "text": { "type": "string", "description": "The text to translate" },
+                    "source_language": { "type": "string", "description": "DeepL code of the text's language (e.g., 'EN', 'FR'). Leave out to detect it automatically." },
+                    "target_language": { "type": "string", "description": "DeepL code of the language to translate into (e.g., 'DE', 'JA', 'EN-GB', 'PT-BR')" },
+                },
+                "required": ["text", "target_language"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // Use the DeepL API key from an environment variable
+        let api_key = env::var("DEEPL_API_KEY").map_err(|_| TranslateError::MissingApiKey)?;
+
+        let mut body = json!({
+            "text": [args.text],
+            "target_lang": args.target_language.trim().to_uppercase(),
+        });
+        if let Some(source_language) = &args.source_language {
+            body["source_lang"] = json!(source_language.trim().to_uppercase());
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| TranslateError::HttpRequestFailed(e.to_string()))?;
+
+        let response = client
+            .post(api_url(&api_key))
+            .header("Authorization", format!("DeepL-Auth-Key {}", api_key))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| TranslateError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        let text = read_body(response).await?;
+
+        match status.as_u16() {
+            200..=299 => parse_translation(&text),
+            400 => Err(bad_request_error(&text)),
+            456 => Err(TranslateError::QuotaExceeded),
+            _ => Err(TranslateError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_translation() {
+        let text = r#"{"translations":[{"detected_source_language":"EN","text":"Hallo, Welt!"}]}"#;
+        assert_eq!(
+            parse_translation(text).unwrap(),
+            Translation {
+                text: "Hallo, Welt!".into(),
+                detected_source_language: "EN".into(),
+            }
+        );
+        assert!(matches!(
+            parse_translation(r#"{"translations":[]}"#),
+            Err(TranslateError::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn test_bad_request_error_and_host() {
+        assert!(matches!(
+            bad_request_error(r#"{"message":"Value for 'target_lang' not supported."}"#),
+            TranslateError::UnsupportedLanguage(_)
+        ));
+        assert!(matches!(
+            bad_request_error(r#"{"message":"Parameter 'text' not specified."}"#),
+            TranslateError::ApiError(_)
+        ));
+        assert_eq!(api_url("0123-abcd:fx"), DEEPL_FREE_URL);
+        assert_eq!(api_url("0123-abcd"), DEEPL_PRO_URL);
+    }
+}
@@ -0,0 +1,37 @@
+mod unit_convert_tool;
+
+use crate::unit_convert_tool::UnitConvertTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the UnitConvertTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a helpful assistant. Use the convert_units tool for every unit conversion instead of working it out yourself, and round the results sensibly in your answer.")
+        .tool(UnitConvertTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example.
+    // Rig answers with the output of the first tool call, so the example needs
+    // only one.
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "A recipe asks for an oven at 350 F. What is that in Celsius?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
@@ -0,0 +1,255 @@
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Quantity {
+    Length,
+    Mass,
+    Temperature,
+    Volume,
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Quantity::Length => "length",
+            Quantity::Mass => "mass",
+            Quantity::Temperature => "temperature",
+            Quantity::Volume => "volume",
+        };
+        f.write_str(name)
+    }
+}
+
+/// A unit and how to convert it to its quantity's base unit (metres,
+/// kilograms, kelvin or litres): `base = value * factor + offset`. Only
+/// temperatures need an offset.
+struct Unit {
+    symbol: &'static str,
+    /// Lowercase names the unit is recognised by, besides its symbol
+    aliases: &'static [&'static str],
+    quantity: Quantity,
+    factor: f64,
+    offset: f64,
+}
+
+const fn unit(
+    symbol: &'static str,
+    aliases: &'static [&'static str],
+    quantity: Quantity,
+    factor: f64,
+) -> Unit {
+    Unit {
+        symbol,
+        aliases,
+        quantity,
+        factor,
+        offset: 0.0,
+    }
+}
+
+// Imperial volumes are US customary units
+const UNITS: &[Unit] = &[
+    unit("mm", &["millimeter", "millimeters", "millimetre", "millimetres"], Quantity::Length, 0.001),
+    unit("cm", &["centimeter", "centimeters", "centimetre", "centimetres"], Quantity::Length, 0.01),
+    unit("m", &["meter", "meters", "metre", "metres"], Quantity::Length, 1.0),
+    unit("km", &["kilometer", "kilometers", "kilometre", "kilometres"], Quantity::Length, 1000.0),
+    unit("in", &["inch", "inches", "\""], Quantity::Length, 0.0254),
+    unit("ft", &["foot", "feet", "'"], Quantity::Length, 0.3048),
+    unit("yd", &["yard", "yards"], Quantity::Length, 0.9144),
+    unit("mi", &["mile", "miles"], Quantity::Length, 1609.344),
+    unit("nmi", &["nautical mile", "nautical miles"], Quantity::Length, 1852.0),
+    unit("mg", &["milligram", "milligrams"], Quantity::Mass, 0.000_001),
+    unit("g", &["gram", "grams"], Quantity::Mass, 0.001),
+    unit("kg", &["kilogram", "kilograms", "kilo", "kilos"], Quantity::Mass, 1.0),
+    unit("t", &["tonne", "tonnes", "metric ton", "metric tons"], Quantity::Mass, 1000.0),
+    unit("oz", &["ounce", "ounces"], Quantity::Mass, 0.028_349_523_125),
+    unit("lb", &["lbs", "pound", "pounds"], Quantity::Mass, 0.453_592_37),
+    unit("st", &["stone", "stones"], Quantity::Mass, 6.350_293_18),
+    Unit {
+        symbol: "°C",
+        aliases: &["c", "celsius", "centigrade"],
+        quantity: Quantity::Temperature,
+        factor: 1.0,
+        offset: 273.15,
+    },
+    Unit {
+        symbol: "°F",
+        aliases: &["f", "fahrenheit"],
+        quantity: Quantity::Temperature,
+        factor: 5.0 / 9.0,
+        offset: 459.67 * 5.0 / 9.0,
+    },
+    unit("K", &["k", "kelvin", "kelvins"], Quantity::Temperature, 1.0),
+    unit("ml", &["milliliter", "milliliters", "millilitre", "millilitres"], Quantity::Volume, 0.001),
+    unit("l", &["liter", "liters", "litre", "litres"], Quantity::Volume, 1.0),
+    unit("m³", &["m3", "cubic meter", "cubic meters", "cubic metre", "cubic metres"], Quantity::Volume, 1000.0),
+    unit("tsp", &["teaspoon", "teaspoons"], Quantity::Volume, 0.004_928_921_593_75),
+    unit("tbsp", &["tablespoon", "tablespoons"], Quantity::Volume, 0.014_786_764_781_25),
+    unit("fl oz", &["fluid ounce", "fluid ounces", "floz"], Quantity::Volume, 0.029_573_529_562_5),
+    unit("cup", &["cups"], Quantity::Volume, 0.236_588_236_5),
+    unit("pt", &["pint", "pints"], Quantity::Volume, 0.473_176_473),
+    unit("qt", &["quart", "quarts"], Quantity::Volume, 0.946_352_946),
+    unit("gal", &["gallon", "gallons"], Quantity::Volume, 3.785_411_784),
+];
+
+#[derive(Deserialize)]
+pub struct UnitConvertArgs {
+    amount: f64,
+    from: String,
+    to: String,
+}
+
+// Anything returned here is sent back to the model as the tool's output, so
+// the messages say what went wrong in terms it can act on
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum UnitConvertError {
+    #[error("Unknown unit '{0}'. Supported: {}", supported_units())]
+    UnknownUnit(String),
+    #[error("Can't convert {from} ({from_quantity}) to {to} ({to_quantity})")]
+    IncompatibleUnits {
+        from: String,
+        from_quantity: String,
+        to: String,
+        to_quantity: String,
+    },
+    #[error("{0} is below absolute zero")]
+    BelowAbsoluteZero(String),
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Conversion {
+    value: f64,
+    unit: &'static str,
+}
+
+fn supported_units() -> String {
+    UNITS.iter().map(|unit| unit.symbol).collect::<Vec<_>>().join(", ")
+}
+
+// Find a unit by symbol or name, ignoring case, surrounding spaces, a trailing
+// period and a "degrees" prefix
+fn find_unit(name: &str) -> Result<&'static Unit, UnitConvertError> {
+    let normalized = name.trim().trim_end_matches('.').to_lowercase();
+    let normalized = normalized
+        .strip_prefix("degrees ")
+        .or_else(|| normalized.strip_prefix('°'))
+        .unwrap_or(&normalized)
+        .trim();
+
+    UNITS
+        .iter()
+        .find(|unit| unit.symbol.to_lowercase() == normalized || unit.aliases.contains(&normalized))
+        .ok_or_else(|| UnitConvertError::UnknownUnit(name.to_string()))
+}
+
+fn convert(amount: f64, from: &str, to: &str) -> Result<Conversion, UnitConvertError> {
+    let from_unit = find_unit(from)?;
+    let to_unit = find_unit(to)?;
+
+    if from_unit.quantity != to_unit.quantity {
+        return Err(UnitConvertError::IncompatibleUnits {
+            from: from_unit.symbol.to_string(),
+            from_quantity: from_unit.quantity.to_string(),
+            to: to_unit.symbol.to_string(),
+            to_quantity: to_unit.quantity.to_string(),
+        });
+    }
+
+    let base = amount * from_unit.factor + from_unit.offset;
+    if from_unit.quantity == Quantity::Temperature && base < 0.0 {
+        return Err(UnitConvertError::BelowAbsoluteZero(format!("{} {}", amount, from_unit.symbol)));
+    }
+
+    Ok(Conversion {
+        value: (base - to_unit.offset) / to_unit.factor,
+        unit: to_unit.symbol,
+    })
+}
+
+/// Converts amounts between units of length, mass, temperature and volume.
+/// Like the calculator, it runs entirely in-process.
+pub struct UnitConvertTool;
+
+impl Tool for UnitConvertTool {
+    const NAME: &'static str = "convert_units";
+
+    type Args = UnitConvertArgs;
+    type Output = Conversion;
+    type Error = UnitConvertError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "convert_units".to_string(),
+            description: "Convert an amount between units of the same kind: length (mm, cm, m, km, in, ft, yd, mi, nmi), mass (mg, g, kg, t, oz, lb, st), temperature (C, F, K) or volume (ml, l, m3, tsp, tbsp, fl oz, cup, pt, qt, gal; US customary)".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "amount": { "type": "number", "description": "The amount to convert (e.g., 26.2)" },
+                    "from": { "type": "string", "description": "The unit of the amount, as a symbol or name (e.g., 'mi' or 'miles')" },
+                    "to": { "type": "string", "description": "The unit to convert to (e.g., 'km')" },
+                },
+                "required": ["amount", "from", "to"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        convert(args.amount, &args.from, &args.to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_converts(amount: f64, from: &str, to: &str, expected: f64) {
+        let conversion = convert(amount, from, to).unwrap();
+        assert!(
+            (conversion.value - expected).abs() < 1e-9,
+            "{} {} = {} {}, expected {}",
+            amount,
+            from,
+            conversion.value,
+            conversion.unit,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_conversions() {
+        assert_converts(26.2, "miles", "km", 42.164_812_8);
+        assert_converts(212.0, "°F", "celsius", 100.0);
+        assert_converts(-40.0, "F", "C", -40.0);
+        assert_converts(0.0, "degrees Celsius", "K", 273.15);
+        assert_converts(10.0, "lbs", "kg", 4.535_923_7);
+        assert_converts(1.0, "gal", "cups", 16.0);
+        assert_converts(3.0, "FT.", "in", 36.0);
+
+        assert_eq!(convert(1.0, "l", "ml").unwrap().unit, "ml");
+    }
+
+    #[test]
+    fn test_invalid_conversions() {
+        assert_eq!(
+            convert(5.0, "km", "kg"),
+            Err(UnitConvertError::IncompatibleUnits {
+                from: "km".into(),
+                from_quantity: "length".into(),
+                to: "kg".into(),
+                to_quantity: "mass".into(),
+            })
+        );
+        assert_eq!(
+            convert(1.0, "furlongs", "m"),
+            Err(UnitConvertError::UnknownUnit("furlongs".into()))
+        );
+        assert!(matches!(
+            convert(-300.0, "C", "F"),
+            Err(UnitConvertError::BelowAbsoluteZero(_))
+        ));
+    }
+}
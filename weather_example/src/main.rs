@@ -0,0 +1,35 @@
+mod weather_tool;
+
+use crate::weather_tool::WeatherTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the WeatherTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a weather assistant. Use the get_weather tool to answer questions about the current weather.")
+        .tool(WeatherTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What's the weather like in Lisbon right now?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
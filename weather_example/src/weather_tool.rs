@@ -0,0 +1,287 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::env;
+use std::time::Duration;
+
+const GEOCODING_URL: &str = "https://api.openweathermap.org/geo/1.0/direct";
+const CURRENT_WEATHER_URL: &str = "https://api.openweathermap.org/data/2.5/weather";
+
+// Maximum time to wait for OpenWeatherMap before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// How many candidate locations to ask the geocoding API for
+const GEOCODING_LIMIT: usize = 5;
+
+#[derive(Deserialize)]
+pub struct WeatherArgs {
+    city: String,
+    units: Option<String>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WeatherError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Missing API key: set the OPENWEATHER_API_KEY environment variable")]
+    MissingApiKey,
+    #[error("Unknown city: {0}")]
+    UnknownCity(String),
+    #[error("'{city}' is ambiguous, it could be any of: {candidates}. Add a state or country code, e.g. 'Paris,FR'")]
+    AmbiguousCity { city: String, candidates: String },
+    #[error("Invalid units '{0}': expected 'metric', 'imperial' or 'standard'")]
+    InvalidUnits(String),
+}
+
+#[derive(Debug, Serialize)]
+pub struct CurrentWeather {
+    location: String,
+    conditions: String,
+    temperature: f64,
+    feels_like: f64,
+    humidity: u8,
+    wind_speed: f64,
+    units: String,
+}
+
+// A candidate returned by the geocoding API
+#[derive(Debug, Clone, Deserialize)]
+struct Location {
+    name: String,
+    lat: f64,
+    lon: f64,
+    country: String,
+    state: Option<String>,
+}
+
+impl Location {
+    fn display_name(&self) -> String {
+        match &self.state {
+            Some(state) => format!("{}, {}, {}", self.name, state, self.country),
+            None => format!("{}, {}", self.name, self.country),
+        }
+    }
+}
+
+// The parts of the current weather response we use
+#[derive(Deserialize)]
+struct WeatherResponse {
+    weather: Vec<WeatherCondition>,
+    main: MainReadings,
+    wind: Wind,
+}
+
+#[derive(Deserialize)]
+struct WeatherCondition {
+    description: String,
+}
+
+#[derive(Deserialize)]
+struct MainReadings {
+    temp: f64,
+    feels_like: f64,
+    humidity: u8,
+}
+
+#[derive(Deserialize)]
+struct Wind {
+    speed: f64,
+}
+
+pub struct WeatherTool;
+
+// Pick the single location `city` refers to. The geocoding API often returns
+// the same place several times, so only distinct regions count as ambiguous.
+fn pick_location(city: &str, locations: Vec<Location>) -> Result<Location, WeatherError> {
+    let mut distinct: Vec<Location> = Vec::new();
+    for location in locations {
+        if !distinct
+            .iter()
+            .any(|l| l.country == location.country && l.state == location.state)
+        {
+            distinct.push(location);
+        }
+    }
+
+    match distinct.len() {
+        0 => Err(WeatherError::UnknownCity(city.to_string())),
+        1 => Ok(distinct.remove(0)),
+        _ => Err(WeatherError::AmbiguousCity {
+            city: city.to_string(),
+            candidates: distinct
+                .iter()
+                .map(Location::display_name)
+                .collect::<Vec<_>>()
+                .join("; "),
+        }),
+    }
+}
+
+impl From<BodyError> for WeatherError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => WeatherError::TooLarge,
+            BodyError::Read(e) => WeatherError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+// Send a GET request and deserialize the JSON body, reporting non-2xx statuses
+// as API errors
+async fn get_json<T: DeserializeOwned>(
+    client: &reqwest::Client,
+    url: &str,
+    query: &[(&str, String)],
+) -> Result<T, WeatherError> {
+    let response = client
+        .get(url)
+        .query(query)
+        .send()
+        .await
+        .map_err(|e| WeatherError::HttpRequestFailed(e.to_string()))?;
+
+    // Get the status code before consuming `response`
+    let status = response.status();
+
+    let text = read_body(response).await?;
+
+    if !status.is_success() {
+        return Err(WeatherError::ApiError(format!(
+            "Status: {}, Response: {}",
+            status, text
+        )));
+    }
+
+    serde_json::from_str(&text).map_err(|e| WeatherError::InvalidResponse(e.to_string()))
+}
+
+impl Tool for WeatherTool {
+    const NAME: &'static str = "get_weather";
+
+    type Args = WeatherArgs;
+    type Output = CurrentWeather;
+    type Error = WeatherError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "get_weather".to_string(),
+            description: "Get the current weather conditions and temperature for a city".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "city": { "type": "string", "description": "City name, optionally followed by a state and/or ISO country code (e.g., 'London', 'Portland,OR,US', 'Paris,FR')" },
+                    "units": { "type": "string", "description": "Units for temperature and wind speed (default 'metric')", "enum": ["metric", "imperial", "standard"] },
+                },
+                "required": ["city"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // Use the OpenWeatherMap API key from an environment variable
+        let api_key = env::var("OPENWEATHER_API_KEY").map_err(|_| WeatherError::MissingApiKey)?;
+
+        let units = args.units.unwrap_or_else(|| "metric".to_string());
+        if !["metric", "imperial", "standard"].contains(&units.as_str()) {
+            return Err(WeatherError::InvalidUnits(units));
+        }
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| WeatherError::HttpRequestFailed(e.to_string()))?;
+
+        // Resolve the city name to coordinates first
+        let locations: Vec<Location> = get_json(
+            &client,
+            GEOCODING_URL,
+            &[
+                ("q", args.city.clone()),
+                ("limit", GEOCODING_LIMIT.to_string()),
+                ("appid", api_key.clone()),
+            ],
+        )
+        .await?;
+        let location = pick_location(&args.city, locations)?;
+
+        let weather: WeatherResponse = get_json(
+            &client,
+            CURRENT_WEATHER_URL,
+            &[
+                ("lat", location.lat.to_string()),
+                ("lon", location.lon.to_string()),
+                ("units", units.clone()),
+                ("appid", api_key),
+            ],
+        )
+        .await?;
+
+        Ok(CurrentWeather {
+            location: location.display_name(),
+            conditions: weather
+                .weather
+                .into_iter()
+                .map(|condition| condition.description)
+                .collect::<Vec<_>>()
+                .join(", "),
+            temperature: weather.main.temp,
+            feels_like: weather.main.feels_like,
+            humidity: weather.main.humidity,
+            wind_speed: weather.wind.speed,
+            units,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn location(name: &str, country: &str, state: Option<&str>) -> Location {
+        Location {
+            name: name.to_string(),
+            lat: 0.0,
+            lon: 0.0,
+            country: country.to_string(),
+            state: state.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_duplicate_matches_are_not_ambiguous() {
+        let locations = vec![
+            location("London", "GB", Some("England")),
+            location("London", "GB", Some("England")),
+        ];
+        let picked = pick_location("London", locations).unwrap();
+        assert_eq!(picked.display_name(), "London, England, GB");
+    }
+
+    #[test]
+    fn test_unknown_and_ambiguous_cities() {
+        assert!(matches!(
+            pick_location("Atlantis", vec![]),
+            Err(WeatherError::UnknownCity(city)) if city == "Atlantis"
+        ));
+
+        let locations = vec![
+            location("Portland", "US", Some("Oregon")),
+            location("Portland", "US", Some("Maine")),
+        ];
+        match pick_location("Portland", locations) {
+            Err(WeatherError::AmbiguousCity { candidates, .. }) => {
+                assert_eq!(candidates, "Portland, Oregon, US; Portland, Maine, US");
+            }
+            other => panic!("expected an ambiguous city, got {:?}", other),
+        }
+    }
+}
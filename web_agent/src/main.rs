@@ -0,0 +1,139 @@
+// main.rs
+
+mod openai_stream;
+
+use anyhow::Context;
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use dotenv::dotenv;
+use futures::{Stream, StreamExt};
+use openai_stream::{request_body, stream_chat_completion};
+use rig::agent::Agent;
+use rig::completion::{Completion, Prompt};
+use rig::providers::openai;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tracing::{error, info};
+
+const MODEL: &str = openai::GPT_4O;
+
+/// Shared by every request; the agent itself is immutable once built
+#[derive(Clone)]
+struct AppState {
+    agent: Arc<Agent<openai::CompletionModel>>,
+    http_client: reqwest::Client,
+    api_key: Arc<str>,
+}
+
+#[derive(Deserialize)]
+struct ChatRequest {
+    message: String,
+}
+
+#[derive(Serialize)]
+struct ChatResponse {
+    reply: String,
+}
+
+#[derive(Serialize)]
+struct ErrorResponse {
+    error: String,
+}
+
+/// Errors are logged and returned to the client as `{ "error": "..." }`
+struct AppError(anyhow::Error);
+
+impl<E: Into<anyhow::Error>> From<E> for AppError {
+    fn from(e: E) -> Self {
+        Self(e.into())
+    }
+}
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        error!("Request failed: {:?}", self.0);
+        let body = Json(ErrorResponse { error: self.0.to_string() });
+        (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
+    }
+}
+
+/// `POST /chat`: answer the message and return the whole reply at once
+async fn chat(
+    State(state): State<AppState>,
+    Json(request): Json<ChatRequest>,
+) -> Result<Json<ChatResponse>, AppError> {
+    let reply = state.agent.prompt(&request.message).await?;
+    Ok(Json(ChatResponse { reply }))
+}
+
+/// `POST /chat/stream`: answer the message as server-sent events, one
+/// `message` event per piece of text and a final `done` event
+async fn chat_stream(
+    State(state): State<AppState>,
+    Json(request): Json<ChatRequest>,
+) -> Result<Sse<impl Stream<Item = Result<Event, Infallible>>>, AppError> {
+    // The agent assembles the request (preamble, context); only sending it
+    // is done by hand because rig cannot stream the response
+    let completion = state.agent.completion(&request.message, vec![]).await?.build();
+    let body = request_body(MODEL, &completion);
+    let deltas = stream_chat_completion(&state.http_client, &state.api_key, body).await?;
+
+    let events = deltas
+        .map(|delta| {
+            Ok(match delta {
+                Ok(text) => Event::default().data(text),
+                Err(e) => {
+                    error!("Stream failed: {:?}", e);
+                    Event::default().event("error").data(e.to_string())
+                }
+            })
+        })
+        .chain(futures::stream::once(async {
+            Ok(Event::default().event("done").data(""))
+        }));
+
+    Ok(Sse::new(events).keep_alive(KeepAlive::default()))
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    dotenv().ok();
+
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+
+    let api_key = std::env::var("OPENAI_API_KEY").context("OPENAI_API_KEY not set")?;
+    let openai_client = openai::Client::new(&api_key);
+
+    let agent = openai_client
+        .agent(MODEL)
+        .preamble("You are a helpful assistant. Keep your answers short and to the point.")
+        .build();
+
+    let state = AppState {
+        agent: Arc::new(agent),
+        http_client: reqwest::Client::new(),
+        api_key: api_key.into(),
+    };
+
+    let app = Router::new()
+        .route("/chat", post(chat))
+        .route("/chat/stream", post(chat_stream))
+        .with_state(state);
+
+    let addr = std::env::var("BIND_ADDR").unwrap_or_else(|_| "127.0.0.1:3000".to_string());
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("Failed to bind {}", addr))?;
+    info!("Listening on http://{}", addr);
+
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
@@ -0,0 +1,133 @@
+// openai_stream.rs
+
+// rig 0.2 has no streaming API, so this module talks to OpenAI's chat
+// completions endpoint directly with `"stream": true` and turns the
+// server-sent events it returns into a stream of text deltas.
+
+use anyhow::Result;
+use futures::{Stream, StreamExt};
+use rig::completion::CompletionRequest;
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+const OPENAI_CHAT_COMPLETIONS_URL: &str = "https://api.openai.com/v1/chat/completions";
+
+/// A single `data:` event of a chat completion stream
+#[derive(Deserialize)]
+struct StreamChunk {
+    choices: Vec<StreamChoice>,
+}
+
+#[derive(Deserialize)]
+struct StreamChoice {
+    delta: StreamDelta,
+}
+
+#[derive(Deserialize)]
+struct StreamDelta {
+    content: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum StreamEvent {
+    Text(String),
+    Done,
+}
+
+/// Convert a request assembled by a rig agent (preamble, chat history and
+/// prompt) into an OpenAI chat completions body
+pub fn request_body(model: &str, request: &CompletionRequest) -> Value {
+    let mut messages: Vec<Value> = Vec::new();
+    if let Some(preamble) = &request.preamble {
+        messages.push(json!({ "role": "system", "content": preamble }));
+    }
+    for message in &request.chat_history {
+        messages.push(json!({ "role": message.role, "content": message.content }));
+    }
+    messages.push(json!({ "role": "user", "content": request.prompt_with_context() }));
+
+    json!({
+        "model": model,
+        "messages": messages,
+        "temperature": request.temperature,
+        "stream": true,
+    })
+}
+
+/// Send a streaming chat completion request and yield the text of the answer
+/// as it arrives
+pub async fn stream_chat_completion(
+    http_client: &reqwest::Client,
+    api_key: &str,
+    body: Value,
+) -> Result<impl Stream<Item = Result<String>>> {
+    let response = http_client
+        .post(OPENAI_CHAT_COMPLETIONS_URL)
+        .bearer_auth(api_key)
+        .json(&body)
+        .send()
+        .await?
+        .error_for_status()?;
+
+    let bytes = response.bytes_stream();
+    let stream = futures::stream::unfold(
+        (bytes, Vec::new(), false),
+        |(mut bytes, mut buffer, done)| async move {
+            loop {
+                if done {
+                    return None;
+                }
+
+                // Events are newline delimited; keep any partial line for the next read
+                if let Some(pos) = buffer.iter().position(|b| *b == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=pos).collect();
+                    match parse_stream_line(String::from_utf8_lossy(&line).trim()) {
+                        Some(StreamEvent::Text(text)) => return Some((Ok(text), (bytes, buffer, false))),
+                        Some(StreamEvent::Done) => return None,
+                        None => continue,
+                    }
+                }
+
+                match bytes.next().await {
+                    Some(Ok(chunk)) => buffer.extend_from_slice(&chunk),
+                    Some(Err(e)) => return Some((Err(e.into()), (bytes, buffer, true))),
+                    None => return None,
+                }
+            }
+        },
+    );
+
+    Ok(stream)
+}
+
+fn parse_stream_line(line: &str) -> Option<StreamEvent> {
+    let data = line.strip_prefix("data:")?.trim();
+    if data == "[DONE]" {
+        return Some(StreamEvent::Done);
+    }
+
+    let chunk: StreamChunk = serde_json::from_str(data).ok()?;
+    chunk
+        .choices
+        .into_iter()
+        .next()
+        .and_then(|choice| choice.delta.content)
+        .filter(|text| !text.is_empty())
+        .map(StreamEvent::Text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_stream_line() {
+        let line = r#"data: {"id":"chatcmpl-1","choices":[{"index":0,"delta":{"content":"Hello"},"finish_reason":null}]}"#;
+        assert_eq!(parse_stream_line(line), Some(StreamEvent::Text("Hello".into())));
+
+        let role_only = r#"data: {"choices":[{"index":0,"delta":{"role":"assistant"}}]}"#;
+        assert_eq!(parse_stream_line(role_only), None);
+        assert_eq!(parse_stream_line("data: [DONE]"), Some(StreamEvent::Done));
+        assert_eq!(parse_stream_line(": keep-alive"), None);
+    }
+}
@@ -0,0 +1,35 @@
+mod web_search_tool;
+
+use crate::web_search_tool::WebSearchTool;
+use dotenv::dotenv;
+use rig::completion::Prompt;
+use rig::providers::openai;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    dotenv().ok();
+
+    // Initialize the OpenAI client
+    let openai_client = openai::Client::from_env();
+
+    // Build the agent with the WebSearchTool
+    let agent = openai_client
+        .agent(openai::GPT_4O)
+        .preamble("You are a research assistant. Use the web_search tool to look things up and list the URLs of the results you relied on.")
+        .tool(WebSearchTool)
+        .build();
+
+    // Take the question from the command line, or fall back to an example
+    let query = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    let query = if query.is_empty() {
+        "What is the latest stable release of Rust?".to_string()
+    } else {
+        query
+    };
+
+    let response = agent.prompt(&query).await?;
+
+    println!("Agent response:\n{}", response);
+
+    Ok(())
+}
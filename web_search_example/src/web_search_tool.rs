@@ -0,0 +1,198 @@
+use http_limits::{read_body, BodyError, MAX_RESPONSE_SIZE};
+use rig::completion::ToolDefinition;
+use rig::tool::Tool;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::env;
+use std::time::Duration;
+
+const BRAVE_SEARCH_URL: &str = "https://api.search.brave.com/res/v1/web/search";
+
+// Maximum time to wait for the search API before giving up
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+// Number of results returned when the model doesn't ask for a specific count
+const DEFAULT_RESULT_COUNT: usize = 5;
+// Brave returns at most 20 results per request
+const MAX_RESULT_COUNT: usize = 20;
+
+#[derive(Deserialize)]
+pub struct WebSearchArgs {
+    query: String,
+    count: Option<usize>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum WebSearchError {
+    #[error("HTTP request failed: {0}")]
+    HttpRequestFailed(String),
+    #[error("Invalid response structure: {0}")]
+    InvalidResponse(String),
+    #[error("API error: {0}")]
+    ApiError(String),
+    #[error("The response is larger than the {} MiB limit", MAX_RESPONSE_SIZE / 1024 / 1024)]
+    TooLarge,
+    #[error("Missing API key: set the BRAVE_API_KEY environment variable")]
+    MissingApiKey,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct SearchResult {
+    title: String,
+    snippet: String,
+    url: String,
+}
+
+// The parts of Brave's web search response we use
+#[derive(Deserialize)]
+struct BraveResponse {
+    web: Option<BraveWebResults>,
+}
+
+#[derive(Deserialize)]
+struct BraveWebResults {
+    results: Vec<BraveResult>,
+}
+
+#[derive(Deserialize)]
+struct BraveResult {
+    title: String,
+    url: String,
+    #[serde(default)]
+    description: String,
+}
+
+pub struct WebSearchTool;
+
+// Turn the raw response body into at most `count` results
+fn parse_results(text: &str, count: usize) -> Result<Vec<SearchResult>, WebSearchError> {
+    let response: BraveResponse =
+        serde_json::from_str(text).map_err(|e| WebSearchError::InvalidResponse(e.to_string()))?;
+
+    Ok(response
+        .web
+        .map(|web| web.results)
+        .unwrap_or_default()
+        .into_iter()
+        .take(count)
+        .map(|result| SearchResult {
+            title: result.title,
+            snippet: result.description,
+            url: result.url,
+        })
+        .collect())
+}
+
+impl From<BodyError> for WebSearchError {
+    fn from(error: BodyError) -> Self {
+        match error {
+            BodyError::TooLarge => WebSearchError::TooLarge,
+            BodyError::Read(e) => WebSearchError::HttpRequestFailed(e.to_string()),
+        }
+    }
+}
+
+impl Tool for WebSearchTool {
+    const NAME: &'static str = "web_search";
+
+    type Args = WebSearchArgs;
+    type Output = Vec<SearchResult>;
+    type Error = WebSearchError;
+
+    async fn definition(&self, _prompt: String) -> ToolDefinition {
+        ToolDefinition {
+            name: "web_search".to_string(),
+            description: "Search the web and return the top results with their title, a short snippet and the URL. Use it for recent events or anything you are unsure about.".to_string(),
+            parameters: json!({
+                "type": "object",
+                "properties": {
+                    "query": { "type": "string", "description": "The search query (e.g., 'latest Rust release')" },
+                    "count": { "type": "integer", "description": "Number of results to return, from 1 to 20 (default 5)" },
+                },
+                "required": ["query"]
+            }),
+        }
+    }
+
+    async fn call(&self, args: Self::Args) -> Result<Self::Output, Self::Error> {
+        // Use the Brave Search API key from an environment variable
+        let api_key = env::var("BRAVE_API_KEY").map_err(|_| WebSearchError::MissingApiKey)?;
+
+        let count = args
+            .count
+            .unwrap_or(DEFAULT_RESULT_COUNT)
+            .clamp(1, MAX_RESULT_COUNT);
+
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| WebSearchError::HttpRequestFailed(e.to_string()))?;
+
+        let response = client
+            .get(BRAVE_SEARCH_URL)
+            .header("Accept", "application/json")
+            .header("X-Subscription-Token", api_key)
+            .query(&[("q", args.query.as_str()), ("count", &count.to_string())])
+            .send()
+            .await
+            .map_err(|e| WebSearchError::HttpRequestFailed(e.to_string()))?;
+
+        // Get the status code before consuming `response`
+        let status = response.status();
+
+        let text = read_body(response).await?;
+
+        if !status.is_success() {
+            return Err(WebSearchError::ApiError(format!(
+                "Status: {}, Response: {}",
+                status, text
+            )));
+        }
+
+        parse_results(&text, count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_results_takes_top_results() {
+        let text = r#"{
+            "type": "search",
+            "web": {
+                "results": [
+                    { "title": "Rust", "url": "https://www.rust-lang.org/", "description": "A language empowering everyone." },
+                    { "title": "Rig", "url": "https://rig.rs/" },
+                    { "title": "Cargo", "url": "https://doc.rust-lang.org/cargo/", "description": "The Rust package manager." }
+                ]
+            }
+        }"#;
+
+        assert_eq!(
+            parse_results(text, 2).unwrap(),
+            vec![
+                SearchResult {
+                    title: "Rust".into(),
+                    snippet: "A language empowering everyone.".into(),
+                    url: "https://www.rust-lang.org/".into(),
+                },
+                SearchResult {
+                    title: "Rig".into(),
+                    snippet: String::new(),
+                    url: "https://rig.rs/".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_results_without_web_section() {
+        assert_eq!(parse_results(r#"{"type": "search"}"#, 5).unwrap(), vec![]);
+        assert!(matches!(
+            parse_results("not json", 5),
+            Err(WebSearchError::InvalidResponse(_))
+        ));
+    }
+}
@@ -0,0 +1,322 @@
+// main.rs
+
+mod message_utils;
+mod rig_agent;
+mod whatsapp_api;
+
+use anyhow::{Context, Result};
+use axum::body::Bytes;
+use axum::extract::{Query, State};
+use axum::http::{HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use dotenv::dotenv;
+use hmac::{Hmac, Mac};
+use message_utils::{chunk_message, WHATSAPP_MESSAGE_LIMIT};
+use rig_agent::RigAgent;
+use serde::Deserialize;
+use sha2::Sha256;
+use std::env;
+use std::sync::Arc;
+use tracing::{debug, error, info, warn};
+use whatsapp_api::WhatsAppClient;
+
+/// Header Meta signs every webhook notification with
+const SIGNATURE_HEADER: &str = "x-hub-signature-256";
+
+/// The reply to anything other than a text message
+const TEXT_ONLY_MESSAGE: &str = "Sorry, I can only read text messages. Please type your question.";
+
+/// Shared by every request
+#[derive(Clone)]
+struct AppState {
+    whatsapp: Arc<WhatsAppClient>,
+    rig_agent: Arc<RigAgent>,
+    /// Chosen by you and entered in the app dashboard with the callback URL
+    /// (`WHATSAPP_VERIFY_TOKEN`)
+    verify_token: Arc<str>,
+    /// Meta app secret the notifications are signed with (`WHATSAPP_APP_SECRET`)
+    app_secret: Arc<str>,
+}
+
+/// Query of the verification request Meta sends when the callback URL is
+/// saved in the app dashboard
+#[derive(Deserialize)]
+struct VerifyParams {
+    #[serde(rename = "hub.mode")]
+    mode: Option<String>,
+    #[serde(rename = "hub.verify_token")]
+    verify_token: Option<String>,
+    #[serde(rename = "hub.challenge")]
+    challenge: Option<String>,
+}
+
+/// A webhook notification. Only the `messages` field of `value` is acted on;
+/// delivery and read receipts arrive as `statuses` and are ignored.
+#[derive(Deserialize)]
+struct Notification {
+    #[serde(default)]
+    entry: Vec<Entry>,
+}
+
+#[derive(Deserialize)]
+struct Entry {
+    #[serde(default)]
+    changes: Vec<Change>,
+}
+
+#[derive(Deserialize)]
+struct Change {
+    value: ChangeValue,
+}
+
+#[derive(Deserialize)]
+struct ChangeValue {
+    #[serde(default)]
+    messages: Vec<WhatsAppMessage>,
+}
+
+#[derive(Deserialize)]
+struct WhatsAppMessage {
+    id: String,
+    from: String,
+    #[serde(rename = "type")]
+    kind: String,
+    text: Option<TextBody>,
+}
+
+#[derive(Deserialize)]
+struct TextBody {
+    body: String,
+}
+
+/// A message a user sent the bot
+#[derive(Debug, PartialEq)]
+struct Incoming {
+    id: String,
+    from: String,
+    /// `None` for images, voice notes, stickers and other non-text messages
+    text: Option<String>,
+}
+
+impl Notification {
+    fn into_incoming(self) -> Vec<Incoming> {
+        self.entry
+            .into_iter()
+            .flat_map(|entry| entry.changes)
+            .flat_map(|change| change.value.messages)
+            .map(|message| Incoming {
+                text: match (message.kind.as_str(), message.text) {
+                    ("text", Some(text)) => Some(text.body),
+                    _ => None,
+                },
+                id: message.id,
+                from: message.from,
+            })
+            .collect()
+    }
+}
+
+/// The challenge to echo back if the verification request carries our verify
+/// token
+fn verification_challenge<'a>(params: &'a VerifyParams, verify_token: &str) -> Option<&'a str> {
+    if params.mode.as_deref() == Some("subscribe") && params.verify_token.as_deref() == Some(verify_token) {
+        params.challenge.as_deref()
+    } else {
+        None
+    }
+}
+
+/// Check the `sha256=<hex>` signature Meta computes over the raw body with the
+/// app secret. Without it, anyone who finds the URL could make the bot run
+/// (and pay for) completions and message arbitrary numbers.
+fn signature_is_valid(app_secret: &str, body: &[u8], signature: Option<&str>) -> bool {
+    let Some(expected) = signature
+        .and_then(|signature| signature.strip_prefix("sha256="))
+        .and_then(|hex_digest| hex::decode(hex_digest).ok())
+    else {
+        return false;
+    };
+    let mut mac = Hmac::<Sha256>::new_from_slice(app_secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    mac.verify_slice(&expected).is_ok()
+}
+
+/// GET /webhook: the verification handshake
+async fn verify(State(state): State<AppState>, Query(params): Query<VerifyParams>) -> Response {
+    match verification_challenge(&params, &state.verify_token) {
+        Some(challenge) => {
+            info!("Webhook verified");
+            challenge.to_string().into_response()
+        }
+        None => {
+            warn!("Rejected webhook verification with a wrong token");
+            StatusCode::FORBIDDEN.into_response()
+        }
+    }
+}
+
+/// POST /webhook: incoming messages and status updates
+async fn receive(State(state): State<AppState>, headers: HeaderMap, body: Bytes) -> StatusCode {
+    let signature = headers.get(SIGNATURE_HEADER).and_then(|value| value.to_str().ok());
+    if !signature_is_valid(&state.app_secret, &body, signature) {
+        warn!("Rejected a notification with a missing or invalid signature");
+        return StatusCode::UNAUTHORIZED;
+    }
+
+    let notification: Notification = match serde_json::from_slice(&body) {
+        Ok(notification) => notification,
+        Err(e) => {
+            warn!("Ignoring unrecognized notification: {}", e);
+            return StatusCode::BAD_REQUEST;
+        }
+    };
+
+    // Meta redelivers notifications that aren't answered with a 200 quickly,
+    // so answer in the background, the same way the Slack bot acknowledges
+    // events before calling the model
+    for message in notification.into_incoming() {
+        debug!("Message from {}: {:?}", message.from, message.text);
+        let state = state.clone();
+        tokio::spawn(async move {
+            if let Err(e) = answer(&state, message).await {
+                error!("Error answering message: {:?}", e);
+            }
+        });
+    }
+
+    StatusCode::OK
+}
+
+/// Mark the message as read, then send the answer, split into several
+/// messages if it is too long for one
+async fn answer(state: &AppState, message: Incoming) -> Result<()> {
+    if let Err(e) = state.whatsapp.mark_as_read(&message.id).await {
+        warn!("Could not mark message as read: {:?}", e);
+    }
+
+    let Some(text) = message.text else {
+        return state.whatsapp.send_text(&message.from, TEXT_ONLY_MESSAGE).await;
+    };
+
+    let response = match state.rig_agent.process_message(&text).await {
+        Ok(response) => response,
+        Err(e) => {
+            error!("Error processing message: {:?}", e);
+            format!("Error processing request: {}", e)
+        }
+    };
+
+    for chunk in chunk_message(&response, WHATSAPP_MESSAGE_LIMIT) {
+        state.whatsapp.send_text(&message.from, &chunk).await?;
+    }
+    Ok(())
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenv().ok();
+
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::DEBUG)
+        .init();
+
+    let access_token = env::var("WHATSAPP_ACCESS_TOKEN").context("WHATSAPP_ACCESS_TOKEN not set")?;
+    let phone_number_id = env::var("WHATSAPP_PHONE_NUMBER_ID").context("WHATSAPP_PHONE_NUMBER_ID not set")?;
+    let verify_token = env::var("WHATSAPP_VERIFY_TOKEN").context("WHATSAPP_VERIFY_TOKEN not set")?;
+    let app_secret = env::var("WHATSAPP_APP_SECRET").context("WHATSAPP_APP_SECRET not set")?;
+
+    let state = AppState {
+        whatsapp: Arc::new(WhatsAppClient::new(access_token, phone_number_id)),
+        rig_agent: Arc::new(RigAgent::new().await?),
+        verify_token: verify_token.into(),
+        app_secret: app_secret.into(),
+    };
+
+    let app = Router::new()
+        .route("/webhook", get(verify).post(receive))
+        .with_state(state);
+
+    let addr = env::var("BIND_ADDR").unwrap_or_else(|_| "127.0.0.1:3000".to_string());
+    let listener = tokio::net::TcpListener::bind(&addr)
+        .await
+        .with_context(|| format!("Failed to bind {}", addr))?;
+    info!("Listening on http://{}/webhook", addr);
+
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verification_and_signature() {
+        let params = VerifyParams {
+            mode: Some("subscribe".into()),
+            verify_token: Some("my-token".into()),
+            challenge: Some("1158201444".into()),
+        };
+        assert_eq!(verification_challenge(&params, "my-token"), Some("1158201444"));
+        assert_eq!(verification_challenge(&params, "other-token"), None);
+
+        let body = br#"{"entry":[]}"#;
+        let valid = {
+            let mut mac = Hmac::<Sha256>::new_from_slice(b"secret").unwrap();
+            mac.update(body);
+            format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+        };
+        assert!(signature_is_valid("secret", body, Some(&valid)));
+        assert!(!signature_is_valid("secret", br#"{"entry":[{}]}"#, Some(&valid)));
+        assert!(!signature_is_valid("other-secret", body, Some(&valid)));
+        assert!(!signature_is_valid("secret", body, Some(valid.trim_start_matches("sha256="))));
+        assert!(!signature_is_valid("secret", body, None));
+    }
+
+    #[test]
+    fn test_incoming_messages() {
+        let text = r#"{
+            "object": "whatsapp_business_account",
+            "entry": [{
+                "id": "102290129340398",
+                "changes": [{
+                    "field": "messages",
+                    "value": {
+                        "messaging_product": "whatsapp",
+                        "metadata": { "display_phone_number": "15550783881", "phone_number_id": "106540352242922" },
+                        "contacts": [{ "profile": { "name": "Sheena Nelson" }, "wa_id": "16505551234" }],
+                        "messages": [
+                            { "from": "16505551234", "id": "wamid.HBgLMTY1MDM4Nzk0MzkVAgASGBQzQTRBNjU5OUFFRTAzODEwMTQ0RgA=", "timestamp": "1749416383", "type": "text", "text": { "body": "Does Rig support Anthropic?" } },
+                            { "from": "16505551234", "id": "wamid.image", "timestamp": "1749416390", "type": "image", "image": { "mime_type": "image/jpeg", "id": "1003383421387256" } }
+                        ]
+                    }
+                }]
+            }]
+        }"#;
+
+        let notification: Notification = serde_json::from_str(text).unwrap();
+        assert_eq!(
+            notification.into_incoming(),
+            vec![
+                Incoming {
+                    id: "wamid.HBgLMTY1MDM4Nzk0MzkVAgASGBQzQTRBNjU5OUFFRTAzODEwMTQ0RgA=".into(),
+                    from: "16505551234".into(),
+                    text: Some("Does Rig support Anthropic?".into()),
+                },
+                Incoming {
+                    id: "wamid.image".into(),
+                    from: "16505551234".into(),
+                    text: None,
+                },
+            ]
+        );
+
+        // Delivery receipts carry `statuses` instead of `messages`
+        let text = r#"{"entry": [{"changes": [{"field": "messages", "value": {"statuses": [{"id": "wamid.1", "status": "delivered"}]}}]}]}"#;
+        let notification: Notification = serde_json::from_str(text).unwrap();
+        assert!(notification.into_incoming().is_empty());
+    }
+}
@@ -0,0 +1,117 @@
+// rig_agent.rs
+
+use anyhow::{Context, Result};
+use rig::providers::openai;
+use rig::vector_store::in_memory_store::InMemoryVectorStore;
+use rig::vector_store::VectorStore;
+use rig::embeddings::EmbeddingsBuilder;
+use rig::agent::Agent;
+use rig::completion::Prompt;
+use std::path::{Path, PathBuf};
+use std::fs;
+use std::sync::Arc;
+
+/// The knowledge base is shared with the Discord bot unless
+/// `RIG_DOCUMENTS_DIR` points somewhere else
+const DEFAULT_DOCUMENTS_DIR: &str = "../discord_rig_bot/documents";
+
+const PREAMBLE: &str = "You are an advanced AI assistant powered by Rig, a Rust library for building LLM applications. Your primary function is to provide accurate, helpful, and context-aware responses by leveraging both your general knowledge and specific information retrieved from a curated knowledge base.
+
+                    Key responsibilities and behaviors:
+                    1. Information Retrieval: You have access to a vast knowledge base. When answering questions, always consider the context provided by the retrieved information.
+                    2. Clarity and Conciseness: Provide clear and concise answers. Use bullet points or numbered lists for complex information when appropriate.
+                    3. Technical Proficiency: You have deep knowledge about Rig and its capabilities. When discussing Rig or answering related questions, provide detailed and technically accurate information.
+                    4. Code Examples: When appropriate, provide short Rust code examples to illustrate concepts, wrapped in triple backticks. WhatsApp shows them in a monospace font without highlighting, so keep them compact.
+                    5. Keep your responses short and concise. If the user needs more information, they can ask follow-up questions.
+                    ";
+
+pub struct RigAgent {
+    agent: Arc<Agent<openai::CompletionModel>>,
+}
+
+impl RigAgent {
+    pub async fn new() -> Result<Self> {
+        // Initialize OpenAI client
+        let api_key = std::env::var("OPENAI_API_KEY").context("OPENAI_API_KEY not set")?;
+        let openai_client = openai::Client::new(&api_key);
+        let embedding_model = openai_client.embedding_model(openai::TEXT_EMBEDDING_3_SMALL);
+
+        // Create vector store
+        let mut vector_store = InMemoryVectorStore::default();
+
+        // Load every markdown file under the documents directory
+        let documents_dir = std::env::var("RIG_DOCUMENTS_DIR")
+            .map(PathBuf::from)
+            .unwrap_or_else(|_| PathBuf::from(DEFAULT_DOCUMENTS_DIR));
+        let documents = Self::load_md_documents(&documents_dir)?;
+        if documents.is_empty() {
+            anyhow::bail!("No markdown documents found in {:?}", documents_dir);
+        }
+
+        // Create embeddings and add to vector store
+        let mut builder = EmbeddingsBuilder::new(embedding_model.clone());
+        for (id, content) in &documents {
+            builder = builder.simple_document(id, content);
+        }
+        let embeddings = builder.build().await?;
+
+        vector_store.add_documents(embeddings).await?;
+
+        // Create index
+        let index = vector_store.index(embedding_model);
+
+        // Create Agent
+        let agent = Arc::new(openai_client.agent(openai::GPT_4O)
+            .preamble(PREAMBLE)
+            .dynamic_context(2, index)
+            .build());
+
+        Ok(Self { agent })
+    }
+
+    fn load_md_content<P: AsRef<Path>>(file_path: P) -> Result<String> {
+        fs::read_to_string(file_path.as_ref())
+            .with_context(|| format!("Failed to read markdown file: {:?}", file_path.as_ref()))
+    }
+
+    /// Recursively collect every `.md` file under `dir` as `(id, content)`
+    /// pairs, where the id is the path relative to `dir` without the extension.
+    fn load_md_documents(dir: &Path) -> Result<Vec<(String, String)>> {
+        let mut paths = Vec::new();
+        Self::collect_md_paths(dir, &mut paths)?;
+        paths.sort();
+
+        paths
+            .into_iter()
+            .map(|path| {
+                let id = path
+                    .strip_prefix(dir)
+                    .unwrap_or(&path)
+                    .with_extension("")
+                    .to_string_lossy()
+                    .replace('\\', "/");
+                Ok((id, Self::load_md_content(&path)?))
+            })
+            .collect()
+    }
+
+    fn collect_md_paths(dir: &Path, paths: &mut Vec<PathBuf>) -> Result<()> {
+        let entries = fs::read_dir(dir)
+            .with_context(|| format!("Failed to read documents directory: {:?}", dir))?;
+
+        for entry in entries {
+            let path = entry?.path();
+            if path.is_dir() {
+                Self::collect_md_paths(&path, paths)?;
+            } else if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("md")) {
+                paths.push(path);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub async fn process_message(&self, message: &str) -> Result<String> {
+        self.agent.prompt(message).await.map_err(anyhow::Error::from)
+    }
+}
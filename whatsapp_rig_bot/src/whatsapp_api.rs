@@ -0,0 +1,72 @@
+// whatsapp_api.rs
+
+// The few WhatsApp Cloud API calls the bot needs, made directly with reqwest.
+// Both go to the `/messages` endpoint of the business phone number.
+
+use anyhow::{bail, Result};
+use serde_json::{json, Value};
+
+const GRAPH_API_URL: &str = "https://graph.facebook.com/v21.0";
+
+pub struct WhatsAppClient {
+    http_client: reqwest::Client,
+    /// System user or temporary access token with the
+    /// `whatsapp_business_messaging` permission
+    access_token: String,
+    /// Id of the business phone number the bot sends from (not the number
+    /// itself)
+    phone_number_id: String,
+}
+
+impl WhatsAppClient {
+    pub fn new(access_token: String, phone_number_id: String) -> Self {
+        Self {
+            http_client: reqwest::Client::new(),
+            access_token,
+            phone_number_id,
+        }
+    }
+
+    /// Send `text` to the WhatsApp user `to` (a phone number in international
+    /// format without the `+`, as received in `from`)
+    pub async fn send_text(&self, to: &str, text: &str) -> Result<()> {
+        self.post(json!({
+            "messaging_product": "whatsapp",
+            "recipient_type": "individual",
+            "to": to,
+            "type": "text",
+            "text": { "body": text, "preview_url": false },
+        }))
+        .await
+    }
+
+    /// Show the user's message as read (blue ticks), so they know it arrived
+    /// while the answer is generated
+    pub async fn mark_as_read(&self, message_id: &str) -> Result<()> {
+        self.post(json!({
+            "messaging_product": "whatsapp",
+            "status": "read",
+            "message_id": message_id,
+        }))
+        .await
+    }
+
+    async fn post(&self, body: Value) -> Result<()> {
+        let response = self
+            .http_client
+            .post(format!("{}/{}/messages", GRAPH_API_URL, self.phone_number_id))
+            .bearer_auth(&self.access_token)
+            .json(&body)
+            .send()
+            .await?;
+
+        // The Graph API explains failures (expired token, recipient outside
+        // the 24-hour window...) in the body of the error response
+        let status = response.status();
+        if !status.is_success() {
+            let text = response.text().await.unwrap_or_default();
+            bail!("WhatsApp API call failed with {}: {}", status, text);
+        }
+        Ok(())
+    }
+}